@@ -46,27 +46,30 @@ pub fn buffer_generic(generics: &syn::Generics) -> Option<BufferGeneric> {
         }
     };
     for param in generics.params.iter().filter_map(type_param) {
-        if let Some(_) = param
+        if param
             .bounds
             .iter()
             .filter_map(trait_bound)
             .find(buffer_bound("Ump"))
+            .is_some()
         {
             return Some(BufferGeneric::Ump(param.clone()));
         };
-        if let Some(_) = param
+        if param
             .bounds
             .iter()
             .filter_map(trait_bound)
             .find(buffer_bound("Bytes"))
+            .is_some()
         {
             return Some(BufferGeneric::Bytes(param.clone()));
         };
-        if let Some(_) = param
+        if param
             .bounds
             .iter()
             .filter_map(trait_bound)
             .find(buffer_bound("Buffer"))
+            .is_some()
         {
             return Some(BufferGeneric::UmpOrBytes(param.clone()));
         };
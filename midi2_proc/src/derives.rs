@@ -213,6 +213,57 @@ pub fn try_rebuffer_from(item: TokenStream1) -> TokenStream1 {
     .into()
 }
 
+pub fn try_from_mut_slice(item: TokenStream1) -> TokenStream1 {
+    let input = parse_macro_input!(item as ItemEnum);
+    let ident = &input.ident;
+    let (generic_unit, unit_type) = match common::buffer_generic(&input.generics)
+        .expect("No buffer generic found.")
+    {
+        common::BufferGeneric::Ump(_) => (TokenStream::new(), quote! { u32 }),
+        common::BufferGeneric::Bytes(_) => (TokenStream::new(), quote! { u8 }),
+        common::BufferGeneric::UmpOrBytes(_) => (quote! { U: crate::buffer::Unit }, quote! { U }),
+    };
+    let mut match_arms = TokenStream::new();
+    for variant in &input.variants {
+        let variant_ident = &variant.ident;
+        let message_path = message_path_from_variant(variant);
+        match_arms.extend(quote! {
+            #ident::#variant_ident(_) => #ident::#variant_ident(
+                <#message_path<&'a mut [#unit_type]> as core::convert::TryFrom<&'a mut [#unit_type]>>::try_from(buffer)?
+            ),
+        });
+    }
+    quote! {
+        impl<'a, #generic_unit> core::convert::TryFrom<&'a mut [#unit_type]> for #ident<&'a mut [#unit_type]> {
+            type Error = crate::error::Error;
+            fn try_from(buffer: &'a mut [#unit_type]) -> core::result::Result<Self, Self::Error> {
+                Ok(match <#ident<&[#unit_type]> as core::convert::TryFrom<&[#unit_type]>>::try_from(&*buffer)? {
+                    #match_arms
+                })
+            }
+        }
+    }
+    .into()
+}
+
+fn message_path_from_variant(variant: &syn::Variant) -> syn::Path {
+    let syn::Fields::Unnamed(fields) = &variant.fields else {
+        panic!("Expected enum variant with unnamed fields");
+    };
+    let Some(syn::Field { ty, .. }) = fields.unnamed.last() else {
+        panic!("Expected an unnamed field in the enum variant");
+    };
+    let syn::Type::Path(syn::TypePath { path, .. }) = ty else {
+        panic!("Expected a 'path' type");
+    };
+    let mut path = path.clone();
+    let Some(last_segment) = path.segments.last_mut() else {
+        panic!("Expected type to have an ident");
+    };
+    last_segment.arguments = syn::PathArguments::None;
+    path
+}
+
 fn message_type_from_variant(variant: &syn::Variant) -> TokenStream {
     let syn::Fields::Unnamed(fields) = &variant.fields else {
         panic!("Expected enum variant with unnamed fields");
@@ -243,12 +294,23 @@ pub fn grouped(item: TokenStream1) -> TokenStream1 {
     let mut match_arms_write = TokenStream::new();
     for variant in &input.variants {
         let variant_ident = &variant.ident;
-        match_arms_read.extend(quote! {
-            #variant_ident(m) => m.group(),
-        });
-        match_arms_write.extend(quote! {
-            #variant_ident(m) => m.set_group(group),
-        });
+        if variant_has_attr(variant, "optionally_grouped") {
+            // the variant only holds messages of the aggregate's grouped type
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalGrouped::group(m)
+                    .expect("Message of a grouped type has a group"),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalGrouped::set_group(m, group),
+            });
+        } else {
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => m.group(),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => m.set_group(group),
+            });
+        }
     }
     quote! {
         impl<B: crate::buffer::Ump> crate::traits::Grouped<B> for #ident<B> {
@@ -279,12 +341,23 @@ pub fn channeled(item: TokenStream1) -> TokenStream1 {
     let mut match_arms_write = TokenStream::new();
     for variant in &input.variants {
         let variant_ident = &variant.ident;
-        match_arms_read.extend(quote! {
-            #variant_ident(m) => m.channel(),
-        });
-        match_arms_write.extend(quote! {
-            #variant_ident(m) => m.set_channel(channel),
-        });
+        if variant_has_attr(variant, "optionally_channeled") {
+            // the variant only holds messages of the aggregate's channeled type
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalChanneled::channel(m)
+                    .expect("Message of a channeled type has a channel"),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalChanneled::set_channel(m, channel),
+            });
+        } else {
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => m.channel(),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => m.set_channel(channel),
+            });
+        }
     }
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let buffer_id = common::buffer_generic(&input.generics)
@@ -312,6 +385,120 @@ pub fn channeled(item: TokenStream1) -> TokenStream1 {
     .into()
 }
 
+fn variant_has_attr(variant: &syn::Variant, id: &str) -> bool {
+    variant.attrs.iter().any(|attr| attr.path().is_ident(id))
+}
+
+pub fn optional_grouped(item: TokenStream1) -> TokenStream1 {
+    let input = parse_macro_input!(item as ItemEnum);
+    let ident = &input.ident;
+    let mut match_arms_read = TokenStream::new();
+    let mut match_arms_write = TokenStream::new();
+    for variant in &input.variants {
+        let variant_ident = &variant.ident;
+        if variant_has_attr(variant, "grouped") {
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => Some(crate::traits::Grouped::group(m)),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => crate::traits::Grouped::set_group(m, group),
+            });
+        } else if variant_has_attr(variant, "optionally_grouped") {
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalGrouped::group(m),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalGrouped::set_group(m, group),
+            });
+        } else {
+            match_arms_read.extend(quote! {
+                #variant_ident(_) => None,
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(_) => {}
+            });
+        }
+    }
+    quote! {
+        impl<B: crate::buffer::Ump> crate::traits::OptionalGrouped<B> for #ident<B> {
+            fn group(&self) -> core::option::Option<crate::ux::u4> {
+                use #ident::*;
+                match self {
+                    #match_arms_read
+                }
+            }
+            #[allow(unused_variables)]
+            fn set_group(&mut self, group: crate::ux::u4)
+            where
+                B: crate::buffer::BufferMut
+            {
+                use #ident::*;
+                match self {
+                    #match_arms_write
+                }
+            }
+        }
+    }
+    .into()
+}
+
+pub fn optional_channeled(item: TokenStream1) -> TokenStream1 {
+    let input = parse_macro_input!(item as ItemEnum);
+    let ident = &input.ident;
+    let mut match_arms_read = TokenStream::new();
+    let mut match_arms_write = TokenStream::new();
+    for variant in &input.variants {
+        let variant_ident = &variant.ident;
+        if variant_has_attr(variant, "channeled") {
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => Some(crate::traits::Channeled::channel(m)),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => crate::traits::Channeled::set_channel(m, channel),
+            });
+        } else if variant_has_attr(variant, "optionally_channeled") {
+            match_arms_read.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalChanneled::channel(m),
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(m) => crate::traits::OptionalChanneled::set_channel(m, channel),
+            });
+        } else {
+            match_arms_read.extend(quote! {
+                #variant_ident(_) => None,
+            });
+            match_arms_write.extend(quote! {
+                #variant_ident(_) => {}
+            });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let buffer_id = common::buffer_generic(&input.generics)
+        .expect("Expected buffer generic")
+        .ident();
+    quote! {
+        impl #impl_generics crate::traits::OptionalChanneled<#buffer_id> for #ident #ty_generics #where_clause {
+            fn channel(&self) -> core::option::Option<crate::ux::u4> {
+                use #ident::*;
+                match self {
+                    #match_arms_read
+                }
+            }
+            #[allow(unused_variables)]
+            fn set_channel(&mut self, channel: crate::ux::u4)
+            where
+                #buffer_id: crate::buffer::BufferMut
+            {
+                use #ident::*;
+                match self {
+                    #match_arms_write
+                }
+            }
+        }
+    }
+    .into()
+}
+
 pub fn debug(item: TokenStream1) -> TokenStream1 {
     let input = parse_macro_input!(item as Item);
     let ident = match &input {
@@ -325,7 +512,7 @@ pub fn debug(item: TokenStream1) -> TokenStream1 {
         _ => panic!("Only enums and structs supported"),
     };
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let buffer_id = common::buffer_generic(&generics)
+    let buffer_id = common::buffer_generic(generics)
         .expect("Expected buffer generic")
         .ident();
     quote! {
@@ -11,6 +11,7 @@ struct Property {
     readonly: bool,
     writeonly: bool,
     resize: bool,
+    validate: bool,
     std: bool,
 }
 
@@ -24,6 +25,9 @@ impl Property {
     fn is_channel(&self) -> bool {
         self.ident == "channel"
     }
+    fn is_optional_channel(&self) -> bool {
+        self.ident == "optional_channel"
+    }
     fn is_sysex_payload(&self) -> bool {
         self.ident == "sysex_payload"
     }
@@ -37,7 +41,7 @@ fn has_attr(field: &syn::Field, id: &str) -> bool {
         path.segments
             .last()
             .iter()
-            .any(|&segment| segment.ident.to_string() == id)
+            .any(|&segment| segment.ident == id)
     })
 }
 
@@ -57,7 +61,7 @@ fn meta_type(field: &syn::Field) -> syn::Type {
                 .segments
                 .last()
                 .iter()
-                .any(|&segment| segment.ident.to_string() == "property")
+                .any(|&segment| segment.ident == "property")
         })
         .map(|list| {
             list.parse_args::<syn::Type>()
@@ -85,6 +89,7 @@ fn properties(input: &syn::ItemStruct) -> Vec<Property> {
             readonly: has_attr(field, "readonly"),
             writeonly: has_attr(field, "writeonly"),
             resize: has_attr(field, "resize"),
+            validate: has_attr(field, "validate"),
             std: has_attr(field, "std"),
         })
         .collect()
@@ -92,7 +97,7 @@ fn properties(input: &syn::ItemStruct) -> Vec<Property> {
 
 fn is_unit_tuple(ty: &syn::Type) -> bool {
     match ty {
-        syn::Type::Tuple(tup) => tup.elems.len() == 0,
+        syn::Type::Tuple(tup) => tup.elems.is_empty(),
         _ => false,
     }
 }
@@ -138,7 +143,7 @@ impl syn::parse::Parse for GenerateMessageArgs {
                 args.min_size_bytes = Some(parse_fixed_size(input));
             }
 
-            if let Err(_) = input.parse::<syn::Token![,]>() {
+            if input.parse::<syn::Token![,]>().is_err() {
                 assert!(input.is_empty());
                 break;
             }
@@ -203,7 +208,7 @@ fn generic_buffer_constraint(args: &GenerateMessageArgs) -> TokenStream {
 fn message(
     root_ident: &syn::Ident,
     args: &GenerateMessageArgs,
-    attributes: &Vec<syn::Attribute>,
+    attributes: &[syn::Attribute],
 ) -> TokenStream {
     let constraint = generic_buffer_constraint(args);
 
@@ -228,7 +233,7 @@ fn message(
 fn message_impl(
     root_ident: &syn::Ident,
     args: &GenerateMessageArgs,
-    properties: &Vec<Property>,
+    properties: &[Property],
 ) -> TokenStream {
     let constraint = generic_buffer_constraint(args);
 
@@ -285,9 +290,41 @@ fn property_setter(property: &Property, public: bool) -> TokenStream {
     };
     let std_only_attribute = std_only_attribute(property);
 
-    if property.resize {
+    let validate = if property.validate {
+        quote! {
+            <#meta_type as crate::detail::property::WriteProperty<B>>::validate(&value)?;
+        }
+    } else {
+        TokenStream::new()
+    };
+
+    if property.resize && property.validate {
+        let fallible_ident = syn::Ident::new(
+            format!("try_{}", ident).as_str(),
+            proc_macro2::Span::call_site(),
+        );
+        quote! {
+            #std_only_attribute
+            #pub_token fn #ident(&mut self, value: #ty) -> crate::result::Result<()>
+            where B: crate::buffer::BufferMut + crate::buffer::BufferResize {
+                #validate
+                <#meta_type as crate::detail::property::ResizeProperty<B>>::resize(self.buffer_access_mut(), &value);
+                <#meta_type as crate::detail::property::WriteProperty<B>>::write(self.buffer_access_mut(), value);
+                Ok(())
+            }
+
+            #std_only_attribute
+            #pub_token fn #fallible_ident(&mut self, value: #ty) -> crate::result::Result<()>
+            where B: crate::buffer::BufferMut + crate::buffer::BufferTryResize {
+                #validate
+                <#meta_type as crate::detail::property::ResizeProperty<B>>::try_resize(self.buffer_access_mut(), &value)?;
+                <#meta_type as crate::detail::property::WriteProperty<B>>::write(self.buffer_access_mut(), value);
+                Ok(())
+            }
+        }
+    } else if property.resize {
         let fallible_ident = syn::Ident::new(
-            format!("try_{}", ident.to_string()).as_str(),
+            format!("try_{}", ident).as_str(),
             proc_macro2::Span::call_site(),
         );
         quote! {
@@ -305,6 +342,16 @@ fn property_setter(property: &Property, public: bool) -> TokenStream {
                 Ok(())
             }
         }
+    } else if property.validate {
+        quote! {
+            #std_only_attribute
+            #pub_token fn #ident(&mut self, value: #ty) -> crate::result::Result<()>
+            where B: crate::buffer::BufferMut {
+                #validate
+                <#meta_type as crate::detail::property::WriteProperty<B>>::write(self.buffer_access_mut(), value);
+                Ok(())
+            }
+        }
     } else {
         quote! {
             #std_only_attribute
@@ -388,7 +435,7 @@ fn data_impl(root_ident: &syn::Ident, args: &GenerateMessageArgs) -> TokenStream
 fn try_from_slice_impl(
     root_ident: &syn::Ident,
     args: &GenerateMessageArgs,
-    properties: &Vec<Property>,
+    properties: &[Property],
 ) -> TokenStream {
     let mut validation_steps = TokenStream::new();
     let generic_unit = match args.representation() {
@@ -420,6 +467,14 @@ fn try_from_slice_impl(
                 Ok(#root_ident(buffer))
             }
         }
+
+        impl<'a, #generic_unit> core::convert::TryFrom<&'a mut [#unit_type]> for #root_ident<&'a mut [#unit_type]> {
+            type Error = crate::error::Error;
+            fn try_from(buffer: &'a mut [#unit_type]) -> core::result::Result<Self, Self::Error> {
+                <#root_ident<&[#unit_type]> as core::convert::TryFrom<&[#unit_type]>>::try_from(&*buffer)?;
+                Ok(#root_ident(buffer))
+            }
+        }
     }
 }
 
@@ -475,7 +530,7 @@ fn try_rebuffer_from_impl(root_ident: &syn::Ident, args: &GenerateMessageArgs) -
 fn new_impl(
     root_ident: &syn::Ident,
     args: &GenerateMessageArgs,
-    properties: &Vec<Property>,
+    properties: &[Property],
 ) -> TokenStream {
     let constraint = generic_buffer_constraint(args);
     let initialise_properties = initialise_property_statements(properties, quote! {B});
@@ -501,7 +556,7 @@ fn new_impl(
 fn new_array_impl(
     root_ident: &syn::Ident,
     args: &GenerateMessageArgs,
-    properties: &Vec<Property>,
+    properties: &[Property],
 ) -> TokenStream {
     let generics = match args.representation() {
         Representation::UmpOrBytes => quote! { , U: crate::buffer::Unit },
@@ -535,7 +590,7 @@ fn new_array_impl(
 fn try_new_impl(
     root_ident: &syn::Ident,
     args: &GenerateMessageArgs,
-    properties: &Vec<Property>,
+    properties: &[Property],
 ) -> TokenStream {
     let constraint = generic_buffer_constraint(args);
     let initialise_properties = initialise_property_statements(properties, quote! {B});
@@ -570,7 +625,7 @@ fn clone_impl(root_ident: &syn::Ident, args: &GenerateMessageArgs) -> TokenStrea
 }
 
 fn initialise_property_statements(
-    properties: &Vec<Property>,
+    properties: &[Property],
     buffer_type: TokenStream,
 ) -> TokenStream {
     let mut initialise_properties = TokenStream::new();
@@ -616,7 +671,32 @@ fn channeled_impl(
     }
 }
 
-fn from_bytes_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
+fn optionally_channeled_impl(
+    root_ident: &syn::Ident,
+    property: &Property,
+    args: &GenerateMessageArgs,
+) -> TokenStream {
+    let meta_type = &property.meta_type;
+    let constraint = generic_buffer_constraint(args);
+    quote! {
+        impl<B: #constraint> crate::traits::OptionalChanneled<B> for #root_ident<B> {
+            fn channel(&self) -> core::option::Option<crate::ux::u4> {
+                <#meta_type as crate::detail::property::ReadProperty<B>>::read(self.buffer_access())
+            }
+            fn set_channel(&mut self, channel: crate::ux::u4)
+            where
+                B: crate::buffer::BufferMut,
+            {
+                <#meta_type as crate::detail::property::WriteProperty<B>>::write(
+                    self.buffer_access_mut(),
+                    Some(channel),
+                );
+            }
+        }
+    }
+}
+
+fn from_bytes_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
     let convert_properties = convert_properties(properties, &quote! { B });
     quote! {
         impl<
@@ -637,7 +717,7 @@ fn from_bytes_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> Token
     }
 }
 
-fn from_bytes_array_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
+fn from_bytes_array_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
     let array_type = quote! { [u32; SIZE] };
     let convert_properties = convert_properties(properties, &array_type);
     quote! {
@@ -653,7 +733,7 @@ fn from_bytes_array_impl(root_ident: &syn::Ident, properties: &Vec<Property>) ->
     }
 }
 
-fn try_from_bytes_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
+fn try_from_bytes_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
     let convert_properties = convert_properties(properties, &quote! { B });
     quote! {
         impl<
@@ -674,7 +754,7 @@ fn try_from_bytes_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> T
     }
 }
 
-fn convert_properties(properties: &Vec<Property>, target_buffer_type: &TokenStream) -> TokenStream {
+fn convert_properties(properties: &[Property], target_buffer_type: &TokenStream) -> TokenStream {
     let mut convert_properties = TokenStream::new();
     for property in properties.iter().filter(|p| !p.readonly && !p.writeonly) {
         let std_only_attribute = std_only_attribute(property);
@@ -691,7 +771,7 @@ fn convert_properties(properties: &Vec<Property>, target_buffer_type: &TokenStre
     convert_properties
 }
 
-fn from_ump_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
+fn from_ump_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
     let convert_properties = convert_properties(properties, &quote! { B });
     quote! {
         impl<
@@ -712,7 +792,7 @@ fn from_ump_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenSt
     }
 }
 
-fn from_ump_array_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
+fn from_ump_array_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
     let array_type = quote! { [u8; SIZE] };
     let convert_properties = convert_properties(properties, &array_type);
     quote! {
@@ -728,7 +808,7 @@ fn from_ump_array_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> T
     }
 }
 
-fn try_from_ump_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
+fn try_from_ump_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
     let convert_properties = convert_properties(properties, &quote! { B });
     quote! {
         impl<
@@ -817,6 +897,9 @@ pub fn generate_message(attrs: TokenStream1, item: TokenStream1) -> TokenStream1
     if let Some(property) = properties.iter().find(|p| p.is_channel()) {
         tokens.extend(channeled_impl(root_ident, property, &args));
     }
+    if let Some(property) = properties.iter().find(|p| p.is_optional_channel()) {
+        tokens.extend(optionally_channeled_impl(root_ident, property, &args));
+    }
     if let Representation::UmpOrBytes = args.representation() {
         // we skip generating conversion for sysex7
         // these traits are implemented manually
@@ -834,11 +917,11 @@ pub fn generate_message(attrs: TokenStream1, item: TokenStream1) -> TokenStream1
     }
     if let Some(via_type) = args.via.as_ref() {
         match args.representation() {
-            Representation::Ump => tokens.extend(ump_message_via(root_ident, &via_type)),
-            Representation::Bytes => tokens.extend(bytes_message_via(root_ident, &via_type)),
+            Representation::Ump => tokens.extend(ump_message_via(root_ident, via_type)),
+            Representation::Bytes => tokens.extend(bytes_message_via(root_ident, via_type)),
             Representation::UmpOrBytes => {
-                tokens.extend(ump_message_via(root_ident, &via_type));
-                tokens.extend(bytes_message_via(root_ident, &via_type));
+                tokens.extend(ump_message_via(root_ident, via_type));
+                tokens.extend(bytes_message_via(root_ident, via_type));
             }
         }
     }
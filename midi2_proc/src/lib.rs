@@ -14,16 +14,26 @@ pub fn derive_data(item: TokenStream1) -> TokenStream1 {
     derives::data(item)
 }
 
-#[proc_macro_derive(Grouped)]
+#[proc_macro_derive(Grouped, attributes(optionally_grouped))]
 pub fn derive_grouped(item: TokenStream1) -> TokenStream1 {
     derives::grouped(item)
 }
 
-#[proc_macro_derive(Channeled)]
+#[proc_macro_derive(Channeled, attributes(optionally_channeled))]
 pub fn derive_channeled(item: TokenStream1) -> TokenStream1 {
     derives::channeled(item)
 }
 
+#[proc_macro_derive(OptionalGrouped, attributes(grouped, optionally_grouped))]
+pub fn derive_optional_grouped(item: TokenStream1) -> TokenStream1 {
+    derives::optional_grouped(item)
+}
+
+#[proc_macro_derive(OptionalChanneled, attributes(channeled, optionally_channeled))]
+pub fn derive_optional_channeled(item: TokenStream1) -> TokenStream1 {
+    derives::optional_channeled(item)
+}
+
 #[proc_macro_derive(Debug)]
 pub fn derive_ump_debug(item: TokenStream1) -> TokenStream1 {
     derives::debug(item)
@@ -58,3 +68,8 @@ pub fn derive_rebuffer_from(item: TokenStream1) -> TokenStream1 {
 pub fn derive_try_rebuffer_from(item: TokenStream1) -> TokenStream1 {
     derives::try_rebuffer_from(item)
 }
+
+#[proc_macro_derive(TryFromMutSlice)]
+pub fn derive_try_from_mut_slice(item: TokenStream1) -> TokenStream1 {
+    derives::try_from_mut_slice(item)
+}
@@ -44,6 +44,8 @@
 //! ```
 //! `Vec<U>` implements [BufferMut] and [BufferResize].
 //! Messages backed with with such buffers have the most powerful api.
+//! It also implements [BufferTryResize], which fails only when
+//! memory can't be allocated.
 //!
 //! ```rust
 //! use midi2::prelude::*;
@@ -117,21 +119,21 @@ pub trait Bytes: Buffer<Unit = u8> {}
 
 impl<B: Buffer<Unit = u8>> Bytes for B {}
 
-impl<'a, U: Unit> Buffer for &'a [U] {
+impl<U: Unit> Buffer for &[U] {
     type Unit = U;
     fn buffer(&self) -> &[Self::Unit] {
         self
     }
 }
 
-impl<'a, U: Unit> Buffer for &'a mut [U] {
+impl<U: Unit> Buffer for &mut [U] {
     type Unit = U;
     fn buffer(&self) -> &[Self::Unit] {
         self
     }
 }
 
-impl<'a, U: Unit> BufferMut for &'a mut [U] {
+impl<U: Unit> BufferMut for &mut [U] {
     fn buffer_mut(&mut self) -> &mut [<Self as Buffer>::Unit] {
         self
     }
@@ -188,6 +190,16 @@ impl<U: Unit> BufferResize for std::vec::Vec<U> {
     }
 }
 
+#[cfg(any(feature = "std", test))]
+impl<U: Unit> BufferTryResize for std::vec::Vec<U> {
+    fn try_resize(&mut self, size: usize) -> Result<(), BufferOverflow> {
+        self.try_reserve(size.saturating_sub(self.len()))
+            .map_err(|_| BufferOverflow)?;
+        self.resize(size, U::zero());
+        Ok(())
+    }
+}
+
 #[cfg(any(feature = "std", test))]
 impl<U: Unit> BufferDefault for std::vec::Vec<U> {
     fn default() -> Self {
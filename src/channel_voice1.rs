@@ -29,6 +29,7 @@ pub(crate) const UMP_MESSAGE_TYPE: u8 = 0x2;
     midi2_proc::TryFromUmp,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -47,7 +48,7 @@ pub enum ChannelVoice1<B: crate::buffer::Buffer> {
 impl<'a, U: crate::buffer::Unit> core::convert::TryFrom<&'a [U]> for ChannelVoice1<&'a [U]> {
     type Error = crate::error::Error;
     fn try_from(buffer: &'a [U]) -> Result<Self, Self::Error> {
-        if buffer.len() < 1 {
+        if buffer.is_empty() {
             return Err(crate::error::Error::InvalidData("Slice is too short"));
         };
         Ok(match status(buffer) {
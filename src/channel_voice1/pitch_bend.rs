@@ -28,6 +28,21 @@ struct PitchBend {
     bend: crate::ux::u14,
 }
 
+impl<B: crate::buffer::Buffer> PitchBend<B> {
+    /// The bend upscaled to its MIDI 2.0 resolution.
+    pub fn bend_u32(&self) -> u32 {
+        crate::scale::u14_to_u32(self.bend())
+    }
+
+    /// Set the bend from a MIDI 2.0 resolution value.
+    pub fn set_bend_u32(&mut self, bend: u32)
+    where
+        B: crate::buffer::BufferMut,
+    {
+        self.set_bend(crate::scale::u32_to_u14(bend));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,4 +86,21 @@ mod tests {
             u14::new(0x147)
         );
     }
+
+    #[test]
+    fn bend_u32() {
+        assert_eq!(
+            PitchBend::try_from(&[0xE0_u8, 0x00, 0x40][..])
+                .unwrap()
+                .bend_u32(),
+            0x8000_0000,
+        );
+    }
+
+    #[test]
+    fn set_bend_u32() {
+        let mut message = PitchBend::<[u32; 4]>::new();
+        message.set_bend_u32(0xFFFF_FFFF);
+        assert_eq!(message.bend(), u14::new(0x3FFF));
+    }
 }
@@ -43,6 +43,7 @@ pub(crate) const UMP_MESSAGE_TYPE: u8 = 0x4;
     midi2_proc::Grouped,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -64,12 +65,16 @@ pub enum ChannelVoice2<B: crate::buffer::Ump> {
     RegisteredPerNoteController(registered_per_note_controller::RegisteredPerNoteController<B>),
     RelativeAssignableController(relative_assignable_controller::RelativeAssignableController<B>),
     RelativeRegisteredController(relative_registered_controller::RelativeRegisteredController<B>),
+    #[cfg(feature = "unknown")]
+    #[optionally_grouped]
+    #[optionally_channeled]
+    Unknown(crate::UnknownUmp<B>),
 }
 
 impl<'a> TryFrom<&'a [u32]> for ChannelVoice2<&'a [u32]> {
     type Error = crate::error::Error;
     fn try_from(buffer: &'a [u32]) -> Result<Self, Self::Error> {
-        if buffer.len() < 1 {
+        if buffer.is_empty() {
             return Err(crate::error::Error::InvalidData("Slice is too short"));
         };
 
@@ -113,6 +118,9 @@ impl<'a> TryFrom<&'a [u32]> for ChannelVoice2<&'a [u32]> {
                 relative_registered_controller::RelativeRegisteredController::try_from(buffer)?
                     .into()
             }
+            #[cfg(feature = "unknown")]
+            _ => crate::UnknownUmp::try_from(buffer)?.into(),
+            #[cfg(not(feature = "unknown"))]
             _ => Err(crate::error::Error::InvalidData(
                 "Unknown midi2 channel voice status",
             ))?,
@@ -125,6 +133,21 @@ mod test {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[cfg(feature = "unknown")]
+    #[test]
+    fn unknown_status() {
+        use crate::traits::Channeled;
+
+        let message = ChannelVoice2::try_from(&[0x4B7C_0000, 0x1234_5678][..]).unwrap();
+        assert_eq!(
+            message,
+            ChannelVoice2::Unknown(
+                crate::UnknownUmp::try_from(&[0x4B7C_0000, 0x1234_5678][..]).unwrap()
+            ),
+        );
+        assert_eq!(message.channel(), crate::ux::u4::new(0xC));
+    }
+
     #[test]
     fn channel() {
         use crate::traits::Channeled;
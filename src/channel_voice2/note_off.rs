@@ -27,6 +27,21 @@ struct NoteOff {
     attribute: Option<Attribute>,
 }
 
+impl<B: crate::buffer::Ump> NoteOff<B> {
+    /// The velocity downscaled to its MIDI 1.0 resolution.
+    pub fn velocity_u7(&self) -> u7 {
+        crate::scale::u16_to_u7(self.velocity())
+    }
+
+    /// Set the velocity from a MIDI 1.0 resolution value.
+    pub fn set_velocity_u7(&mut self, velocity: u7)
+    where
+        B: crate::buffer::BufferMut,
+    {
+        self.set_velocity(crate::scale::u7_to_u16(velocity));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +103,21 @@ mod tests {
             Some(Attribute::ManufacturerSpecific(0xCC6E)),
         );
     }
+
+    #[test]
+    fn velocity_u7() {
+        assert_eq!(
+            NoteOff::try_from(&[0x4888_5E03, 0x6A14_E98A][..])
+                .unwrap()
+                .velocity_u7(),
+            u7::new(0x35),
+        );
+    }
+
+    #[test]
+    fn set_velocity_u7() {
+        let mut message = NoteOff::<[u32; 4]>::new();
+        message.set_velocity_u7(u7::new(0x7F));
+        assert_eq!(message.velocity(), 0xFFFF);
+    }
 }
@@ -47,6 +47,21 @@ struct NoteOn {
     attribute: Option<Attribute>,
 }
 
+impl<B: crate::buffer::Ump> NoteOn<B> {
+    /// The velocity downscaled to its MIDI 1.0 resolution.
+    pub fn velocity_u7(&self) -> u7 {
+        crate::scale::u16_to_u7(self.velocity())
+    }
+
+    /// Set the velocity from a MIDI 1.0 resolution value.
+    pub fn set_velocity_u7(&mut self, velocity: u7)
+    where
+        B: crate::buffer::BufferMut,
+    {
+        self.set_velocity(crate::scale::u7_to_u16(velocity));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +130,21 @@ mod tests {
             }),
         );
     }
+
+    #[test]
+    fn velocity_u7() {
+        assert_eq!(
+            NoteOn::try_from(&[0x4898_5E03, 0x6A14_E98A][..])
+                .unwrap()
+                .velocity_u7(),
+            u7::new(0x35),
+        );
+    }
+
+    #[test]
+    fn set_velocity_u7() {
+        let mut message = NoteOn::<[u32; 4]>::new();
+        message.set_velocity_u7(u7::new(0x7F));
+        assert_eq!(message.velocity(), 0xFFFF);
+    }
 }
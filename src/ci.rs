@@ -1 +1,966 @@
 #![doc = include_str!("ci/README.md")]
+
+use crate::{
+    buffer::{
+        Buffer, BufferMut, BufferResize, BufferTryResize, SpecialiseU32, SpecialiseU8, Unit,
+        UnitPrivate, UNIT_ID_U8,
+    },
+    detail::{
+        property::{Property, ReadProperty, ResizeProperty, WriteProperty},
+        Encode7Bit,
+    },
+    sysex7,
+    ux::{u14, u28, u4, u7},
+};
+
+mod ack;
+mod discovery;
+mod end_of_midi_message_report;
+mod endpoint_inquiry;
+mod get_property_data;
+mod invalidate_muid;
+mod midi_message_report;
+mod nak;
+mod notify;
+mod process_inquiry;
+mod process_inquiry_capabilities;
+mod profile_added_report;
+mod profile_details_inquiry;
+mod profile_disabled_report;
+mod profile_enabled_report;
+mod profile_id;
+mod profile_inquiry;
+mod profile_removed_report;
+mod profile_specific_data;
+mod property_exchange;
+mod property_exchange_assembler;
+mod property_exchange_capabilities;
+mod property_exchange_splitter;
+mod reply_to_discovery;
+mod reply_to_endpoint_inquiry;
+mod reply_to_get_property_data;
+mod reply_to_midi_message_report;
+mod reply_to_process_inquiry_capabilities;
+mod reply_to_profile_details_inquiry;
+mod reply_to_profile_inquiry;
+mod reply_to_property_exchange_capabilities;
+mod reply_to_set_property_data;
+mod reply_to_subscription;
+mod set_profile_off;
+mod set_profile_on;
+mod set_property_data;
+mod state_machine;
+mod subscription;
+
+pub use ack::*;
+pub use discovery::*;
+pub use end_of_midi_message_report::*;
+pub use endpoint_inquiry::*;
+pub use get_property_data::*;
+pub use invalidate_muid::*;
+pub use midi_message_report::*;
+pub use nak::*;
+pub use notify::*;
+pub use process_inquiry::*;
+pub use process_inquiry_capabilities::*;
+pub use profile_added_report::*;
+pub use profile_details_inquiry::*;
+pub use profile_disabled_report::*;
+pub use profile_enabled_report::*;
+pub use profile_id::*;
+pub use profile_inquiry::*;
+pub use profile_removed_report::*;
+pub use profile_specific_data::*;
+pub use property_exchange_assembler::*;
+pub use property_exchange_capabilities::*;
+pub use property_exchange_splitter::*;
+pub use reply_to_discovery::*;
+pub use reply_to_endpoint_inquiry::*;
+pub use reply_to_get_property_data::*;
+pub use reply_to_midi_message_report::*;
+pub use reply_to_process_inquiry_capabilities::*;
+pub use reply_to_profile_details_inquiry::*;
+pub use reply_to_profile_inquiry::*;
+pub use reply_to_property_exchange_capabilities::*;
+pub use reply_to_set_property_data::*;
+pub use reply_to_subscription::*;
+pub use set_profile_off::*;
+pub use set_profile_on::*;
+pub use set_property_data::*;
+pub use state_machine::*;
+pub use subscription::*;
+
+/// The MUID which addresses every MIDI-CI device.
+pub const BROADCAST_MUID: u28 = u28::new(0x0FFF_FFFF);
+
+/// The MIDI-CI message version written by the messages in this module.
+pub const VERSION: u7 = u7::new(0x02);
+
+const UNIVERSAL_SYSEX: u8 = 0x7E;
+const UNIVERSAL_SYSEX_SUB_ID_MIDI_CI: u8 = 0x0D;
+
+const DEVICE_ID_GROUP: u8 = 0x7E;
+const DEVICE_ID_FUNCTION_BLOCK: u8 = 0x7F;
+
+const DEVICE_ID_INDEX: usize = 1;
+const SUB_ID_2_INDEX: usize = 3;
+const VERSION_INDEX: usize = 4;
+const SOURCE_MUID_INDEX: usize = 5;
+const DESTINATION_MUID_INDEX: usize = 9;
+
+const ERR_NOT_CI: &str = "Not a MIDI-CI message";
+const ERR_INCORRECT_SUB_ID_2: &str = "Incorrect MIDI-CI message type";
+const ERR_PAYLOAD_TOO_SHORT: &str = "Payload too short for MIDI-CI message";
+const ERR_INVALID_DEVICE_ID: &str = "Invalid MIDI-CI device id";
+const ERR_DATA_OVERRUN: &str = "MIDI-CI data length exceeds the message size";
+pub(crate) const ERR_DATA_TOO_LONG: &str = "MIDI-CI data is too long for its length field";
+pub(crate) const ERR_NOT_7_BIT: &str = "MIDI-CI data must only contain 7 bit bytes";
+
+/// The destination of a MIDI-CI message within the receiving device.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeviceId {
+    /// A single MIDI channel.
+    Channel(u4),
+    /// The whole group carrying the message.
+    Group,
+    /// The whole function block.
+    #[default]
+    FunctionBlock,
+}
+
+#[derive(
+    derive_more::From,
+    midi2_proc::Data,
+    midi2_proc::Grouped,
+    midi2_proc::RebufferFrom,
+    midi2_proc::TryRebufferFrom,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+#[non_exhaustive]
+pub enum Ci<B: Buffer> {
+    Ack(ack::Ack<B>),
+    Discovery(discovery::Discovery<B>),
+    EndOfMidiMessageReport(end_of_midi_message_report::EndOfMidiMessageReport<B>),
+    EndpointInquiry(endpoint_inquiry::EndpointInquiry<B>),
+    GetPropertyData(get_property_data::GetPropertyData<B>),
+    InvalidateMuid(invalidate_muid::InvalidateMuid<B>),
+    MidiMessageReport(midi_message_report::MidiMessageReport<B>),
+    Nak(nak::Nak<B>),
+    Notify(notify::Notify<B>),
+    ProcessInquiryCapabilities(process_inquiry_capabilities::ProcessInquiryCapabilities<B>),
+    ProfileAddedReport(profile_added_report::ProfileAddedReport<B>),
+    ProfileDetailsInquiry(profile_details_inquiry::ProfileDetailsInquiry<B>),
+    ProfileDisabledReport(profile_disabled_report::ProfileDisabledReport<B>),
+    ProfileEnabledReport(profile_enabled_report::ProfileEnabledReport<B>),
+    ProfileInquiry(profile_inquiry::ProfileInquiry<B>),
+    ProfileRemovedReport(profile_removed_report::ProfileRemovedReport<B>),
+    ProfileSpecificData(profile_specific_data::ProfileSpecificData<B>),
+    PropertyExchangeCapabilities(property_exchange_capabilities::PropertyExchangeCapabilities<B>),
+    ReplyToDiscovery(reply_to_discovery::ReplyToDiscovery<B>),
+    ReplyToEndpointInquiry(reply_to_endpoint_inquiry::ReplyToEndpointInquiry<B>),
+    ReplyToGetPropertyData(reply_to_get_property_data::ReplyToGetPropertyData<B>),
+    ReplyToMidiMessageReport(reply_to_midi_message_report::ReplyToMidiMessageReport<B>),
+    ReplyToProcessInquiryCapabilities(
+        reply_to_process_inquiry_capabilities::ReplyToProcessInquiryCapabilities<B>,
+    ),
+    ReplyToProfileDetailsInquiry(reply_to_profile_details_inquiry::ReplyToProfileDetailsInquiry<B>),
+    ReplyToProfileInquiry(reply_to_profile_inquiry::ReplyToProfileInquiry<B>),
+    ReplyToPropertyExchangeCapabilities(
+        reply_to_property_exchange_capabilities::ReplyToPropertyExchangeCapabilities<B>,
+    ),
+    ReplyToSetPropertyData(reply_to_set_property_data::ReplyToSetPropertyData<B>),
+    ReplyToSubscription(reply_to_subscription::ReplyToSubscription<B>),
+    SetProfileOff(set_profile_off::SetProfileOff<B>),
+    SetProfileOn(set_profile_on::SetProfileOn<B>),
+    SetPropertyData(set_property_data::SetPropertyData<B>),
+    Subscription(subscription::Subscription<B>),
+}
+
+impl<'a, U: Unit> TryFrom<&'a [U]> for Ci<&'a [U]> {
+    type Error = crate::error::Error;
+    fn try_from(buffer: &'a [U]) -> Result<Self, Self::Error> {
+        use crate::traits::{Size, Sysex};
+
+        let sysex = sysex7::Sysex7::try_from(buffer)?;
+        let mut payload = sysex.payload();
+        if payload.len() <= SUB_ID_2_INDEX {
+            return Err(crate::error::Error::InvalidData(ERR_PAYLOAD_TOO_SHORT));
+        }
+        let header = [
+            payload.next().unwrap(),
+            payload.next().unwrap(),
+            payload.next().unwrap(),
+            payload.next().unwrap(),
+        ];
+        if u8::from(header[0]) != UNIVERSAL_SYSEX
+            || u8::from(header[2]) != UNIVERSAL_SYSEX_SUB_ID_MIDI_CI
+        {
+            return Err(crate::error::Error::InvalidData(ERR_NOT_CI));
+        }
+
+        let buffer = &buffer[..sysex.size()];
+        Ok(match u8::from(header[SUB_ID_2_INDEX]) {
+            ack::SUB_ID_2 => Ci::Ack(Ack::try_from(buffer)?),
+            discovery::SUB_ID_2 => Ci::Discovery(Discovery::try_from(buffer)?),
+            end_of_midi_message_report::SUB_ID_2 => {
+                Ci::EndOfMidiMessageReport(EndOfMidiMessageReport::try_from(buffer)?)
+            }
+            endpoint_inquiry::SUB_ID_2 => Ci::EndpointInquiry(EndpointInquiry::try_from(buffer)?),
+            get_property_data::SUB_ID_2 => Ci::GetPropertyData(GetPropertyData::try_from(buffer)?),
+            invalidate_muid::SUB_ID_2 => Ci::InvalidateMuid(InvalidateMuid::try_from(buffer)?),
+            midi_message_report::SUB_ID_2 => {
+                Ci::MidiMessageReport(MidiMessageReport::try_from(buffer)?)
+            }
+            nak::SUB_ID_2 => Ci::Nak(Nak::try_from(buffer)?),
+            notify::SUB_ID_2 => Ci::Notify(Notify::try_from(buffer)?),
+            process_inquiry_capabilities::SUB_ID_2 => {
+                Ci::ProcessInquiryCapabilities(ProcessInquiryCapabilities::try_from(buffer)?)
+            }
+            profile_added_report::SUB_ID_2 => {
+                Ci::ProfileAddedReport(ProfileAddedReport::try_from(buffer)?)
+            }
+            profile_details_inquiry::SUB_ID_2 => {
+                Ci::ProfileDetailsInquiry(ProfileDetailsInquiry::try_from(buffer)?)
+            }
+            profile_disabled_report::SUB_ID_2 => {
+                Ci::ProfileDisabledReport(ProfileDisabledReport::try_from(buffer)?)
+            }
+            profile_enabled_report::SUB_ID_2 => {
+                Ci::ProfileEnabledReport(ProfileEnabledReport::try_from(buffer)?)
+            }
+            profile_inquiry::SUB_ID_2 => Ci::ProfileInquiry(ProfileInquiry::try_from(buffer)?),
+            profile_removed_report::SUB_ID_2 => {
+                Ci::ProfileRemovedReport(ProfileRemovedReport::try_from(buffer)?)
+            }
+            profile_specific_data::SUB_ID_2 => {
+                Ci::ProfileSpecificData(ProfileSpecificData::try_from(buffer)?)
+            }
+            property_exchange_capabilities::SUB_ID_2 => {
+                Ci::PropertyExchangeCapabilities(PropertyExchangeCapabilities::try_from(buffer)?)
+            }
+            reply_to_discovery::SUB_ID_2 => {
+                Ci::ReplyToDiscovery(ReplyToDiscovery::try_from(buffer)?)
+            }
+            reply_to_endpoint_inquiry::SUB_ID_2 => {
+                Ci::ReplyToEndpointInquiry(ReplyToEndpointInquiry::try_from(buffer)?)
+            }
+            reply_to_get_property_data::SUB_ID_2 => {
+                Ci::ReplyToGetPropertyData(ReplyToGetPropertyData::try_from(buffer)?)
+            }
+            reply_to_midi_message_report::SUB_ID_2 => {
+                Ci::ReplyToMidiMessageReport(ReplyToMidiMessageReport::try_from(buffer)?)
+            }
+            reply_to_process_inquiry_capabilities::SUB_ID_2 => {
+                Ci::ReplyToProcessInquiryCapabilities(ReplyToProcessInquiryCapabilities::try_from(
+                    buffer,
+                )?)
+            }
+            reply_to_profile_details_inquiry::SUB_ID_2 => {
+                Ci::ReplyToProfileDetailsInquiry(ReplyToProfileDetailsInquiry::try_from(buffer)?)
+            }
+            reply_to_profile_inquiry::SUB_ID_2 => {
+                Ci::ReplyToProfileInquiry(ReplyToProfileInquiry::try_from(buffer)?)
+            }
+            reply_to_property_exchange_capabilities::SUB_ID_2 => {
+                Ci::ReplyToPropertyExchangeCapabilities(
+                    ReplyToPropertyExchangeCapabilities::try_from(buffer)?,
+                )
+            }
+            reply_to_set_property_data::SUB_ID_2 => {
+                Ci::ReplyToSetPropertyData(ReplyToSetPropertyData::try_from(buffer)?)
+            }
+            reply_to_subscription::SUB_ID_2 => {
+                Ci::ReplyToSubscription(ReplyToSubscription::try_from(buffer)?)
+            }
+            set_profile_off::SUB_ID_2 => Ci::SetProfileOff(SetProfileOff::try_from(buffer)?),
+            set_profile_on::SUB_ID_2 => Ci::SetProfileOn(SetProfileOn::try_from(buffer)?),
+            set_property_data::SUB_ID_2 => Ci::SetPropertyData(SetPropertyData::try_from(buffer)?),
+            subscription::SUB_ID_2 => Ci::Subscription(Subscription::try_from(buffer)?),
+            _ => Err(crate::error::Error::InvalidData(ERR_INCORRECT_SUB_ID_2))?,
+        })
+    }
+}
+
+// ***********************************************************************
+// helpers
+
+// the payload of the sysex message held in the buffer
+fn payload<B: Buffer>(buffer: &B) -> sysex7::PayloadIterator<'_, B::Unit> {
+    sysex7::payload(&buffer.buffer()[..sysex7::size(buffer)])
+}
+
+fn read_u7s<const N: usize, B: Buffer>(buffer: &B, index: usize) -> [u7; N] {
+    let mut ret = [u7::default(); N];
+    for (r, v) in ret.iter_mut().zip(payload(buffer).skip(index)) {
+        *r = v;
+    }
+    ret
+}
+
+fn write_u7s<B: Buffer + BufferMut>(buffer: &mut B, index: usize, values: &[u7]) {
+    for (i, v) in values.iter().enumerate() {
+        sysex7::write_datum(buffer, *v, index + i);
+    }
+}
+
+// write 8 bit data into the payload.
+// NOTE: the caller must ensure the data passes validate_bytes
+fn write_bytes<B: Buffer + BufferMut>(buffer: &mut B, index: usize, values: &[u8]) {
+    for (i, v) in values.iter().enumerate() {
+        sysex7::write_datum(buffer, u7::new(*v), index + i);
+    }
+}
+
+pub(crate) fn validate_bytes(values: &[u8]) -> crate::result::Result<()> {
+    if values.iter().any(|v| v & 0x80 != 0) {
+        return Err(crate::error::Error::InvalidData(ERR_NOT_7_BIT));
+    }
+    Ok(())
+}
+
+// move a run of data within the payload.
+// NOTE: the caller must ensure the buffer is large enough
+fn move_data<B: Buffer + BufferMut>(buffer: &mut B, from: usize, to: usize, len: usize) {
+    let mut copy = |i: usize| {
+        let datum = sysex7::read_datum(buffer, from + i);
+        sysex7::write_datum(buffer, datum, to + i);
+    };
+    if to > from {
+        (0..len).rev().for_each(&mut copy);
+    } else {
+        (0..len).for_each(&mut copy);
+    }
+}
+
+// resize a variable length field which is followed by more data.
+// the data following the field is moved to the new end of the field.
+fn resize_field<
+    B: Buffer + BufferMut,
+    ResizePayload: Fn(&mut B, usize) -> Result<(), crate::error::BufferOverflow>,
+>(
+    buffer: &mut B,
+    end: usize,
+    new_end: usize,
+    resize_payload: ResizePayload,
+) -> Result<(), crate::error::BufferOverflow> {
+    let tail_size = payload(buffer).len() - end;
+    if new_end > end {
+        resize_payload(buffer, new_end + tail_size)?;
+        move_data(buffer, end, new_end, tail_size);
+    } else {
+        move_data(buffer, end, new_end, tail_size);
+        resize_payload(buffer, new_end + tail_size)?;
+    }
+    Ok(())
+}
+
+// the size of the buffer needed to hold a sysex message with the given payload size
+fn buffer_size<U: Unit>(payload_size: usize) -> usize {
+    if <U as UnitPrivate>::UNIT_ID == UNIT_ID_U8 {
+        payload_size + 2
+    } else {
+        sysex7::buffer_size_from_payload_size_ump(payload_size)
+    }
+}
+
+// ***********************************************************************
+// properties
+
+// Lays out the sysex message and writes the universal sysex header
+// common to every MIDI-CI message.
+pub(crate) struct CiProperty<const SUB_ID_2: u8, const PAYLOAD_SIZE: usize>;
+
+impl<const SUB_ID_2: u8, const PAYLOAD_SIZE: usize, B: Buffer> Property<B>
+    for CiProperty<SUB_ID_2, PAYLOAD_SIZE>
+{
+    type Type = ();
+}
+
+impl<'a, const SUB_ID_2: u8, const PAYLOAD_SIZE: usize, B: Buffer> ReadProperty<'a, B>
+    for CiProperty<SUB_ID_2, PAYLOAD_SIZE>
+{
+    fn read(_buffer: &'a B) -> Self::Type {}
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        <sysex7::Sysex7BytesBeginByte as ReadProperty<B>>::validate(buffer)?;
+        <sysex7::Sysex7BytesEndByte as ReadProperty<B>>::validate(buffer)?;
+        <sysex7::ConsistentStatuses as ReadProperty<B>>::validate(buffer)?;
+        <sysex7::ValidPacketSizes as ReadProperty<B>>::validate(buffer)?;
+
+        if payload(buffer).len() < PAYLOAD_SIZE {
+            return Err(crate::error::Error::InvalidData(ERR_PAYLOAD_TOO_SHORT));
+        }
+        let header = read_u7s::<4, B>(buffer, 0);
+        if u8::from(header[0]) != UNIVERSAL_SYSEX
+            || u8::from(header[2]) != UNIVERSAL_SYSEX_SUB_ID_MIDI_CI
+        {
+            return Err(crate::error::Error::InvalidData(ERR_NOT_CI));
+        }
+        if u8::from(header[SUB_ID_2_INDEX]) != SUB_ID_2 {
+            return Err(crate::error::Error::InvalidData(ERR_INCORRECT_SUB_ID_2));
+        }
+        Ok(())
+    }
+}
+
+impl<const SUB_ID_2: u8, const PAYLOAD_SIZE: usize, B: Buffer + BufferMut> WriteProperty<B>
+    for CiProperty<SUB_ID_2, PAYLOAD_SIZE>
+{
+    fn write(buffer: &mut B, _v: Self::Type) {
+        match <B::Unit as UnitPrivate>::UNIT_ID {
+            UNIT_ID_U8 => {
+                let bytes = buffer.specialise_u8_mut();
+                bytes[0] = 0xF0;
+                bytes[PAYLOAD_SIZE + 1] = 0xF7;
+            }
+            _ => {
+                let size = sysex7::buffer_size_from_payload_size_ump(PAYLOAD_SIZE);
+                sysex7::write_ump_packet_headers(
+                    &mut buffer.specialise_u32_mut()[..size],
+                    PAYLOAD_SIZE,
+                );
+            }
+        }
+        write_u7s(
+            buffer,
+            0,
+            &[
+                u7::new(UNIVERSAL_SYSEX),
+                u7::default(),
+                u7::new(UNIVERSAL_SYSEX_SUB_ID_MIDI_CI),
+                u7::new(SUB_ID_2),
+            ],
+        );
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {}
+}
+
+pub(crate) struct DeviceIdProperty;
+
+impl<B: Buffer> Property<B> for DeviceIdProperty {
+    type Type = DeviceId;
+}
+
+impl<'a, B: Buffer> ReadProperty<'a, B> for DeviceIdProperty {
+    fn read(buffer: &'a B) -> Self::Type {
+        match u8::from(read_u7s::<1, B>(buffer, DEVICE_ID_INDEX)[0]) {
+            v @ 0x0..=0xF => DeviceId::Channel(u4::new(v)),
+            DEVICE_ID_GROUP => DeviceId::Group,
+            _ => DeviceId::FunctionBlock,
+        }
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        match u8::from(read_u7s::<1, B>(buffer, DEVICE_ID_INDEX)[0]) {
+            0x0..=0xF | DEVICE_ID_GROUP | DEVICE_ID_FUNCTION_BLOCK => Ok(()),
+            _ => Err(crate::error::Error::InvalidData(ERR_INVALID_DEVICE_ID)),
+        }
+    }
+}
+
+impl<B: Buffer + BufferMut> WriteProperty<B> for DeviceIdProperty {
+    fn write(buffer: &mut B, v: Self::Type) {
+        let v = match v {
+            DeviceId::Channel(channel) => u8::from(channel),
+            DeviceId::Group => DEVICE_ID_GROUP,
+            DeviceId::FunctionBlock => DEVICE_ID_FUNCTION_BLOCK,
+        };
+        sysex7::write_datum(buffer, u7::new(v), DEVICE_ID_INDEX);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+pub(crate) struct VersionProperty;
+
+impl<B: Buffer> Property<B> for VersionProperty {
+    type Type = u7;
+}
+
+impl<'a, B: Buffer> ReadProperty<'a, B> for VersionProperty {
+    fn read(buffer: &'a B) -> Self::Type {
+        read_u7s::<1, B>(buffer, VERSION_INDEX)[0]
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<B: Buffer + BufferMut> WriteProperty<B> for VersionProperty {
+    fn write(buffer: &mut B, v: Self::Type) {
+        sysex7::write_datum(buffer, v, VERSION_INDEX);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        VERSION
+    }
+}
+
+pub(crate) type SourceMuidProperty = U28Property<SOURCE_MUID_INDEX>;
+
+pub(crate) struct DestinationMuidProperty;
+
+impl<B: Buffer> Property<B> for DestinationMuidProperty {
+    type Type = u28;
+}
+
+impl<'a, B: Buffer> ReadProperty<'a, B> for DestinationMuidProperty {
+    fn read(buffer: &'a B) -> Self::Type {
+        <U28Property<DESTINATION_MUID_INDEX> as ReadProperty<B>>::read(buffer)
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<B: Buffer + BufferMut> WriteProperty<B> for DestinationMuidProperty {
+    fn write(buffer: &mut B, v: Self::Type) {
+        <U28Property<DESTINATION_MUID_INDEX> as WriteProperty<B>>::write(buffer, v);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        BROADCAST_MUID
+    }
+}
+
+pub(crate) struct U7Property<const INDEX: usize>;
+
+impl<const INDEX: usize, B: Buffer> Property<B> for U7Property<INDEX> {
+    type Type = u7;
+}
+
+impl<'a, const INDEX: usize, B: Buffer> ReadProperty<'a, B> for U7Property<INDEX> {
+    fn read(buffer: &'a B) -> Self::Type {
+        read_u7s::<1, B>(buffer, INDEX)[0]
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const INDEX: usize, B: Buffer + BufferMut> WriteProperty<B> for U7Property<INDEX> {
+    fn write(buffer: &mut B, v: Self::Type) {
+        sysex7::write_datum(buffer, v, INDEX);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+pub(crate) struct U14Property<const INDEX: usize>;
+
+impl<const INDEX: usize, B: Buffer> Property<B> for U14Property<INDEX> {
+    type Type = u14;
+}
+
+impl<'a, const INDEX: usize, B: Buffer> ReadProperty<'a, B> for U14Property<INDEX> {
+    fn read(buffer: &'a B) -> Self::Type {
+        u14::from_u7s(&read_u7s::<2, B>(buffer, INDEX))
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const INDEX: usize, B: Buffer + BufferMut> WriteProperty<B> for U14Property<INDEX> {
+    fn write(buffer: &mut B, v: Self::Type) {
+        let mut data = [u7::default(); 2];
+        v.to_u7s(&mut data);
+        write_u7s(buffer, INDEX, &data);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+pub(crate) struct U28Property<const INDEX: usize>;
+
+impl<const INDEX: usize, B: Buffer> Property<B> for U28Property<INDEX> {
+    type Type = u28;
+}
+
+impl<'a, const INDEX: usize, B: Buffer> ReadProperty<'a, B> for U28Property<INDEX> {
+    fn read(buffer: &'a B) -> Self::Type {
+        u28::from_u7s(&read_u7s::<4, B>(buffer, INDEX))
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const INDEX: usize, B: Buffer + BufferMut> WriteProperty<B> for U28Property<INDEX> {
+    fn write(buffer: &mut B, v: Self::Type) {
+        let mut data = [u7::default(); 4];
+        v.to_u7s(&mut data);
+        write_u7s(buffer, INDEX, &data);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+pub(crate) struct U7ArrayProperty<const INDEX: usize, const N: usize>;
+
+impl<const INDEX: usize, const N: usize, B: Buffer> Property<B> for U7ArrayProperty<INDEX, N> {
+    type Type = [u7; N];
+}
+
+impl<'a, const INDEX: usize, const N: usize, B: Buffer> ReadProperty<'a, B>
+    for U7ArrayProperty<INDEX, N>
+{
+    fn read(buffer: &'a B) -> Self::Type {
+        read_u7s(buffer, INDEX)
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const INDEX: usize, const N: usize, B: Buffer + BufferMut> WriteProperty<B>
+    for U7ArrayProperty<INDEX, N>
+{
+    fn write(buffer: &mut B, v: Self::Type) {
+        write_u7s(buffer, INDEX, &v);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        [u7::default(); N]
+    }
+}
+
+pub(crate) struct BitProperty<const INDEX: usize, const BIT: u8>;
+
+impl<const INDEX: usize, const BIT: u8, B: Buffer> Property<B> for BitProperty<INDEX, BIT> {
+    type Type = bool;
+}
+
+impl<'a, const INDEX: usize, const BIT: u8, B: Buffer> ReadProperty<'a, B>
+    for BitProperty<INDEX, BIT>
+{
+    fn read(buffer: &'a B) -> Self::Type {
+        u8::from(read_u7s::<1, B>(buffer, INDEX)[0]) & (1 << BIT) != 0
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const INDEX: usize, const BIT: u8, B: Buffer + BufferMut> WriteProperty<B>
+    for BitProperty<INDEX, BIT>
+{
+    fn write(buffer: &mut B, v: Self::Type) {
+        let mut data = u8::from(read_u7s::<1, B>(buffer, INDEX)[0]);
+        if v {
+            data |= 1 << BIT;
+        } else {
+            data &= !(1 << BIT);
+        }
+        sysex7::write_datum(buffer, u7::new(data), INDEX);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        false
+    }
+}
+
+// Variable length data preceded by its length, which is either
+// two or four 7 bit bytes long. The data makes up the tail of a message.
+
+fn read_length<B: Buffer>(buffer: &B, index: usize, size: usize) -> usize {
+    match size {
+        2 => u16::from(u14::from_u7s(&read_u7s::<2, B>(buffer, index))) as usize,
+        4 => u32::from(u28::from_u7s(&read_u7s::<4, B>(buffer, index))) as usize,
+        _ => unreachable!(),
+    }
+}
+
+pub(crate) fn validate_length(size: usize, length: usize) -> crate::result::Result<()> {
+    let max = match size {
+        2 => u16::from(u14::MAX) as usize,
+        4 => u32::from(u28::MAX) as usize,
+        _ => unreachable!(),
+    };
+    if length > max {
+        return Err(crate::error::Error::InvalidData(ERR_DATA_TOO_LONG));
+    }
+    Ok(())
+}
+
+// NOTE: the caller must ensure the length passes validate_length
+fn write_length<B: Buffer + BufferMut>(buffer: &mut B, index: usize, size: usize, length: usize) {
+    let mut data = [u7::default(); 4];
+    match size {
+        2 => u14::new(length as u16).to_u7s(&mut data[..2]),
+        4 => u28::new(length as u32).to_u7s(&mut data[..]),
+        _ => unreachable!(),
+    }
+    write_u7s(buffer, index, &data[..size]);
+}
+
+// resize the message to fit the given payload size
+fn resize_payload<B: Buffer + BufferMut + BufferResize>(
+    buffer: &mut B,
+    payload_size: usize,
+) -> Result<(), crate::error::BufferOverflow> {
+    sysex7::resize(buffer, payload_size);
+    Ok(())
+}
+
+// fallible version of the above.
+// a failed resize leaves the message untouched.
+fn try_resize_payload<B: Buffer + BufferMut + BufferTryResize>(
+    buffer: &mut B,
+    payload_size: usize,
+) -> Result<(), crate::error::BufferOverflow> {
+    let size = buffer_size::<B::Unit>(payload_size);
+    if size > buffer.buffer().len() {
+        buffer.try_resize(size)?;
+    }
+    sysex7::try_resize(buffer, payload_size).map_err(|_| crate::error::BufferOverflow)
+}
+
+pub(crate) struct DataWriteProperty<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize>(
+    core::marker::PhantomData<&'a u7>,
+);
+
+impl<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize, B: Buffer> Property<B>
+    for DataWriteProperty<'a, LENGTH_INDEX, LENGTH_SIZE>
+{
+    type Type = &'a [u7];
+}
+
+impl<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize, B: Buffer + BufferMut>
+    WriteProperty<B> for DataWriteProperty<'a, LENGTH_INDEX, LENGTH_SIZE>
+{
+    fn write(buffer: &mut B, data: Self::Type) {
+        write_length(buffer, LENGTH_INDEX, LENGTH_SIZE, data.len());
+        write_u7s(buffer, LENGTH_INDEX + LENGTH_SIZE, data);
+    }
+    fn validate(v: &Self::Type) -> crate::result::Result<()> {
+        validate_length(LENGTH_SIZE, v.len())
+    }
+    fn default() -> Self::Type {
+        &[]
+    }
+}
+
+impl<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize, B: Buffer + BufferMut>
+    ResizeProperty<B> for DataWriteProperty<'a, LENGTH_INDEX, LENGTH_SIZE>
+{
+    fn resize(buffer: &mut B, value: &Self::Type)
+    where
+        B: BufferResize,
+    {
+        sysex7::resize(buffer, LENGTH_INDEX + LENGTH_SIZE + value.len());
+    }
+
+    fn try_resize(buffer: &mut B, value: &Self::Type) -> Result<(), crate::error::BufferOverflow>
+    where
+        B: BufferTryResize,
+    {
+        try_resize_payload(buffer, LENGTH_INDEX + LENGTH_SIZE + value.len())
+    }
+}
+
+pub(crate) struct TextWriteProperty<'a, const LENGTH_INDEX: usize>(
+    core::marker::PhantomData<&'a str>,
+);
+
+impl<'a, const LENGTH_INDEX: usize, B: Buffer> Property<B> for TextWriteProperty<'a, LENGTH_INDEX> {
+    type Type = &'a str;
+}
+
+impl<'a, const LENGTH_INDEX: usize, B: Buffer + BufferMut> WriteProperty<B>
+    for TextWriteProperty<'a, LENGTH_INDEX>
+{
+    fn write(buffer: &mut B, text: Self::Type) {
+        write_length(buffer, LENGTH_INDEX, 2, text.len());
+        write_bytes(buffer, LENGTH_INDEX + 2, text.as_bytes());
+    }
+    fn validate(v: &Self::Type) -> crate::result::Result<()> {
+        validate_length(2, v.len())?;
+        validate_bytes(v.as_bytes())
+    }
+    fn default() -> Self::Type {
+        ""
+    }
+}
+
+impl<'a, const LENGTH_INDEX: usize, B: Buffer + BufferMut> ResizeProperty<B>
+    for TextWriteProperty<'a, LENGTH_INDEX>
+{
+    fn resize(buffer: &mut B, value: &Self::Type)
+    where
+        B: BufferResize,
+    {
+        sysex7::resize(buffer, LENGTH_INDEX + 2 + value.len());
+    }
+
+    fn try_resize(buffer: &mut B, value: &Self::Type) -> Result<(), crate::error::BufferOverflow>
+    where
+        B: BufferTryResize,
+    {
+        try_resize_payload(buffer, LENGTH_INDEX + 2 + value.len())
+    }
+}
+
+pub(crate) struct DataReadProperty<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize>(
+    core::marker::PhantomData<&'a u7>,
+);
+
+impl<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize, B: 'a + Buffer> Property<B>
+    for DataReadProperty<'a, LENGTH_INDEX, LENGTH_SIZE>
+{
+    type Type = DataIterator<'a, B::Unit>;
+}
+
+impl<'a, const LENGTH_INDEX: usize, const LENGTH_SIZE: usize, B: 'a + Buffer> ReadProperty<'a, B>
+    for DataReadProperty<'a, LENGTH_INDEX, LENGTH_SIZE>
+{
+    fn read(buffer: &'a B) -> Self::Type {
+        let length = read_length(buffer, LENGTH_INDEX, LENGTH_SIZE);
+        DataIterator(
+            payload(buffer)
+                .skip(LENGTH_INDEX + LENGTH_SIZE)
+                .take(length),
+        )
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        let length = read_length(buffer, LENGTH_INDEX, LENGTH_SIZE);
+        if payload(buffer).len() < LENGTH_INDEX + LENGTH_SIZE + length {
+            return Err(crate::error::Error::InvalidData(ERR_DATA_OVERRUN));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+pub(crate) struct TextReadStringProperty<const LENGTH_INDEX: usize>;
+
+#[cfg(feature = "std")]
+impl<const LENGTH_INDEX: usize, B: Buffer> Property<B> for TextReadStringProperty<LENGTH_INDEX> {
+    type Type = std::string::String;
+}
+
+#[cfg(feature = "std")]
+impl<'a, const LENGTH_INDEX: usize, B: Buffer> ReadProperty<'a, B>
+    for TextReadStringProperty<LENGTH_INDEX>
+{
+    fn read(buffer: &'a B) -> Self::Type {
+        <DataReadProperty<LENGTH_INDEX, 2> as ReadProperty<B>>::read(buffer)
+            .map(|b| char::from(u8::from(b)))
+            .collect()
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        <DataReadProperty<LENGTH_INDEX, 2> as ReadProperty<B>>::validate(buffer)
+    }
+}
+
+/// An iterator over the variable length data carried by a MIDI-CI message.
+#[derive(Debug, Clone)]
+pub struct DataIterator<'a, U: Unit>(
+    core::iter::Take<core::iter::Skip<sysex7::PayloadIterator<'a, U>>>,
+);
+
+impl<'a, U: Unit> core::iter::Iterator for DataIterator<'a, U> {
+    type Item = u7;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<'a, U: Unit> core::iter::FusedIterator for DataIterator<'a, U> {}
+
+impl<'a, U: Unit> core::iter::ExactSizeIterator for DataIterator<'a, U> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn try_from_bytes() {
+        let buffer = [
+            0xF0_u8, 0x7E, 0x7F, 0x0D, 0x7E, 0x02, 0x01, 0x02, 0x03, 0x04, 0x7F, 0x7F, 0x7F, 0x7F,
+            0x05, 0x06, 0x07, 0x08, 0xF7,
+        ];
+        assert_eq!(
+            Ci::try_from(&buffer[..]),
+            Ok(Ci::InvalidateMuid(
+                InvalidateMuid::try_from(&buffer[..]).unwrap()
+            )),
+        );
+    }
+
+    #[test]
+    fn try_from_ump() {
+        let buffer = [
+            0x3016_7E7F_u32,
+            0x0D7E_0201,
+            0x3026_0203,
+            0x047F_7F7F,
+            0x3035_7F05,
+            0x0607_0800,
+        ];
+        assert_eq!(
+            Ci::try_from(&buffer[..]),
+            Ok(Ci::InvalidateMuid(
+                InvalidateMuid::try_from(&buffer[..]).unwrap()
+            )),
+        );
+    }
+
+    #[test]
+    fn try_from_not_ci() {
+        assert_eq!(
+            Ci::try_from(&[0xF0_u8, 0x7E, 0x7F, 0x0C, 0x7E, 0x02, 0xF7][..]),
+            Err(crate::error::Error::InvalidData(ERR_NOT_CI)),
+        );
+    }
+
+    #[test]
+    fn try_from_unknown_sub_id_2() {
+        assert_eq!(
+            Ci::try_from(&[0xF0_u8, 0x7E, 0x7F, 0x0D, 0x10, 0x02, 0xF7][..]),
+            Err(crate::error::Error::InvalidData(ERR_INCORRECT_SUB_ID_2)),
+        );
+    }
+
+    #[test]
+    fn try_from_too_short() {
+        assert_eq!(
+            Ci::try_from(&[0xF0_u8, 0x7E, 0x7F, 0xF7][..]),
+            Err(crate::error::Error::InvalidData(ERR_PAYLOAD_TOO_SHORT)),
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x7D;
+const PAYLOAD_SIZE: usize = 23;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(25))]
+/// Acknowledges a MIDI-CI inquiry.
+struct Ack {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7Property<13>)]
+    original_sub_id_2: u7,
+    #[property(ci::U7Property<14>)]
+    status_code: u7,
+    #[property(ci::U7Property<15>)]
+    status_data: u7,
+    #[property(ci::U7ArrayProperty<16, 5>)]
+    details: [u7; 5],
+    #[property(ci::TextWriteProperty<21>)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    message_text: &str,
+    #[property(ci::DataReadProperty<21, 2>)]
+    #[readonly]
+    message_text_bytes: ci::DataIterator<'_, B::Unit>,
+    #[property(ci::TextReadStringProperty<21>)]
+    #[readonly]
+    #[std]
+    message_text: std::string::String,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for Ack<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 29] = [
+        0xF0, 0x7E, 0x7E, 0x0D, 0x7D, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x34,
+        0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x04, 0x00, 0x4F, 0x6B, 0x61, 0x79, 0xF7,
+    ];
+
+    const UMP: [u32; 10] = [
+        0x3316_7E7E,
+        0x0D7D_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B34,
+        0x0000_0102,
+        0x3326_0304,
+        0x0504_004F,
+        0x3333_6B61,
+        0x7900_0000,
+    ];
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferResize>(
+        message: &mut Ack<B>,
+    ) {
+        message.set_device_id(ci::DeviceId::Group);
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_original_sub_id_2(u7::new(0x34));
+        message.set_details([
+            u7::new(0x1),
+            u7::new(0x2),
+            u7::new(0x3),
+            u7::new(0x4),
+            u7::new(0x5),
+        ]);
+        message.set_message_text("Okay").unwrap();
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            Ack::<std::vec::Vec<u8>>::new(),
+            Ack(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7D, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            Ack::<std::vec::Vec<u32>>::new(),
+            Ack(std::vec![
+                0x3016_7E7F,
+                0x0D7D_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3026_7F00,
+                0x0000_0000,
+                0x3035_0000,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = Ack::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, Ack(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = Ack::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, Ack(UMP.to_vec()));
+    }
+
+    #[test]
+    fn device_id() {
+        assert_eq!(
+            Ack::try_from(&BYTES[..]).unwrap().device_id(),
+            ci::DeviceId::Group,
+        );
+    }
+
+    #[test]
+    fn original_sub_id_2() {
+        assert_eq!(
+            Ack::try_from(&UMP[..]).unwrap().original_sub_id_2(),
+            u7::new(0x34),
+        );
+    }
+
+    #[test]
+    fn details() {
+        assert_eq!(
+            Ack::try_from(&UMP[..]).unwrap().details(),
+            [
+                u7::new(0x1),
+                u7::new(0x2),
+                u7::new(0x3),
+                u7::new(0x4),
+                u7::new(0x5),
+            ],
+        );
+    }
+
+    #[test]
+    fn message_text_bytes() {
+        assert_eq!(
+            Ack::try_from(&BYTES[..])
+                .unwrap()
+                .message_text_bytes()
+                .map(u8::from)
+                .collect::<std::vec::Vec<_>>(),
+            b"Okay".to_vec(),
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn message_text() {
+        assert_eq!(Ack::try_from(&UMP[..]).unwrap().message_text(), "Okay");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn empty_message_text() {
+        assert_eq!(Ack::<std::vec::Vec<u8>>::new().message_text(), "");
+    }
+
+    #[test]
+    fn non_7_bit_message_text() {
+        let mut message = Ack::<std::vec::Vec<u8>>::new();
+        assert_eq!(
+            message.set_message_text("café"),
+            Err(crate::error::Error::InvalidData(ci::ERR_NOT_7_BIT)),
+        );
+        assert_eq!(message, Ack::<std::vec::Vec<u8>>::new());
+    }
+
+    #[test]
+    fn message_text_too_long() {
+        let mut message = Ack::<std::vec::Vec<u8>>::new();
+        let text = "a".repeat(0x4000);
+        assert_eq!(
+            message.try_set_message_text(&text),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_TOO_LONG)),
+        );
+        assert_eq!(message, Ack::<std::vec::Vec<u8>>::new());
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x70;
+const PAYLOAD_SIZE: usize = 30;
+
+#[midi2_proc::generate_message(MinSizeUmp(10), MinSizeBytes(32))]
+/// Sent by an initiator to discover the MIDI-CI devices it is connected to.
+struct Discovery {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7ArrayProperty<13, 3>)]
+    device_manufacturer: [u7; 3],
+    #[property(ci::U14Property<16>)]
+    device_family: u14,
+    #[property(ci::U14Property<18>)]
+    device_family_model_number: u14,
+    #[property(ci::U7ArrayProperty<20, 4>)]
+    software_version: [u7; 4],
+    #[property(ci::BitProperty<24, 1>)]
+    supports_protocol_negotiation: bool,
+    #[property(ci::BitProperty<24, 2>)]
+    supports_profile_configuration: bool,
+    #[property(ci::BitProperty<24, 3>)]
+    supports_property_exchange: bool,
+    #[property(ci::BitProperty<24, 4>)]
+    supports_process_inquiry: bool,
+    #[property(ci::U28Property<25>)]
+    max_sysex_size: u28,
+    #[property(ci::U7Property<29>)]
+    output_path_id: u7,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for Discovery<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{Data, Grouped};
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 32] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x70, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x7F, 0x7F, 0x7F, 0x7F, 0x01,
+        0x02, 0x03, 0x34, 0x24, 0x67, 0x0A, 0x01, 0x02, 0x03, 0x04, 0x0C, 0x00, 0x04, 0x00, 0x00,
+        0x05, 0xF7,
+    ];
+
+    const UMP: [u32; 10] = [
+        0x3416_7E7F,
+        0x0D70_0267,
+        0x3426_0A0D,
+        0x097F_7F7F,
+        0x3426_7F01,
+        0x0203_3424,
+        0x3426_670A,
+        0x0102_0304,
+        0x3436_0C00,
+        0x0400_0005,
+    ];
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            Discovery::<std::vec::Vec<u8>>::new(),
+            Discovery(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x70, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            Discovery::<std::vec::Vec<u32>>::new(),
+            Discovery(std::vec![
+                0x3016_7E7F,
+                0x0D70_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3026_7F00,
+                0x0000_0000,
+                0x3026_0000,
+                0x0000_0000,
+                0x3036_0000,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_array() {
+        assert_eq!(
+            Discovery::<[u8; 32]>::new().data(),
+            Discovery::<std::vec::Vec<u8>>::new().data(),
+        );
+    }
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut>(message: &mut Discovery<B>) {
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_device_manufacturer([u7::new(0x1), u7::new(0x2), u7::new(0x3)]);
+        message.set_device_family(u14::new(0x1234));
+        message.set_device_family_model_number(u14::new(0x0567));
+        message.set_software_version([u7::new(0x1), u7::new(0x2), u7::new(0x3), u7::new(0x4)]);
+        message.set_supports_profile_configuration(true);
+        message.set_supports_property_exchange(true);
+        message.set_max_sysex_size(u28::new(512));
+        message.set_output_path_id(u7::new(0x5));
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = Discovery::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, Discovery(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = Discovery::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x4));
+        build(&mut message);
+        assert_eq!(message, Discovery(UMP.to_vec()));
+    }
+
+    #[test]
+    fn group() {
+        assert_eq!(Discovery::try_from(&UMP[..]).unwrap().group(), u4::new(0x4));
+    }
+
+    #[test]
+    fn device_id() {
+        assert_eq!(
+            Discovery::try_from(&BYTES[..]).unwrap().device_id(),
+            ci::DeviceId::FunctionBlock,
+        );
+    }
+
+    #[test]
+    fn version() {
+        assert_eq!(
+            Discovery::try_from(&UMP[..]).unwrap().version(),
+            u7::new(0x2)
+        );
+    }
+
+    #[test]
+    fn source_muid() {
+        assert_eq!(
+            Discovery::try_from(&BYTES[..]).unwrap().source_muid(),
+            u28::new(0x0123_4567),
+        );
+        assert_eq!(
+            Discovery::try_from(&UMP[..]).unwrap().source_muid(),
+            u28::new(0x0123_4567),
+        );
+    }
+
+    #[test]
+    fn destination_muid() {
+        assert_eq!(
+            Discovery::try_from(&UMP[..]).unwrap().destination_muid(),
+            ci::BROADCAST_MUID,
+        );
+    }
+
+    #[test]
+    fn device_manufacturer() {
+        assert_eq!(
+            Discovery::try_from(&UMP[..]).unwrap().device_manufacturer(),
+            [u7::new(0x1), u7::new(0x2), u7::new(0x3)],
+        );
+    }
+
+    #[test]
+    fn device_family() {
+        assert_eq!(
+            Discovery::try_from(&BYTES[..]).unwrap().device_family(),
+            u14::new(0x1234),
+        );
+    }
+
+    #[test]
+    fn device_family_model_number() {
+        assert_eq!(
+            Discovery::try_from(&UMP[..])
+                .unwrap()
+                .device_family_model_number(),
+            u14::new(0x0567),
+        );
+    }
+
+    #[test]
+    fn software_version() {
+        assert_eq!(
+            Discovery::try_from(&BYTES[..]).unwrap().software_version(),
+            [u7::new(0x1), u7::new(0x2), u7::new(0x3), u7::new(0x4)],
+        );
+    }
+
+    #[test]
+    fn categories() {
+        let message = Discovery::try_from(&UMP[..]).unwrap();
+        assert!(!message.supports_protocol_negotiation());
+        assert!(message.supports_profile_configuration());
+        assert!(message.supports_property_exchange());
+        assert!(!message.supports_process_inquiry());
+    }
+
+    #[test]
+    fn max_sysex_size() {
+        assert_eq!(
+            Discovery::try_from(&UMP[..]).unwrap().max_sysex_size(),
+            u28::new(512),
+        );
+    }
+
+    #[test]
+    fn output_path_id() {
+        assert_eq!(
+            Discovery::try_from(&BYTES[..]).unwrap().output_path_id(),
+            u7::new(0x5),
+        );
+    }
+
+    #[test]
+    fn try_from_wrong_sub_id_2() {
+        let mut buffer = BYTES;
+        buffer[4] = 0x71;
+        assert_eq!(
+            Discovery::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_INCORRECT_SUB_ID_2)),
+        );
+    }
+
+    #[test]
+    fn try_from_truncated() {
+        let mut buffer = BYTES;
+        buffer[30] = 0xF7;
+        assert_eq!(
+            Discovery::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_PAYLOAD_TOO_SHORT)),
+        );
+    }
+
+    #[test]
+    fn data() {
+        assert_eq!(Discovery::try_from(&UMP[..]).unwrap().data(), &UMP[..]);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x44;
+const PAYLOAD_SIZE: usize = 13;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(15))]
+/// Marks the end of the messages sent in response to a
+/// [MidiMessageReport](crate::ci::MidiMessageReport).
+struct EndOfMidiMessageReport {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for EndOfMidiMessageReport<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            EndOfMidiMessageReport::<std::vec::Vec<u32>>::new(),
+            EndOfMidiMessageReport(std::vec![
+                0x3016_7E7F,
+                0x0D44_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3031_7F00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = EndOfMidiMessageReport::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_device_id(ci::DeviceId::Channel(u4::new(0x5)));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        assert_eq!(
+            message,
+            EndOfMidiMessageReport(std::vec![
+                0x3316_7E05,
+                0x0D44_0267,
+                0x3326_0A0D,
+                0x0921_0615,
+                0x3331_3B00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn device_id() {
+        assert_eq!(
+            EndOfMidiMessageReport::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x05, 0x0D, 0x44, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06,
+                    0x15, 0x3B, 0xF7,
+                ][..]
+            )
+            .unwrap()
+            .device_id(),
+            ci::DeviceId::Channel(u4::new(0x5)),
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x72;
+const PAYLOAD_SIZE: usize = 14;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(16))]
+/// Requests information about the endpoint of a MIDI-CI device.
+struct EndpointInquiry {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7Property<13>)]
+    status: u7,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for EndpointInquiry<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            EndpointInquiry::<std::vec::Vec<u8>>::new(),
+            EndpointInquiry(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x72, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = EndpointInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x2));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        assert_eq!(
+            message,
+            EndpointInquiry(std::vec![
+                0x3216_7E7F,
+                0x0D72_0267,
+                0x3226_0A0D,
+                0x0921_0615,
+                0x3232_3B00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn destination_muid() {
+        assert_eq!(
+            EndpointInquiry::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x7F, 0x0D, 0x72, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06,
+                    0x15, 0x3B, 0x00, 0xF7,
+                ][..]
+            )
+            .unwrap()
+            .destination_muid(),
+            u28::new(0x0765_4321),
+        );
+    }
+
+    #[test]
+    fn status() {
+        assert_eq!(
+            EndpointInquiry::try_from(
+                &[
+                    0x3216_7E7F_u32,
+                    0x0D72_0267,
+                    0x3226_0A0D,
+                    0x0921_0615,
+                    0x3232_3B00,
+                    0x0000_0000,
+                ][..]
+            )
+            .unwrap()
+            .status(),
+            u7::new(0x0),
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x34;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(24))]
+/// Requests the data of a property from a MIDI-CI device.
+struct GetPropertyData {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, {ci::property_exchange::PAYLOAD_SIZE}>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::property_exchange::RequestIdProperty)]
+    request_id: u7,
+    #[property(ci::property_exchange::HeaderWriteProperty)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    header: &[u8],
+    #[property(ci::property_exchange::HeaderReadProperty)]
+    #[readonly]
+    header: ci::DataIterator<'_, B::Unit>,
+    #[property(ci::property_exchange::NumberOfChunksProperty)]
+    number_of_chunks: u14,
+    #[property(ci::property_exchange::ThisChunkProperty)]
+    this_chunk: u14,
+    #[property(ci::property_exchange::PropertyDataWriteProperty)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    property_data: &[u8],
+    #[property(ci::property_exchange::PropertyDataReadProperty)]
+    #[readonly]
+    property_data: ci::DataIterator<'_, B::Unit>,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for GetPropertyData<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 34] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x34, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x01,
+        0x07, 0x00, 0x7B, 0x22, 0x78, 0x22, 0x3A, 0x31, 0x7D, 0x01, 0x00, 0x01, 0x00, 0x03, 0x00,
+        0x61, 0x62, 0x63, 0xF7,
+    ];
+
+    const UMP: [u32; 12] = [
+        0x3316_7E7F,
+        0x0D34_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B01,
+        0x0700_7B22,
+        0x3326_7822,
+        0x3A31_7D01,
+        0x3326_0001,
+        0x0003_0061,
+        0x3332_6263,
+        0x0000_0000,
+    ];
+
+    const HEADER: &[u8] = b"{\"x\":1}";
+    const PROPERTY_DATA: &[u8] = b"abc";
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferResize>(
+        message: &mut GetPropertyData<B>,
+    ) {
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_request_id(u7::new(0x1));
+        message.set_header(HEADER).unwrap();
+        message.set_property_data(PROPERTY_DATA).unwrap();
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            GetPropertyData::<std::vec::Vec<u8>>::new(),
+            GetPropertyData(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x34, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            GetPropertyData::<std::vec::Vec<u32>>::new(),
+            GetPropertyData(std::vec![
+                0x3016_7E7F,
+                0x0D34_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3026_7F00,
+                0x0000_0100,
+                0x3034_0100,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = GetPropertyData::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, GetPropertyData(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = GetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, GetPropertyData(UMP.to_vec()));
+    }
+
+    #[test]
+    fn set_header_after_property_data() {
+        let mut message = GetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_request_id(u7::new(0x1));
+        message.set_property_data(PROPERTY_DATA).unwrap();
+        message.set_header(HEADER).unwrap();
+        assert_eq!(message, GetPropertyData(UMP.to_vec()));
+    }
+
+    #[test]
+    fn shrink_header() {
+        let mut message = GetPropertyData::<std::vec::Vec<u8>>::new();
+        message.set_header(HEADER).unwrap();
+        message.set_header(&[]).unwrap();
+        assert_eq!(message, GetPropertyData::<std::vec::Vec<u8>>::new());
+    }
+
+    #[test]
+    fn try_set_header_overflow() {
+        let mut message = GetPropertyData::<[u8; 30]>::new();
+        assert_eq!(
+            message.try_set_header(HEADER),
+            Err(crate::error::Error::BufferOverflow)
+        );
+        assert_eq!(message, GetPropertyData::<[u8; 30]>::new());
+    }
+
+    #[test]
+    fn non_7_bit_header() {
+        let mut message = GetPropertyData::<std::vec::Vec<u8>>::new();
+        assert_eq!(
+            message.set_header(&[0xC3, 0xA9]),
+            Err(crate::error::Error::InvalidData(ci::ERR_NOT_7_BIT)),
+        );
+        assert_eq!(message, GetPropertyData::<std::vec::Vec<u8>>::new());
+    }
+
+    #[test]
+    fn header_too_long() {
+        let mut message = GetPropertyData::<std::vec::Vec<u8>>::new();
+        assert_eq!(
+            message.try_set_header(&[b'a'; 0x4000]),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_TOO_LONG)),
+        );
+        assert_eq!(message, GetPropertyData::<std::vec::Vec<u8>>::new());
+    }
+
+    #[test]
+    fn non_7_bit_property_data() {
+        let mut message = GetPropertyData::<std::vec::Vec<u8>>::new();
+        assert_eq!(
+            message.set_property_data(&[0x80]),
+            Err(crate::error::Error::InvalidData(ci::ERR_NOT_7_BIT)),
+        );
+        assert_eq!(message, GetPropertyData::<std::vec::Vec<u8>>::new());
+    }
+
+    #[test]
+    fn header() {
+        assert_eq!(
+            GetPropertyData::try_from(&UMP[..])
+                .unwrap()
+                .header()
+                .map(u8::from)
+                .collect::<std::vec::Vec<_>>(),
+            HEADER.to_vec(),
+        );
+    }
+
+    #[test]
+    fn number_of_chunks() {
+        assert_eq!(
+            GetPropertyData::try_from(&BYTES[..])
+                .unwrap()
+                .number_of_chunks(),
+            u14::new(0x1),
+        );
+    }
+
+    #[test]
+    fn property_data() {
+        assert_eq!(
+            GetPropertyData::try_from(&BYTES[..])
+                .unwrap()
+                .property_data()
+                .map(u8::from)
+                .collect::<std::vec::Vec<_>>(),
+            PROPERTY_DATA.to_vec(),
+        );
+    }
+
+    #[test]
+    fn try_from_header_overrun() {
+        let mut buffer = BYTES;
+        buffer[16] = 0x01;
+        assert_eq!(
+            GetPropertyData::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN)),
+        );
+    }
+
+    #[test]
+    fn try_from_property_data_overrun() {
+        let mut buffer = BYTES;
+        buffer[29] = 0x04;
+        assert_eq!(
+            GetPropertyData::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN)),
+        );
+    }
+}
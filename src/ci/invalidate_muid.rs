@@ -0,0 +1,144 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x7E;
+const PAYLOAD_SIZE: usize = 17;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(19))]
+/// Instructs MIDI-CI devices to forget the target MUID.
+struct InvalidateMuid {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U28Property<13>)]
+    target_muid: u28,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for InvalidateMuid<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{Grouped, Size};
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            InvalidateMuid::<std::vec::Vec<u8>>::new(),
+            InvalidateMuid(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7E, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            InvalidateMuid::<std::vec::Vec<u32>>::new(),
+            InvalidateMuid(std::vec![
+                0x3016_7E7F,
+                0x0D7E_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3035_7F00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = InvalidateMuid::<[u8; 19]>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_target_muid(u28::new(0x0765_4321));
+        assert_eq!(
+            message,
+            InvalidateMuid([
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7E, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x21, 0x06, 0x15, 0x3B, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = InvalidateMuid::<[u32; 6]>::new();
+        message.set_group(u4::new(0x1));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_target_muid(u28::new(0x0765_4321));
+        assert_eq!(
+            message,
+            InvalidateMuid([
+                0x3116_7E7F,
+                0x0D7E_0267,
+                0x3126_0A0D,
+                0x097F_7F7F,
+                0x3135_7F21,
+                0x0615_3B00,
+            ]),
+        );
+    }
+
+    #[test]
+    fn target_muid() {
+        assert_eq!(
+            InvalidateMuid::try_from(
+                &[
+                    0x3116_7E7F_u32,
+                    0x0D7E_0267,
+                    0x3126_0A0D,
+                    0x097F_7F7F,
+                    0x3135_7F21,
+                    0x0615_3B00,
+                ][..]
+            )
+            .unwrap()
+            .target_muid(),
+            u28::new(0x0765_4321),
+        );
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(InvalidateMuid::<[u8; 32]>::new().size(), 19);
+    }
+
+    #[test]
+    fn try_from_invalid_device_id() {
+        assert_eq!(
+            InvalidateMuid::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x20, 0x0D, 0x7E, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x7F, 0x7F,
+                    0x7F, 0x7F, 0x21, 0x06, 0x15, 0x3B, 0xF7,
+                ][..]
+            ),
+            Err(crate::error::Error::InvalidData(ci::ERR_INVALID_DEVICE_ID)),
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x42;
+const PAYLOAD_SIZE: usize = 18;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(20))]
+/// Requests a snapshot of the current state of a MIDI-CI device
+/// in the form of MIDI messages.
+struct MidiMessageReport {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::MessageDataControlProperty<13>)]
+    message_data_control: ci::MessageDataControl,
+    #[property(ci::MessageBitmapProperty<ci::SystemMessages, 14>)]
+    system_messages: ci::SystemMessages,
+    #[property(ci::MessageBitmapProperty<ci::ChannelControllerMessages, 16>)]
+    channel_controller_messages: ci::ChannelControllerMessages,
+    #[property(ci::MessageBitmapProperty<ci::NoteDataMessages, 17>)]
+    note_data_messages: ci::NoteDataMessages,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for MidiMessageReport<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 20] = [
+        0xF0, 0x7E, 0x05, 0x0D, 0x42, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x7F,
+        0x05, 0x00, 0x23, 0x03, 0xF7,
+    ];
+
+    const UMP: [u32; 6] = [
+        0x3316_7E05,
+        0x0D42_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3336_3B7F,
+        0x0500_2303,
+    ];
+
+    const SYSTEM_MESSAGES: ci::SystemMessages = ci::SystemMessages {
+        mtc_quarter_frame: true,
+        song_position: false,
+        song_select: true,
+    };
+
+    const CHANNEL_CONTROLLER_MESSAGES: ci::ChannelControllerMessages =
+        ci::ChannelControllerMessages {
+            pitch_bend: true,
+            control_change: true,
+            registered_controller: false,
+            assignable_controller: false,
+            program_change: false,
+            channel_pressure: true,
+        };
+
+    const NOTE_DATA_MESSAGES: ci::NoteDataMessages = ci::NoteDataMessages {
+        notes: true,
+        key_pressure: true,
+        per_note_pitch_bend: false,
+        registered_per_note_controller: false,
+        assignable_per_note_controller: false,
+    };
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut>(
+        message: &mut MidiMessageReport<B>,
+    ) {
+        message.set_device_id(ci::DeviceId::Channel(u4::new(0x5)));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_message_data_control(ci::MessageDataControl::Full);
+        message.set_system_messages(SYSTEM_MESSAGES);
+        message.set_channel_controller_messages(CHANNEL_CONTROLLER_MESSAGES);
+        message.set_note_data_messages(NOTE_DATA_MESSAGES);
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            MidiMessageReport::<std::vec::Vec<u8>>::new(),
+            MidiMessageReport(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x42, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = MidiMessageReport::<[u8; 20]>::new();
+        build(&mut message);
+        assert_eq!(message, MidiMessageReport(BYTES));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = MidiMessageReport::<[u32; 6]>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, MidiMessageReport(UMP));
+    }
+
+    #[test]
+    fn message_data_control() {
+        assert_eq!(
+            MidiMessageReport::try_from(&BYTES[..])
+                .unwrap()
+                .message_data_control(),
+            ci::MessageDataControl::Full,
+        );
+    }
+
+    #[test]
+    fn system_messages() {
+        assert_eq!(
+            MidiMessageReport::try_from(&UMP[..])
+                .unwrap()
+                .system_messages(),
+            SYSTEM_MESSAGES,
+        );
+    }
+
+    #[test]
+    fn channel_controller_messages() {
+        assert_eq!(
+            MidiMessageReport::try_from(&UMP[..])
+                .unwrap()
+                .channel_controller_messages(),
+            CHANNEL_CONTROLLER_MESSAGES,
+        );
+    }
+
+    #[test]
+    fn note_data_messages() {
+        assert_eq!(
+            MidiMessageReport::try_from(&BYTES[..])
+                .unwrap()
+                .note_data_messages(),
+            NOTE_DATA_MESSAGES,
+        );
+    }
+
+    #[test]
+    fn try_from_invalid_message_data_control() {
+        let mut buffer = BYTES;
+        buffer[14] = 0x02;
+        assert!(MidiMessageReport::try_from(&buffer[..]).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x7F;
+const PAYLOAD_SIZE: usize = 23;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(25))]
+/// Rejects a MIDI-CI inquiry.
+struct Nak {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7Property<13>)]
+    original_sub_id_2: u7,
+    #[property(ci::U7Property<14>)]
+    status_code: u7,
+    #[property(ci::U7Property<15>)]
+    status_data: u7,
+    #[property(ci::U7ArrayProperty<16, 5>)]
+    details: [u7; 5],
+    #[property(ci::TextWriteProperty<21>)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    message_text: &str,
+    #[property(ci::DataReadProperty<21, 2>)]
+    #[readonly]
+    message_text_bytes: ci::DataIterator<'_, B::Unit>,
+    #[property(ci::TextReadStringProperty<21>)]
+    #[readonly]
+    #[std]
+    message_text: std::string::String,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for Nak<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 29] = [
+        0xF0, 0x7E, 0x03, 0x0D, 0x7F, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x34,
+        0x10, 0x0A, 0x01, 0x02, 0x03, 0x04, 0x05, 0x04, 0x00, 0x42, 0x75, 0x73, 0x79, 0xF7,
+    ];
+
+    const UMP: [u32; 10] = [
+        0x3316_7E03,
+        0x0D7F_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B34,
+        0x100A_0102,
+        0x3326_0304,
+        0x0504_0042,
+        0x3333_7573,
+        0x7900_0000,
+    ];
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferResize>(
+        message: &mut Nak<B>,
+    ) {
+        message.set_device_id(ci::DeviceId::Channel(u4::new(0x3)));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_original_sub_id_2(u7::new(0x34));
+        message.set_status_code(u7::new(0x10));
+        message.set_status_data(u7::new(0x0A));
+        message.set_details([
+            u7::new(0x1),
+            u7::new(0x2),
+            u7::new(0x3),
+            u7::new(0x4),
+            u7::new(0x5),
+        ]);
+        message.set_message_text("Busy").unwrap();
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            Nak::<std::vec::Vec<u8>>::new(),
+            Nak(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7F, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = Nak::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, Nak(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = Nak::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, Nak(UMP.to_vec()));
+    }
+
+    #[test]
+    fn device_id() {
+        assert_eq!(
+            Nak::try_from(&UMP[..]).unwrap().device_id(),
+            ci::DeviceId::Channel(u4::new(0x3)),
+        );
+    }
+
+    #[test]
+    fn status_code() {
+        assert_eq!(
+            Nak::try_from(&BYTES[..]).unwrap().status_code(),
+            u7::new(0x10)
+        );
+    }
+
+    #[test]
+    fn status_data() {
+        assert_eq!(
+            Nak::try_from(&UMP[..]).unwrap().status_data(),
+            u7::new(0x0A)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn message_text() {
+        assert_eq!(Nak::try_from(&BYTES[..]).unwrap().message_text(), "Busy");
+    }
+
+    #[test]
+    fn try_from_ack() {
+        let mut buffer = BYTES;
+        buffer[4] = 0x7D;
+        assert_eq!(
+            Nak::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_INCORRECT_SUB_ID_2)),
+        );
+    }
+}
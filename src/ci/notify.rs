@@ -0,0 +1,118 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x3F;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(24))]
+/// Notifies a MIDI-CI device about a property exchange transaction.
+struct Notify {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, {ci::property_exchange::PAYLOAD_SIZE}>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::property_exchange::RequestIdProperty)]
+    request_id: u7,
+    #[property(ci::property_exchange::HeaderWriteProperty)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    header: &[u8],
+    #[property(ci::property_exchange::HeaderReadProperty)]
+    #[readonly]
+    header: ci::DataIterator<'_, B::Unit>,
+    #[property(ci::property_exchange::NumberOfChunksProperty)]
+    number_of_chunks: u14,
+    #[property(ci::property_exchange::ThisChunkProperty)]
+    this_chunk: u14,
+    #[property(ci::property_exchange::PropertyDataWriteProperty)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    property_data: &[u8],
+    #[property(ci::property_exchange::PropertyDataReadProperty)]
+    #[readonly]
+    property_data: ci::DataIterator<'_, B::Unit>,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for Notify<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const UMP: [u32; 12] = [
+        0x3316_7E7F,
+        0x0D3F_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B01,
+        0x0700_7B22,
+        0x3326_7822,
+        0x3A31_7D01,
+        0x3326_0001,
+        0x0003_0061,
+        0x3332_6263,
+        0x0000_0000,
+    ];
+
+    #[test]
+    fn builder_ump() {
+        let mut message = Notify::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_request_id(u7::new(0x1));
+        message.set_header(b"{\"x\":1}").unwrap();
+        message.set_property_data(b"abc").unwrap();
+        assert_eq!(message, Notify(UMP.to_vec()));
+    }
+
+    #[test]
+    fn header() {
+        assert_eq!(
+            Notify::try_from(&UMP[..])
+                .unwrap()
+                .header()
+                .map(u8::from)
+                .collect::<std::vec::Vec<_>>(),
+            b"{\"x\":1}".to_vec(),
+        );
+    }
+
+    #[test]
+    fn property_data() {
+        assert_eq!(
+            Notify::try_from(&UMP[..])
+                .unwrap()
+                .property_data()
+                .map(u8::from)
+                .collect::<std::vec::Vec<_>>(),
+            b"abc".to_vec(),
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{
+    buffer::{Buffer, BufferMut},
+    ci,
+    detail::property::{Property, ReadProperty, WriteProperty},
+    sysex7,
+    ux::u7,
+};
+
+const ERR_INVALID_MESSAGE_DATA_CONTROL: &str = "Invalid MIDI message report data control";
+
+const NO_DATA: u8 = 0x00;
+const ONLY_NON_DEFAULT: u8 = 0x01;
+const FULL: u8 = 0x7F;
+
+/// Selects which values a [MidiMessageReport](crate::ci::MidiMessageReport)
+/// asks the device to report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MessageDataControl {
+    /// Report no message data.
+    #[default]
+    NoData,
+    /// Report only the values which differ from their defaults.
+    OnlyNonDefault,
+    /// Report every value.
+    Full,
+}
+
+macro_rules! message_bitmap {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$field_meta:meta])* $field:ident: $bit:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: bool,)*
+        }
+
+        impl From<u7> for $name {
+            fn from(v: u7) -> Self {
+                let v = u8::from(v);
+                $name {
+                    $($field: v & (1 << $bit) != 0,)*
+                }
+            }
+        }
+
+        impl From<$name> for u7 {
+            fn from(v: $name) -> Self {
+                let mut data = 0x0_u8;
+                $(
+                    if v.$field {
+                        data |= 1 << $bit;
+                    }
+                )*
+                u7::new(data)
+            }
+        }
+    };
+}
+
+message_bitmap! {
+    /// The system messages covered by a MIDI message report.
+    SystemMessages {
+        mtc_quarter_frame: 0,
+        song_position: 1,
+        song_select: 2,
+    }
+}
+
+message_bitmap! {
+    /// The channel controller messages covered by a MIDI message report.
+    ChannelControllerMessages {
+        pitch_bend: 0,
+        control_change: 1,
+        registered_controller: 2,
+        assignable_controller: 3,
+        program_change: 4,
+        channel_pressure: 5,
+    }
+}
+
+message_bitmap! {
+    /// The note data messages covered by a MIDI message report.
+    NoteDataMessages {
+        notes: 0,
+        key_pressure: 1,
+        per_note_pitch_bend: 2,
+        registered_per_note_controller: 3,
+        assignable_per_note_controller: 4,
+    }
+}
+
+pub(crate) struct MessageDataControlProperty<const INDEX: usize>;
+
+impl<const INDEX: usize, B: Buffer> Property<B> for MessageDataControlProperty<INDEX> {
+    type Type = MessageDataControl;
+}
+
+impl<'a, const INDEX: usize, B: Buffer> ReadProperty<'a, B> for MessageDataControlProperty<INDEX> {
+    fn read(buffer: &'a B) -> Self::Type {
+        match u8::from(ci::read_u7s::<1, B>(buffer, INDEX)[0]) {
+            ONLY_NON_DEFAULT => MessageDataControl::OnlyNonDefault,
+            FULL => MessageDataControl::Full,
+            _ => MessageDataControl::NoData,
+        }
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        match u8::from(ci::read_u7s::<1, B>(buffer, INDEX)[0]) {
+            NO_DATA | ONLY_NON_DEFAULT | FULL => Ok(()),
+            _ => Err(crate::error::Error::InvalidData(
+                ERR_INVALID_MESSAGE_DATA_CONTROL,
+            )),
+        }
+    }
+}
+
+impl<const INDEX: usize, B: Buffer + BufferMut> WriteProperty<B>
+    for MessageDataControlProperty<INDEX>
+{
+    fn write(buffer: &mut B, v: Self::Type) {
+        let v = match v {
+            MessageDataControl::NoData => NO_DATA,
+            MessageDataControl::OnlyNonDefault => ONLY_NON_DEFAULT,
+            MessageDataControl::Full => FULL,
+        };
+        sysex7::write_datum(buffer, u7::new(v), INDEX);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+// one of the message bitmaps above
+pub(crate) struct MessageBitmapProperty<T, const INDEX: usize>(core::marker::PhantomData<T>);
+
+impl<T, const INDEX: usize, B: Buffer> Property<B> for MessageBitmapProperty<T, INDEX> {
+    type Type = T;
+}
+
+impl<'a, T: From<u7>, const INDEX: usize, B: Buffer> ReadProperty<'a, B>
+    for MessageBitmapProperty<T, INDEX>
+{
+    fn read(buffer: &'a B) -> Self::Type {
+        ci::read_u7s::<1, B>(buffer, INDEX)[0].into()
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: Default + Into<u7>, const INDEX: usize, B: Buffer + BufferMut> WriteProperty<B>
+    for MessageBitmapProperty<T, INDEX>
+{
+    fn write(buffer: &mut B, v: Self::Type) {
+        sysex7::write_datum(buffer, v.into(), INDEX);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn channel_controller_messages_from_u7() {
+        assert_eq!(
+            ChannelControllerMessages::from(u7::new(0b0010_0110)),
+            ChannelControllerMessages {
+                control_change: true,
+                registered_controller: true,
+                channel_pressure: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn note_data_messages_into_u7() {
+        assert_eq!(
+            u7::from(NoteDataMessages {
+                notes: true,
+                assignable_per_note_controller: true,
+                ..Default::default()
+            }),
+            u7::new(0b0001_0001),
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x26;
+const PAYLOAD_SIZE: usize = 18;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(20))]
+/// Reports that a profile has been added to a MIDI-CI device.
+struct ProfileAddedReport {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ProfileAddedReport<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PROFILE_ID: ci::ProfileId = ci::ProfileId::Standard {
+        bank: u7::new(0x31),
+        number: u7::new(0x0),
+        version: u7::new(0x1),
+        level: u7::new(0x1),
+    };
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            ProfileAddedReport::<std::vec::Vec<u32>>::new(),
+            ProfileAddedReport(std::vec![
+                0x3016_7E7F,
+                0x0D26_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3036_7F7E,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ProfileAddedReport::<[u8; 20]>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(PROFILE_ID);
+        assert_eq!(
+            message,
+            ProfileAddedReport([
+                0xF0, 0x7E, 0x7F, 0x0D, 0x26, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B,
+                0x7E, 0x31, 0x00, 0x01, 0x01, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn profile_id() {
+        assert_eq!(
+            ProfileAddedReport::try_from(
+                &[
+                    0x3016_7E7F_u32,
+                    0x0D26_0267,
+                    0x3026_0A0D,
+                    0x0921_0615,
+                    0x3036_3B7E,
+                    0x3100_0101,
+                ][..]
+            )
+            .unwrap()
+            .profile_id(),
+            PROFILE_ID,
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x28;
+const PAYLOAD_SIZE: usize = 19;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(21))]
+/// Requests details about a profile from a MIDI-CI device.
+struct ProfileDetailsInquiry {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(ci::U7Property<18>)]
+    inquiry_target: u7,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ProfileDetailsInquiry<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ProfileDetailsInquiry::<std::vec::Vec<u8>>::new(),
+            ProfileDetailsInquiry(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x28, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x7E, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ProfileDetailsInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(ci::ProfileId::Standard {
+            bank: u7::new(0x31),
+            number: u7::new(0x0),
+            version: u7::new(0x1),
+            level: u7::new(0x1),
+        });
+        message.set_inquiry_target(u7::new(0x1));
+        assert_eq!(
+            message,
+            ProfileDetailsInquiry(std::vec![
+                0x3316_7E7F,
+                0x0D28_0267,
+                0x3326_0A0D,
+                0x0921_0615,
+                0x3326_3B7E,
+                0x3100_0101,
+                0x3331_0100,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn inquiry_target() {
+        assert_eq!(
+            ProfileDetailsInquiry::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x7F, 0x0D, 0x28, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06,
+                    0x15, 0x3B, 0x7E, 0x31, 0x00, 0x01, 0x01, 0x01, 0xF7,
+                ][..]
+            )
+            .unwrap()
+            .inquiry_target(),
+            u7::new(0x1),
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x25;
+const PAYLOAD_SIZE: usize = 20;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(22))]
+/// Reports that a MIDI-CI device has disabled a profile.
+struct ProfileDisabledReport {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(ci::U14Property<18>)]
+    number_of_channels: u14,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ProfileDisabledReport<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const UMP: [u32; 8] = [
+        0x3316_7E7F,
+        0x0D25_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B7E,
+        0x3100_0101,
+        0x3332_1000,
+        0x0000_0000,
+    ];
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ProfileDisabledReport::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(ci::ProfileId::Standard {
+            bank: u7::new(0x31),
+            number: u7::new(0x0),
+            version: u7::new(0x1),
+            level: u7::new(0x1),
+        });
+        message.set_number_of_channels(u14::new(0x10));
+        assert_eq!(message, ProfileDisabledReport(UMP.to_vec()));
+    }
+
+    #[test]
+    fn number_of_channels() {
+        assert_eq!(
+            ProfileDisabledReport::try_from(&UMP[..])
+                .unwrap()
+                .number_of_channels(),
+            u14::new(0x10),
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x24;
+const PAYLOAD_SIZE: usize = 20;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(22))]
+/// Reports that a MIDI-CI device has enabled a profile.
+struct ProfileEnabledReport {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(ci::U14Property<18>)]
+    number_of_channels: u14,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ProfileEnabledReport<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const PROFILE_ID: ci::ProfileId = ci::ProfileId::Standard {
+        bank: u7::new(0x31),
+        number: u7::new(0x0),
+        version: u7::new(0x1),
+        level: u7::new(0x1),
+    };
+
+    const BYTES: [u8; 22] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x24, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x7E,
+        0x31, 0x00, 0x01, 0x01, 0x10, 0x00, 0xF7,
+    ];
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ProfileEnabledReport::<std::vec::Vec<u8>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(PROFILE_ID);
+        message.set_number_of_channels(u14::new(0x10));
+        assert_eq!(message, ProfileEnabledReport(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn profile_id() {
+        assert_eq!(
+            ProfileEnabledReport::try_from(
+                &[
+                    0x3016_7E7F_u32,
+                    0x0D24_0267,
+                    0x3026_0A0D,
+                    0x0921_0615,
+                    0x3026_3B7E,
+                    0x3100_0101,
+                    0x3032_1000,
+                    0x0000_0000,
+                ][..]
+            )
+            .unwrap()
+            .profile_id(),
+            PROFILE_ID,
+        );
+    }
+
+    #[test]
+    fn number_of_channels() {
+        assert_eq!(
+            ProfileEnabledReport::try_from(&BYTES[..])
+                .unwrap()
+                .number_of_channels(),
+            u14::new(0x10),
+        );
+    }
+}
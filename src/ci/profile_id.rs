@@ -0,0 +1,191 @@
+use crate::{
+    buffer::{Buffer, BufferMut, Unit},
+    ci,
+    detail::property::{Property, ReadProperty, WriteProperty},
+    sysex7,
+    ux::u7,
+};
+
+const STANDARD_PROFILE: u8 = 0x7E;
+pub(crate) const PROFILE_ID_SIZE: usize = 5;
+
+/// Identifies a MIDI-CI profile.
+///
+/// ```rust
+/// use midi2::{ci::ProfileId, ux::u7};
+///
+/// let data = [u7::new(0x7E), u7::new(0x31), u7::new(0x0), u7::new(0x1), u7::new(0x1)];
+/// let id = ProfileId::from(data);
+/// assert_eq!(
+///     id,
+///     ProfileId::Standard {
+///         bank: u7::new(0x31),
+///         number: u7::new(0x0),
+///         version: u7::new(0x1),
+///         level: u7::new(0x1),
+///     },
+/// );
+/// assert_eq!(<[u7; 5]>::from(id), data);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProfileId {
+    /// A profile defined by the MIDI Association.
+    Standard {
+        bank: u7,
+        number: u7,
+        version: u7,
+        level: u7,
+    },
+    /// A profile defined by a manufacturer.
+    ManufacturerSpecific {
+        manufacturer: [u7; 3],
+        data: [u7; 2],
+    },
+}
+
+impl Default for ProfileId {
+    fn default() -> Self {
+        ProfileId::Standard {
+            bank: Default::default(),
+            number: Default::default(),
+            version: Default::default(),
+            level: Default::default(),
+        }
+    }
+}
+
+impl From<[u7; 5]> for ProfileId {
+    fn from(data: [u7; 5]) -> Self {
+        if u8::from(data[0]) == STANDARD_PROFILE {
+            ProfileId::Standard {
+                bank: data[1],
+                number: data[2],
+                version: data[3],
+                level: data[4],
+            }
+        } else {
+            ProfileId::ManufacturerSpecific {
+                manufacturer: [data[0], data[1], data[2]],
+                data: [data[3], data[4]],
+            }
+        }
+    }
+}
+
+impl From<ProfileId> for [u7; 5] {
+    fn from(id: ProfileId) -> Self {
+        match id {
+            ProfileId::Standard {
+                bank,
+                number,
+                version,
+                level,
+            } => [u7::new(STANDARD_PROFILE), bank, number, version, level],
+            ProfileId::ManufacturerSpecific { manufacturer, data } => [
+                manufacturer[0],
+                manufacturer[1],
+                manufacturer[2],
+                data[0],
+                data[1],
+            ],
+        }
+    }
+}
+
+/// An iterator over a list of profile ids carried by a MIDI-CI message.
+#[derive(Debug, Clone)]
+pub struct ProfileIdIterator<'a, U: Unit>(
+    core::iter::Take<core::iter::Skip<sysex7::PayloadIterator<'a, U>>>,
+);
+
+impl<'a, U: Unit> ProfileIdIterator<'a, U> {
+    pub(crate) fn new<B: Buffer<Unit = U>>(
+        buffer: &'a B,
+        index: usize,
+        number_of_profiles: usize,
+    ) -> Self {
+        ProfileIdIterator(
+            ci::payload(buffer)
+                .skip(index)
+                .take(number_of_profiles * PROFILE_ID_SIZE),
+        )
+    }
+}
+
+impl<'a, U: Unit> core::iter::Iterator for ProfileIdIterator<'a, U> {
+    type Item = ProfileId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut data = [u7::default(); PROFILE_ID_SIZE];
+        for d in data.iter_mut() {
+            *d = self.0.next()?;
+        }
+        Some(data.into())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() / PROFILE_ID_SIZE;
+        (len, Some(len))
+    }
+}
+
+impl<'a, U: Unit> core::iter::FusedIterator for ProfileIdIterator<'a, U> {}
+
+impl<'a, U: Unit> core::iter::ExactSizeIterator for ProfileIdIterator<'a, U> {}
+
+pub(crate) struct ProfileIdProperty;
+
+// all the profile messages carry the profile id straight after the header
+const PROFILE_ID_INDEX: usize = 13;
+
+impl<B: Buffer> Property<B> for ProfileIdProperty {
+    type Type = ProfileId;
+}
+
+impl<'a, B: Buffer> ReadProperty<'a, B> for ProfileIdProperty {
+    fn read(buffer: &'a B) -> Self::Type {
+        ci::read_u7s::<PROFILE_ID_SIZE, B>(buffer, PROFILE_ID_INDEX).into()
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<B: Buffer + BufferMut> WriteProperty<B> for ProfileIdProperty {
+    fn write(buffer: &mut B, v: Self::Type) {
+        let data: [u7; PROFILE_ID_SIZE] = v.into();
+        ci::write_u7s(buffer, PROFILE_ID_INDEX, &data);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn manufacturer_specific() {
+        let data = [
+            u7::new(0x00),
+            u7::new(0x21),
+            u7::new(0x09),
+            u7::new(0x01),
+            u7::new(0x02),
+        ];
+        let id = ProfileId::from(data);
+        assert_eq!(
+            id,
+            ProfileId::ManufacturerSpecific {
+                manufacturer: [u7::new(0x00), u7::new(0x21), u7::new(0x09)],
+                data: [u7::new(0x01), u7::new(0x02)],
+            },
+        );
+        assert_eq!(<[u7; 5]>::from(id), data);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x20;
+const PAYLOAD_SIZE: usize = 13;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(15))]
+/// Requests the list of profiles supported by a MIDI-CI device.
+struct ProfileInquiry {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ProfileInquiry<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ProfileInquiry::<std::vec::Vec<u8>>::new(),
+            ProfileInquiry(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x20, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ProfileInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        assert_eq!(
+            message,
+            ProfileInquiry(std::vec![
+                0x3316_7E7F,
+                0x0D20_0267,
+                0x3326_0A0D,
+                0x0921_0615,
+                0x3331_3B00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn source_muid() {
+        assert_eq!(
+            ProfileInquiry::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x7F, 0x0D, 0x20, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06,
+                    0x15, 0x3B, 0xF7,
+                ][..]
+            )
+            .unwrap()
+            .source_muid(),
+            u28::new(0x0123_4567),
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x2F;
+const PAYLOAD_SIZE: usize = 22;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(24))]
+/// Carries data defined by the specification of a profile.
+struct ProfileSpecificData {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(ci::DataWriteProperty<18, 4>)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    profile_data: &[u7],
+    #[property(ci::DataReadProperty<18, 4>)]
+    #[readonly]
+    profile_data: ci::DataIterator<'_, B::Unit>,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ProfileSpecificData<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 28] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x2F, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x7E,
+        0x31, 0x00, 0x01, 0x01, 0x04, 0x00, 0x00, 0x00, 0x10, 0x11, 0x12, 0x13, 0xF7,
+    ];
+
+    const UMP: [u32; 10] = [
+        0x3316_7E7F,
+        0x0D2F_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B7E,
+        0x3100_0101,
+        0x3326_0400,
+        0x0000_1011,
+        0x3332_1213,
+        0x0000_0000,
+    ];
+
+    const DATA: [u7; 4] = [u7::new(0x10), u7::new(0x11), u7::new(0x12), u7::new(0x13)];
+
+    fn build<
+        B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferTryResize,
+    >(
+        message: &mut ProfileSpecificData<B>,
+    ) {
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(ci::ProfileId::Standard {
+            bank: u7::new(0x31),
+            number: u7::new(0x0),
+            version: u7::new(0x1),
+            level: u7::new(0x1),
+        });
+        message.try_set_profile_data(&DATA[..]).unwrap();
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            ProfileSpecificData::<std::vec::Vec<u32>>::new(),
+            ProfileSpecificData(std::vec![
+                0x3016_7E7F,
+                0x0D2F_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3026_7F7E,
+                0x0000_0000,
+                0x3034_0000,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ProfileSpecificData::<[u8; 28]>::new();
+        build(&mut message);
+        assert_eq!(message, ProfileSpecificData(BYTES));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ProfileSpecificData::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, ProfileSpecificData(UMP.to_vec()));
+    }
+
+    #[test]
+    fn shrink_profile_data() {
+        let mut message = ProfileSpecificData::<std::vec::Vec<u8>>::new();
+        message.try_set_profile_data(&DATA[..]).unwrap();
+        message.try_set_profile_data(&[]).unwrap();
+        assert_eq!(message, ProfileSpecificData::<std::vec::Vec<u8>>::new());
+    }
+
+    #[test]
+    fn profile_data() {
+        assert_eq!(
+            ProfileSpecificData::try_from(&BYTES[..])
+                .unwrap()
+                .profile_data()
+                .collect::<std::vec::Vec<_>>(),
+            DATA.to_vec(),
+        );
+    }
+
+    #[test]
+    fn try_from_data_overrun() {
+        let mut buffer = BYTES;
+        buffer[20] = 0x05;
+        assert_eq!(
+            ProfileSpecificData::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN)),
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    buffer::{Buffer, BufferMut, BufferResize, BufferTryResize},
+    ci,
+    detail::property::{Property, ReadProperty, ResizeProperty, WriteProperty},
+    ux::{u14, u28, u7},
+};
+
+// Property exchange data messages share a common layout:
+// request id, header length, header, number of chunks,
+// this chunk, property data length and property data.
+
+const REQUEST_ID_INDEX: usize = 13;
+const HEADER_LENGTH_INDEX: usize = 14;
+const HEADER_INDEX: usize = 16;
+const NUMBER_OF_CHUNKS_OFFSET: usize = 0;
+const THIS_CHUNK_OFFSET: usize = 2;
+const PROPERTY_DATA_LENGTH_OFFSET: usize = 4;
+const PROPERTY_DATA_OFFSET: usize = 6;
+
+// the payload size of a property exchange data message
+// with an empty header and no property data
+pub(crate) const PAYLOAD_SIZE: usize = HEADER_INDEX + PROPERTY_DATA_OFFSET;
+
+pub(crate) type RequestIdProperty = ci::U7Property<REQUEST_ID_INDEX>;
+
+fn header_length<B: Buffer>(buffer: &B) -> usize {
+    ci::read_length(buffer, HEADER_LENGTH_INDEX, 2)
+}
+
+// the payload index of the fields which follow the header
+fn chunk_index<B: Buffer>(buffer: &B) -> usize {
+    HEADER_INDEX + header_length(buffer)
+}
+
+fn property_data_length<B: Buffer>(buffer: &B) -> usize {
+    ci::read_length(buffer, chunk_index(buffer) + PROPERTY_DATA_LENGTH_OFFSET, 2)
+}
+
+pub(crate) struct HeaderWriteProperty<'a>(core::marker::PhantomData<&'a u8>);
+
+impl<'a, B: Buffer> Property<B> for HeaderWriteProperty<'a> {
+    type Type = &'a [u8];
+}
+
+impl<'a, B: Buffer + BufferMut> WriteProperty<B> for HeaderWriteProperty<'a> {
+    fn write(buffer: &mut B, header: Self::Type) {
+        ci::write_length(buffer, HEADER_LENGTH_INDEX, 2, header.len());
+        ci::write_bytes(buffer, HEADER_INDEX, header);
+    }
+    fn validate(v: &Self::Type) -> crate::result::Result<()> {
+        ci::validate_length(2, v.len())?;
+        ci::validate_bytes(v)
+    }
+    fn default() -> Self::Type {
+        &[]
+    }
+}
+
+impl<'a, B: Buffer + BufferMut> ResizeProperty<B> for HeaderWriteProperty<'a> {
+    fn resize(buffer: &mut B, value: &Self::Type)
+    where
+        B: BufferResize,
+    {
+        let end = chunk_index(buffer);
+        ci::resize_field(buffer, end, HEADER_INDEX + value.len(), ci::resize_payload).unwrap()
+    }
+
+    fn try_resize(buffer: &mut B, value: &Self::Type) -> Result<(), crate::error::BufferOverflow>
+    where
+        B: BufferTryResize,
+    {
+        let end = chunk_index(buffer);
+        ci::resize_field(
+            buffer,
+            end,
+            HEADER_INDEX + value.len(),
+            ci::try_resize_payload,
+        )
+    }
+}
+
+pub(crate) struct HeaderReadProperty<'a>(core::marker::PhantomData<&'a u8>);
+
+impl<'a, B: 'a + Buffer> Property<B> for HeaderReadProperty<'a> {
+    type Type = ci::DataIterator<'a, B::Unit>;
+}
+
+impl<'a, B: 'a + Buffer> ReadProperty<'a, B> for HeaderReadProperty<'a> {
+    fn read(buffer: &'a B) -> Self::Type {
+        ci::DataIterator(
+            ci::payload(buffer)
+                .skip(HEADER_INDEX)
+                .take(header_length(buffer)),
+        )
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        // the chunk fields must follow the header
+        if ci::payload(buffer).len() < chunk_index(buffer) + PROPERTY_DATA_OFFSET {
+            return Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN));
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct ChunkProperty<const OFFSET: usize>;
+
+pub(crate) type NumberOfChunksProperty = ChunkProperty<NUMBER_OF_CHUNKS_OFFSET>;
+pub(crate) type ThisChunkProperty = ChunkProperty<THIS_CHUNK_OFFSET>;
+
+impl<const OFFSET: usize, B: Buffer> Property<B> for ChunkProperty<OFFSET> {
+    type Type = u14;
+}
+
+impl<'a, const OFFSET: usize, B: Buffer> ReadProperty<'a, B> for ChunkProperty<OFFSET> {
+    fn read(buffer: &'a B) -> Self::Type {
+        u14::new(ci::read_length(buffer, chunk_index(buffer) + OFFSET, 2) as u16)
+    }
+    fn validate(_buffer: &B) -> crate::result::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const OFFSET: usize, B: Buffer + BufferMut> WriteProperty<B> for ChunkProperty<OFFSET> {
+    fn write(buffer: &mut B, v: Self::Type) {
+        let index = chunk_index(buffer) + OFFSET;
+        ci::write_length(buffer, index, 2, u16::from(v) as usize);
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        // a complete message
+        u14::new(1)
+    }
+}
+
+pub(crate) struct PropertyDataWriteProperty<'a>(core::marker::PhantomData<&'a u8>);
+
+impl<'a, B: Buffer> Property<B> for PropertyDataWriteProperty<'a> {
+    type Type = &'a [u8];
+}
+
+impl<'a, B: Buffer + BufferMut> WriteProperty<B> for PropertyDataWriteProperty<'a> {
+    fn write(buffer: &mut B, data: Self::Type) {
+        let index = chunk_index(buffer);
+        ci::write_length(buffer, index + PROPERTY_DATA_LENGTH_OFFSET, 2, data.len());
+        ci::write_bytes(buffer, index + PROPERTY_DATA_OFFSET, data);
+    }
+    fn validate(v: &Self::Type) -> crate::result::Result<()> {
+        ci::validate_length(2, v.len())?;
+        ci::validate_bytes(v)
+    }
+    fn default() -> Self::Type {
+        &[]
+    }
+}
+
+impl<'a, B: Buffer + BufferMut> ResizeProperty<B> for PropertyDataWriteProperty<'a> {
+    fn resize(buffer: &mut B, value: &Self::Type)
+    where
+        B: BufferResize,
+    {
+        let payload_size = chunk_index(buffer) + PROPERTY_DATA_OFFSET + value.len();
+        ci::resize_payload(buffer, payload_size).unwrap()
+    }
+
+    fn try_resize(buffer: &mut B, value: &Self::Type) -> Result<(), crate::error::BufferOverflow>
+    where
+        B: BufferTryResize,
+    {
+        let payload_size = chunk_index(buffer) + PROPERTY_DATA_OFFSET + value.len();
+        ci::try_resize_payload(buffer, payload_size)
+    }
+}
+
+pub(crate) struct PropertyDataReadProperty<'a>(core::marker::PhantomData<&'a u8>);
+
+impl<'a, B: 'a + Buffer> Property<B> for PropertyDataReadProperty<'a> {
+    type Type = ci::DataIterator<'a, B::Unit>;
+}
+
+impl<'a, B: 'a + Buffer> ReadProperty<'a, B> for PropertyDataReadProperty<'a> {
+    fn read(buffer: &'a B) -> Self::Type {
+        ci::DataIterator(
+            ci::payload(buffer)
+                .skip(chunk_index(buffer) + PROPERTY_DATA_OFFSET)
+                .take(property_data_length(buffer)),
+        )
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        let end = chunk_index(buffer) + PROPERTY_DATA_OFFSET + property_data_length(buffer);
+        if ci::payload(buffer).len() < end {
+            return Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN));
+        }
+        Ok(())
+    }
+}
+
+// read the fields of a property exchange data message
+pub(crate) fn source_muid<B: Buffer>(buffer: &B) -> u28 {
+    <ci::SourceMuidProperty as ReadProperty<B>>::read(buffer)
+}
+
+pub(crate) fn request_id<B: Buffer>(buffer: &B) -> u7 {
+    <RequestIdProperty as ReadProperty<B>>::read(buffer)
+}
+
+pub(crate) fn number_of_chunks<B: Buffer>(buffer: &B) -> u14 {
+    <NumberOfChunksProperty as ReadProperty<B>>::read(buffer)
+}
+
+pub(crate) fn this_chunk<B: Buffer>(buffer: &B) -> u14 {
+    <ThisChunkProperty as ReadProperty<B>>::read(buffer)
+}
+
+pub(crate) fn header<B: Buffer>(buffer: &B) -> ci::DataIterator<'_, B::Unit> {
+    <HeaderReadProperty as ReadProperty<B>>::read(buffer)
+}
+
+pub(crate) fn property_data<B: Buffer>(buffer: &B) -> ci::DataIterator<'_, B::Unit> {
+    <PropertyDataReadProperty as ReadProperty<B>>::read(buffer)
+}
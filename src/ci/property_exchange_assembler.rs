@@ -0,0 +1,567 @@
+use crate::{
+    buffer::{Buffer, BufferDefault, BufferMut, BufferTryResize, Bytes},
+    ci::{self, property_exchange, Ci},
+    error::Error,
+    traits::Data,
+    ux::{u28, u7},
+};
+
+const ERR_NOT_PROPERTY_DATA: &str = "Not a MIDI-CI property exchange data message";
+const ERR_UNEXPECTED_CHUNK: &str = "Property exchange chunk received out of sequence";
+const ERR_TOO_MANY_REQUESTS: &str = "Too many property exchange requests in progress";
+const ERR_MISMATCHED_CHUNK: &str =
+    "Property exchange chunk is a different message to the one in progress";
+
+/// The header and property data of a property exchange message
+/// reassembled by a [PropertyExchangeAssembler].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyExchangeData<'a> {
+    pub source_muid: u28,
+    pub request_id: u7,
+    pub header: &'a [u8],
+    pub property_data: &'a [u8],
+}
+
+/// Reassembles the chunks of a property exchange message into a
+/// contiguous header and property data.
+///
+/// The header is taken from the first chunk and the property data
+/// of each chunk is appended in order. Chunks of each request must
+/// arrive in sequence, but the chunks of different requests may be
+/// interleaved. Every request in progress occupies one of the `SLOTS`
+/// slots of the assembler until its final chunk arrives. Request ids
+/// are only unique per sender, so the slots are keyed by the source
+/// MUID together with the request id. A chunk which continues a request
+/// must be the same kind of message as its first chunk.
+///
+/// A number of chunks of zero means the sender doesn't know the total
+/// in advance. It then marks the final chunk by setting the number of
+/// chunks to the number of that chunk.
+///
+/// The header and property data are collected in buffers of type `B`.
+/// When `B` is a fixed size array the assembler doesn't allocate and
+/// data which is too large to fit is reported with [Error::BufferOverflow].
+///
+/// ```rust
+/// use midi2::{ci, prelude::*};
+///
+/// let mut assembler = ci::PropertyExchangeAssembler::<[u8; 64], 4>::new();
+///
+/// let mut message = ci::ReplyToGetPropertyData::<Vec<u8>>::new();
+/// message.set_request_id(u7::new(0x1));
+/// message.set_header(b"{\"status\":200}").unwrap();
+/// message.set_number_of_chunks(u14::new(2));
+/// message.set_this_chunk(u14::new(1));
+/// message.set_property_data(b"[1,").unwrap();
+/// assert_eq!(assembler.push(&ci::Ci::from(message.clone())), Ok(None));
+///
+/// message.set_header(&[]).unwrap();
+/// message.set_this_chunk(u14::new(2));
+/// message.set_property_data(b"2]").unwrap();
+/// let data = assembler.push(&ci::Ci::from(message)).unwrap().unwrap();
+///
+/// assert_eq!(data.header, b"{\"status\":200}");
+/// assert_eq!(data.property_data, b"[1,2]");
+/// ```
+#[derive(Clone, Debug)]
+pub struct PropertyExchangeAssembler<
+    B: Bytes + BufferMut + BufferDefault + BufferTryResize,
+    const SLOTS: usize,
+> {
+    slots: [Slot<B>; SLOTS],
+}
+
+#[derive(Clone, Debug)]
+struct Slot<B: Bytes + BufferMut + BufferDefault + BufferTryResize> {
+    header: B,
+    header_len: usize,
+    property_data: B,
+    property_data_len: usize,
+    state: State,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Inactive,
+    Active {
+        source_muid: u28,
+        request_id: u7,
+        // the sub id #2 of the first chunk
+        sub_id_2: u8,
+        // zero when the total is unknown
+        number_of_chunks: u16,
+        next_chunk: u16,
+    },
+}
+
+impl<B: Bytes + BufferMut + BufferDefault + BufferTryResize, const SLOTS: usize> Default
+    for PropertyExchangeAssembler<B, SLOTS>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Bytes + BufferMut + BufferDefault + BufferTryResize, const SLOTS: usize>
+    PropertyExchangeAssembler<B, SLOTS>
+{
+    pub fn new() -> Self {
+        PropertyExchangeAssembler {
+            slots: core::array::from_fn(|_| Slot::new()),
+        }
+    }
+
+    /// Add the next chunk of a property exchange message.
+    ///
+    /// Returns the reassembled data once the final chunk has been pushed.
+    /// A first chunk always begins a new message, discarding any
+    /// partially assembled one with the same request id.
+    /// A first chunk which finds every slot in use is rejected with
+    /// an error, as is a chunk of a different kind of message to the
+    /// request it continues. Neither affects the requests in progress.
+    /// On any other error the request is discarded.
+    pub fn push<C: Buffer>(
+        &mut self,
+        message: &Ci<C>,
+    ) -> Result<Option<PropertyExchangeData<'_>>, Error> {
+        use Ci::*;
+
+        let (buffer, sub_id_2) = match message {
+            GetPropertyData(m) => (m.data(), ci::get_property_data::SUB_ID_2),
+            Notify(m) => (m.data(), ci::notify::SUB_ID_2),
+            ReplyToGetPropertyData(m) => (m.data(), ci::reply_to_get_property_data::SUB_ID_2),
+            ReplyToSetPropertyData(m) => (m.data(), ci::reply_to_set_property_data::SUB_ID_2),
+            ReplyToSubscription(m) => (m.data(), ci::reply_to_subscription::SUB_ID_2),
+            SetPropertyData(m) => (m.data(), ci::set_property_data::SUB_ID_2),
+            Subscription(m) => (m.data(), ci::subscription::SUB_ID_2),
+            _ => return Err(Error::InvalidData(ERR_NOT_PROPERTY_DATA)),
+        };
+
+        let source_muid = property_exchange::source_muid(&buffer);
+        let request_id = property_exchange::request_id(&buffer);
+        let this_chunk = u16::from(property_exchange::this_chunk(&buffer));
+
+        let slot = if this_chunk == 1 {
+            let index = self
+                .position(source_muid, request_id)
+                .or_else(|| {
+                    self.slots
+                        .iter()
+                        .position(|slot| slot.state == State::Inactive)
+                })
+                .ok_or(Error::InvalidData(ERR_TOO_MANY_REQUESTS))?;
+            &mut self.slots[index]
+        } else {
+            let index = self
+                .position(source_muid, request_id)
+                .ok_or(Error::InvalidData(ERR_UNEXPECTED_CHUNK))?;
+            let slot = &mut self.slots[index];
+            if !matches!(slot.state, State::Active { sub_id_2: active, .. } if active == sub_id_2) {
+                return Err(Error::InvalidData(ERR_MISMATCHED_CHUNK));
+            }
+            slot
+        };
+
+        let result = slot.push_chunk(&buffer, sub_id_2);
+        if result.is_err() {
+            slot.reset();
+        }
+        result?;
+
+        match slot.state {
+            State::Active {
+                number_of_chunks,
+                next_chunk,
+                ..
+            } if next_chunk > number_of_chunks && number_of_chunks != 0 => {
+                slot.state = State::Inactive;
+                Ok(Some(PropertyExchangeData {
+                    source_muid,
+                    request_id,
+                    header: &slot.header.buffer()[..slot.header_len],
+                    property_data: &slot.property_data.buffer()[..slot.property_data_len],
+                }))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Discard the partially assembled message with the given
+    /// source MUID and request id.
+    pub fn discard(&mut self, source_muid: u28, request_id: u7) {
+        if let Some(index) = self.position(source_muid, request_id) {
+            self.slots[index].reset();
+        }
+    }
+
+    /// Discard every partially assembled message.
+    pub fn reset(&mut self) {
+        self.slots.iter_mut().for_each(Slot::reset);
+    }
+
+    fn position(&self, source_muid: u28, request_id: u7) -> Option<usize> {
+        self.slots.iter().position(|slot| {
+            matches!(
+                slot.state,
+                State::Active { source_muid: muid, request_id: id, .. }
+                    if muid == source_muid && id == request_id
+            )
+        })
+    }
+}
+
+impl<B: Bytes + BufferMut + BufferDefault + BufferTryResize> Slot<B> {
+    fn new() -> Self {
+        Slot {
+            header: B::default(),
+            header_len: 0,
+            property_data: B::default(),
+            property_data_len: 0,
+            state: State::Inactive,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.header_len = 0;
+        self.property_data_len = 0;
+        self.state = State::Inactive;
+    }
+
+    fn push_chunk<C: Buffer>(&mut self, buffer: &C, sub_id_2: u8) -> Result<(), Error> {
+        let source_muid = property_exchange::source_muid(buffer);
+        let request_id = property_exchange::request_id(buffer);
+        let number_of_chunks = u16::from(property_exchange::number_of_chunks(buffer));
+        let this_chunk = u16::from(property_exchange::this_chunk(buffer));
+
+        if number_of_chunks != 0 && this_chunk > number_of_chunks {
+            return Err(Error::InvalidData(ERR_UNEXPECTED_CHUNK));
+        }
+
+        if this_chunk == 1 {
+            self.reset();
+            self.header_len = extend(&mut self.header, 0, property_exchange::header(buffer))?;
+        } else {
+            match self.state {
+                State::Active {
+                    number_of_chunks: active_number_of_chunks,
+                    next_chunk,
+                    ..
+                } if next_chunk == this_chunk
+                    && (active_number_of_chunks == number_of_chunks
+                        // the final chunk of a message of unknown length
+                        || (active_number_of_chunks == 0 && number_of_chunks == this_chunk)) => {}
+                _ => return Err(Error::InvalidData(ERR_UNEXPECTED_CHUNK)),
+            }
+        }
+
+        self.property_data_len = extend(
+            &mut self.property_data,
+            self.property_data_len,
+            property_exchange::property_data(buffer),
+        )?;
+        self.state = State::Active {
+            source_muid,
+            request_id,
+            sub_id_2,
+            number_of_chunks,
+            next_chunk: this_chunk + 1,
+        };
+        Ok(())
+    }
+}
+
+// append the data to the buffer, returning the new length of the data
+fn extend<B: Bytes + BufferMut + BufferTryResize, U: crate::buffer::Unit>(
+    buffer: &mut B,
+    len: usize,
+    data: ci::DataIterator<'_, U>,
+) -> Result<usize, Error> {
+    let new_len = len + data.len();
+    if new_len > buffer.buffer().len() {
+        buffer.try_resize(new_len)?;
+    }
+    for (b, d) in buffer.buffer_mut()[len..new_len].iter_mut().zip(data) {
+        *b = d.into();
+    }
+    Ok(new_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ux::u14;
+
+    const MUID: u28 = u28::new(0x0123_4567);
+    use pretty_assertions::assert_eq;
+
+    fn chunk(
+        request_id: u8,
+        number_of_chunks: u16,
+        this_chunk: u16,
+        header: &[u8],
+        property_data: &[u8],
+    ) -> Ci<std::vec::Vec<u32>> {
+        chunk_from(
+            MUID,
+            request_id,
+            number_of_chunks,
+            this_chunk,
+            header,
+            property_data,
+        )
+    }
+
+    fn chunk_from(
+        source_muid: u28,
+        request_id: u8,
+        number_of_chunks: u16,
+        this_chunk: u16,
+        header: &[u8],
+        property_data: &[u8],
+    ) -> Ci<std::vec::Vec<u32>> {
+        let mut message = ci::ReplyToGetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(source_muid);
+        message.set_request_id(u7::new(request_id));
+        message.set_header(header).unwrap();
+        message.set_number_of_chunks(u14::new(number_of_chunks));
+        message.set_this_chunk(u14::new(this_chunk));
+        message.set_property_data(property_data).unwrap();
+        message.into()
+    }
+
+    #[test]
+    fn single_chunk() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(
+            assembler.push(&chunk(0x2, 1, 1, b"{}", b"abc")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abc",
+            })),
+        );
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 3, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(assembler.push(&chunk(0x2, 3, 2, b"", b"def")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x2, 3, 3, b"", b"g")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abcdefg",
+            })),
+        );
+    }
+
+    #[test]
+    fn first_chunk_restarts() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"def")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 2, b"", b"g")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"defg",
+            })),
+        );
+    }
+
+    #[test]
+    fn interleaved_requests() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(assembler.push(&chunk(0x3, 2, 1, b"[]", b"def")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x3, 2, 2, b"", b"g")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x3),
+                header: b"[]",
+                property_data: b"defg",
+            })),
+        );
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 2, b"", b"h")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abch",
+            })),
+        );
+    }
+
+    #[test]
+    fn interleaved_senders() {
+        let other = u28::new(0x0765_4321);
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk_from(other, 0x2, 2, 1, b"[]", b"def")),
+            Ok(None)
+        );
+        assert_eq!(
+            assembler.push(&chunk_from(other, 0x2, 2, 2, b"", b"g")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: other,
+                request_id: u7::new(0x2),
+                header: b"[]",
+                property_data: b"defg",
+            })),
+        );
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 2, b"", b"h")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abch",
+            })),
+        );
+    }
+
+    #[test]
+    fn chunk_of_other_message() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+
+        let mut message = ci::SetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(MUID);
+        message.set_request_id(u7::new(0x2));
+        message.set_number_of_chunks(u14::new(2));
+        message.set_this_chunk(u14::new(2));
+        message.set_property_data(b"def").unwrap();
+        assert_eq!(
+            assembler.push(&message.into()),
+            Err(Error::InvalidData(ERR_MISMATCHED_CHUNK)),
+        );
+
+        // the request in progress is unaffected
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 2, b"", b"g")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abcg",
+            })),
+        );
+    }
+
+    #[test]
+    fn too_many_requests() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(assembler.push(&chunk(0x3, 2, 1, b"{}", b"def")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x4, 2, 1, b"{}", b"ghi")),
+            Err(Error::InvalidData(ERR_TOO_MANY_REQUESTS)),
+        );
+        // the requests in progress are unaffected
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 2, b"", b"j")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abcj",
+            })),
+        );
+    }
+
+    #[test]
+    fn discard() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 1>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assembler.discard(MUID, u7::new(0x2));
+        assert!(assembler
+            .push(&chunk(0x3, 1, 1, b"{}", b"d"))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn unknown_number_of_chunks() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 0, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(assembler.push(&chunk(0x2, 0, 2, b"", b"def")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x2, 3, 3, b"", b"g")),
+            Ok(Some(PropertyExchangeData {
+                source_muid: MUID,
+                request_id: u7::new(0x2),
+                header: b"{}",
+                property_data: b"abcdefg",
+            })),
+        );
+    }
+
+    #[test]
+    fn chunk_beyond_number_of_chunks() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert!(assembler
+            .push(&chunk(0x2, 2, 2, b"", b"d"))
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 3, b"", b"e")),
+            Err(Error::InvalidData(ERR_UNEXPECTED_CHUNK)),
+        );
+    }
+
+    #[test]
+    fn chunk_out_of_sequence() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 3, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x2, 3, 3, b"", b"def")),
+            Err(Error::InvalidData(ERR_UNEXPECTED_CHUNK)),
+        );
+        // the assembler is reset
+        assert_eq!(
+            assembler.push(&chunk(0x2, 3, 2, b"", b"def")),
+            Err(Error::InvalidData(ERR_UNEXPECTED_CHUNK)),
+        );
+    }
+
+    #[test]
+    fn chunk_from_other_request() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x3, 2, 2, b"", b"def")),
+            Err(Error::InvalidData(ERR_UNEXPECTED_CHUNK)),
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let mut assembler = PropertyExchangeAssembler::<[u8; 4], 1>::new();
+        assert_eq!(assembler.push(&chunk(0x2, 2, 1, b"{}", b"abc")), Ok(None));
+        assert_eq!(
+            assembler.push(&chunk(0x2, 2, 2, b"", b"de")),
+            Err(Error::BufferOverflow),
+        );
+    }
+
+    #[test]
+    fn not_property_data() {
+        let mut assembler = PropertyExchangeAssembler::<std::vec::Vec<u8>, 2>::new();
+        let mut message = ci::InvalidateMuid::<std::vec::Vec<u32>>::new();
+        message.set_target_muid(u28::new(0x1234));
+        assert_eq!(
+            assembler.push(&message.into()),
+            Err(Error::InvalidData(ERR_NOT_PROPERTY_DATA)),
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x30;
+const PAYLOAD_SIZE: usize = 16;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(18))]
+/// Requests the property exchange capabilities of a MIDI-CI device.
+struct PropertyExchangeCapabilities {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7Property<13>)]
+    number_of_simultaneous_requests: u7,
+    #[property(ci::U7Property<14>)]
+    major_version: u7,
+    #[property(ci::U7Property<15>)]
+    minor_version: u7,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for PropertyExchangeCapabilities<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            PropertyExchangeCapabilities::<std::vec::Vec<u8>>::new(),
+            PropertyExchangeCapabilities(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x30, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = PropertyExchangeCapabilities::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_number_of_simultaneous_requests(u7::new(0x4));
+        assert_eq!(
+            message,
+            PropertyExchangeCapabilities(std::vec![
+                0x3316_7E7F,
+                0x0D30_0267,
+                0x3326_0A0D,
+                0x0921_0615,
+                0x3334_3B04,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn number_of_simultaneous_requests() {
+        assert_eq!(
+            PropertyExchangeCapabilities::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x7F, 0x0D, 0x30, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06,
+                    0x15, 0x3B, 0x04, 0x00, 0x00, 0xF7,
+                ][..]
+            )
+            .unwrap()
+            .number_of_simultaneous_requests(),
+            u7::new(0x4),
+        );
+    }
+}
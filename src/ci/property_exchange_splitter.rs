@@ -0,0 +1,223 @@
+use crate::{
+    ci::{self, property_exchange},
+    error::Error,
+    ux::u14,
+};
+
+const ERR_MAX_SYSEX_SIZE_TOO_SMALL: &str =
+    "Maximum sysex size too small to carry the property exchange data";
+
+// the sysex start and end bytes around the payload
+const SYSEX_FRAMING_SIZE: usize = 2;
+
+/// A chunk of a property exchange message produced by a
+/// [PropertyExchangeSplitter].
+///
+/// Only the first chunk carries the header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PropertyExchangeChunk<'a> {
+    pub number_of_chunks: u14,
+    pub this_chunk: u14,
+    pub header: &'a [u8],
+    pub property_data: &'a [u8],
+}
+
+/// Splits the property data of a property exchange message into chunks
+/// which each fit within a negotiated maximum sysex message size.
+///
+/// The maximum size is the size in bytes of a complete sysex message,
+/// including the start and end bytes, as reported by the
+/// `max_sysex_size` of the discovery messages.
+///
+/// ```rust
+/// use midi2::{ci, prelude::*};
+///
+/// let property_data = [b'a'; 100];
+/// let splitter = ci::PropertyExchangeSplitter::new(b"{}", &property_data, 64).unwrap();
+///
+/// let mut messages = Vec::new();
+/// for chunk in splitter {
+///     let mut message = ci::ReplyToGetPropertyData::<Vec<u8>>::new();
+///     message.set_header(chunk.header).unwrap();
+///     message.set_number_of_chunks(chunk.number_of_chunks);
+///     message.set_this_chunk(chunk.this_chunk);
+///     message.set_property_data(chunk.property_data).unwrap();
+///     assert!(message.data().len() <= 64);
+///     messages.push(message);
+/// }
+/// assert_eq!(messages.len(), 3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PropertyExchangeSplitter<'a> {
+    header: &'a [u8],
+    property_data: &'a [u8],
+    chunk_size: usize,
+    number_of_chunks: usize,
+    this_chunk: usize,
+}
+
+impl<'a> PropertyExchangeSplitter<'a> {
+    /// Fails when the header and at least one byte of
+    /// property data can't fit within a single message,
+    /// or when the header or property data isn't 7 bit.
+    pub fn new(
+        header: &'a [u8],
+        property_data: &'a [u8],
+        max_sysex_size: usize,
+    ) -> Result<Self, Error> {
+        ci::validate_length(2, header.len())?;
+        ci::validate_bytes(header)?;
+        ci::validate_bytes(property_data)?;
+        let chunk_size = max_sysex_size
+            .checked_sub(SYSEX_FRAMING_SIZE + property_exchange::PAYLOAD_SIZE + header.len())
+            .filter(|size| *size > 0)
+            .ok_or(Error::InvalidData(ERR_MAX_SYSEX_SIZE_TOO_SMALL))?
+            // each chunk must fit the 14 bit property data length field
+            .min(u16::from(u14::MAX) as usize);
+        let number_of_chunks = ((property_data.len() + chunk_size - 1) / chunk_size).max(1);
+        if number_of_chunks > u16::from(u14::MAX) as usize {
+            return Err(Error::InvalidData(ERR_MAX_SYSEX_SIZE_TOO_SMALL));
+        }
+        Ok(PropertyExchangeSplitter {
+            header,
+            property_data,
+            chunk_size,
+            number_of_chunks,
+            this_chunk: 0,
+        })
+    }
+
+    pub fn number_of_chunks(&self) -> u14 {
+        u14::new(self.number_of_chunks as u16)
+    }
+}
+
+impl<'a> core::iter::Iterator for PropertyExchangeSplitter<'a> {
+    type Item = PropertyExchangeChunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.this_chunk == self.number_of_chunks {
+            return None;
+        }
+        let start = (self.this_chunk * self.chunk_size).min(self.property_data.len());
+        let end = (start + self.chunk_size).min(self.property_data.len());
+        let header = if self.this_chunk == 0 {
+            self.header
+        } else {
+            &[]
+        };
+        self.this_chunk += 1;
+        Some(PropertyExchangeChunk {
+            number_of_chunks: self.number_of_chunks(),
+            this_chunk: u14::new(self.this_chunk as u16),
+            header,
+            property_data: &self.property_data[start..end],
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.number_of_chunks - self.this_chunk;
+        (len, Some(len))
+    }
+}
+
+impl<'a> core::iter::FusedIterator for PropertyExchangeSplitter<'a> {}
+
+impl<'a> core::iter::ExactSizeIterator for PropertyExchangeSplitter<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn single_chunk() {
+        assert_eq!(
+            PropertyExchangeSplitter::new(b"{}", b"abc", 128)
+                .unwrap()
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![PropertyExchangeChunk {
+                number_of_chunks: u14::new(1),
+                this_chunk: u14::new(1),
+                header: b"{}",
+                property_data: b"abc",
+            }],
+        );
+    }
+
+    #[test]
+    fn empty_property_data() {
+        assert_eq!(
+            PropertyExchangeSplitter::new(b"{}", b"", 128)
+                .unwrap()
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![PropertyExchangeChunk {
+                number_of_chunks: u14::new(1),
+                this_chunk: u14::new(1),
+                header: b"{}",
+                property_data: b"",
+            }],
+        );
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        // room for 3 bytes of property data in each message
+        assert_eq!(
+            PropertyExchangeSplitter::new(b"{}", b"abcdefg", 29)
+                .unwrap()
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                PropertyExchangeChunk {
+                    number_of_chunks: u14::new(3),
+                    this_chunk: u14::new(1),
+                    header: b"{}",
+                    property_data: b"abc",
+                },
+                PropertyExchangeChunk {
+                    number_of_chunks: u14::new(3),
+                    this_chunk: u14::new(2),
+                    header: b"",
+                    property_data: b"def",
+                },
+                PropertyExchangeChunk {
+                    number_of_chunks: u14::new(3),
+                    this_chunk: u14::new(3),
+                    header: b"",
+                    property_data: b"g",
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn len() {
+        let mut splitter = PropertyExchangeSplitter::new(b"{}", b"abcdefg", 29).unwrap();
+        assert_eq!(splitter.len(), 3);
+        splitter.next();
+        assert_eq!(splitter.len(), 2);
+    }
+
+    #[test]
+    fn max_sysex_size_too_small() {
+        assert_eq!(
+            PropertyExchangeSplitter::new(b"{}", b"abc", 26).err(),
+            Some(Error::InvalidData(ERR_MAX_SYSEX_SIZE_TOO_SMALL)),
+        );
+    }
+
+    #[test]
+    fn non_7_bit_property_data() {
+        assert_eq!(
+            PropertyExchangeSplitter::new(b"{}", &[0xC3, 0xA9], 128).err(),
+            Some(Error::InvalidData(ci::ERR_NOT_7_BIT)),
+        );
+    }
+
+    #[test]
+    fn chunks_fit_the_length_field() {
+        let property_data = std::vec![b'a'; 0x4000];
+        let splitter = PropertyExchangeSplitter::new(b"{}", &property_data, usize::MAX).unwrap();
+        assert_eq!(splitter.len(), 2);
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x71;
+const PAYLOAD_SIZE: usize = 31;
+
+#[midi2_proc::generate_message(MinSizeUmp(12), MinSizeBytes(33))]
+/// Sent by a MIDI-CI device in response to a [Discovery](crate::ci::Discovery).
+struct ReplyToDiscovery {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7ArrayProperty<13, 3>)]
+    device_manufacturer: [u7; 3],
+    #[property(ci::U14Property<16>)]
+    device_family: u14,
+    #[property(ci::U14Property<18>)]
+    device_family_model_number: u14,
+    #[property(ci::U7ArrayProperty<20, 4>)]
+    software_version: [u7; 4],
+    #[property(ci::BitProperty<24, 1>)]
+    supports_protocol_negotiation: bool,
+    #[property(ci::BitProperty<24, 2>)]
+    supports_profile_configuration: bool,
+    #[property(ci::BitProperty<24, 3>)]
+    supports_property_exchange: bool,
+    #[property(ci::BitProperty<24, 4>)]
+    supports_process_inquiry: bool,
+    #[property(ci::U28Property<25>)]
+    max_sysex_size: u28,
+    #[property(ci::U7Property<29>)]
+    output_path_id: u7,
+    #[property(ci::U7Property<30>)]
+    function_block: u7,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ReplyToDiscovery<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 33] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x71, 0x02, 0x21, 0x06, 0x15, 0x3B, 0x67, 0x0A, 0x0D, 0x09, 0x01,
+        0x02, 0x03, 0x34, 0x24, 0x67, 0x0A, 0x01, 0x02, 0x03, 0x04, 0x1E, 0x00, 0x04, 0x00, 0x00,
+        0x05, 0x03, 0xF7,
+    ];
+
+    const UMP: [u32; 12] = [
+        0x3416_7E7F,
+        0x0D71_0221,
+        0x3426_0615,
+        0x3B67_0A0D,
+        0x3426_0901,
+        0x0203_3424,
+        0x3426_670A,
+        0x0102_0304,
+        0x3426_1E00,
+        0x0400_0005,
+        0x3431_0300,
+        0x0000_0000,
+    ];
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ReplyToDiscovery::<std::vec::Vec<u8>>::new(),
+            ReplyToDiscovery(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x71, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            ReplyToDiscovery::<std::vec::Vec<u32>>::new(),
+            ReplyToDiscovery(std::vec![
+                0x3016_7E7F,
+                0x0D71_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3026_7F00,
+                0x0000_0000,
+                0x3026_0000,
+                0x0000_0000,
+                0x3026_0000,
+                0x0000_0000,
+                0x3031_0000,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut>(
+        message: &mut ReplyToDiscovery<B>,
+    ) {
+        message.set_source_muid(u28::new(0x0765_4321));
+        message.set_destination_muid(u28::new(0x0123_4567));
+        message.set_device_manufacturer([u7::new(0x1), u7::new(0x2), u7::new(0x3)]);
+        message.set_device_family(u14::new(0x1234));
+        message.set_device_family_model_number(u14::new(0x0567));
+        message.set_software_version([u7::new(0x1), u7::new(0x2), u7::new(0x3), u7::new(0x4)]);
+        message.set_supports_protocol_negotiation(true);
+        message.set_supports_profile_configuration(true);
+        message.set_supports_property_exchange(true);
+        message.set_supports_process_inquiry(true);
+        message.set_max_sysex_size(u28::new(512));
+        message.set_output_path_id(u7::new(0x5));
+        message.set_function_block(u7::new(0x3));
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ReplyToDiscovery::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, ReplyToDiscovery(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ReplyToDiscovery::<[u32; 12]>::new();
+        message.set_group(u4::new(0x4));
+        build(&mut message);
+        assert_eq!(message, ReplyToDiscovery(UMP));
+    }
+
+    #[test]
+    fn source_muid() {
+        assert_eq!(
+            ReplyToDiscovery::try_from(&UMP[..]).unwrap().source_muid(),
+            u28::new(0x0765_4321),
+        );
+    }
+
+    #[test]
+    fn destination_muid() {
+        assert_eq!(
+            ReplyToDiscovery::try_from(&BYTES[..])
+                .unwrap()
+                .destination_muid(),
+            u28::new(0x0123_4567),
+        );
+    }
+
+    #[test]
+    fn categories() {
+        let message = ReplyToDiscovery::try_from(&BYTES[..]).unwrap();
+        assert!(message.supports_protocol_negotiation());
+        assert!(message.supports_profile_configuration());
+        assert!(message.supports_property_exchange());
+        assert!(message.supports_process_inquiry());
+    }
+
+    #[test]
+    fn clear_category() {
+        let mut message = ReplyToDiscovery::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        message.set_supports_property_exchange(false);
+        assert!(message.supports_profile_configuration());
+        assert!(!message.supports_property_exchange());
+        assert!(message.supports_process_inquiry());
+    }
+
+    #[test]
+    fn max_sysex_size() {
+        assert_eq!(
+            ReplyToDiscovery::try_from(&BYTES[..])
+                .unwrap()
+                .max_sysex_size(),
+            u28::new(512),
+        );
+    }
+
+    #[test]
+    fn function_block() {
+        assert_eq!(
+            ReplyToDiscovery::try_from(&UMP[..])
+                .unwrap()
+                .function_block(),
+            u7::new(0x3),
+        );
+    }
+
+    #[test]
+    fn try_from_discovery() {
+        assert_eq!(
+            ReplyToDiscovery::try_from(
+                &[
+                    0xF0_u8, 0x7E, 0x7F, 0x0D, 0x70, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F,
+                    0x7F, 0x7F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF7
+                ][..]
+            ),
+            Err(crate::error::Error::InvalidData(ci::ERR_INCORRECT_SUB_ID_2)),
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x73;
+const PAYLOAD_SIZE: usize = 16;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(18))]
+/// Sent by a MIDI-CI device in response to an
+/// [EndpointInquiry](crate::ci::EndpointInquiry).
+struct ReplyToEndpointInquiry {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::U7Property<13>)]
+    status: u7,
+    #[property(ci::DataWriteProperty<14, 2>)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    information_data: &[u7],
+    #[property(ci::DataReadProperty<14, 2>)]
+    #[readonly]
+    information_data: ci::DataIterator<'_, B::Unit>,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ReplyToEndpointInquiry<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 23] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x73, 0x02, 0x21, 0x06, 0x15, 0x3B, 0x67, 0x0A, 0x0D, 0x09, 0x00,
+        0x05, 0x00, 0x10, 0x11, 0x12, 0x13, 0x14, 0xF7,
+    ];
+
+    const UMP: [u32; 8] = [
+        0x3216_7E7F,
+        0x0D73_0221,
+        0x3226_0615,
+        0x3B67_0A0D,
+        0x3226_0900,
+        0x0500_1011,
+        0x3233_1213,
+        0x1400_0000,
+    ];
+
+    const INFORMATION_DATA: [u7; 5] = [
+        u7::new(0x10),
+        u7::new(0x11),
+        u7::new(0x12),
+        u7::new(0x13),
+        u7::new(0x14),
+    ];
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ReplyToEndpointInquiry::<std::vec::Vec<u8>>::new(),
+            ReplyToEndpointInquiry(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x73, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            ReplyToEndpointInquiry::<std::vec::Vec<u32>>::new(),
+            ReplyToEndpointInquiry(std::vec![
+                0x3016_7E7F,
+                0x0D73_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3034_7F00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ReplyToEndpointInquiry::<std::vec::Vec<u8>>::new();
+        message.set_source_muid(u28::new(0x0765_4321));
+        message.set_destination_muid(u28::new(0x0123_4567));
+        message.set_information_data(&INFORMATION_DATA[..]).unwrap();
+        assert_eq!(message, ReplyToEndpointInquiry(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ReplyToEndpointInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x2));
+        message.set_source_muid(u28::new(0x0765_4321));
+        message.set_destination_muid(u28::new(0x0123_4567));
+        message.set_information_data(&INFORMATION_DATA[..]).unwrap();
+        assert_eq!(message, ReplyToEndpointInquiry(UMP.to_vec()));
+    }
+
+    #[test]
+    fn shrink_information_data() {
+        let mut message = ReplyToEndpointInquiry::<std::vec::Vec<u32>>::new();
+        message.set_information_data(&INFORMATION_DATA[..]).unwrap();
+        message.set_information_data(&[]).unwrap();
+        assert_eq!(message, ReplyToEndpointInquiry::<std::vec::Vec<u32>>::new());
+    }
+
+    #[test]
+    fn try_set_information_data_overflow() {
+        let mut message = ReplyToEndpointInquiry::<[u8; 20]>::new();
+        assert_eq!(
+            message.try_set_information_data(&INFORMATION_DATA[..]),
+            Err(crate::error::Error::BufferOverflow),
+        );
+        assert_eq!(message, ReplyToEndpointInquiry::<[u8; 20]>::new());
+    }
+
+    #[test]
+    fn try_set_information_data_array() {
+        let mut message = ReplyToEndpointInquiry::<[u8; 23]>::new();
+        message.set_source_muid(u28::new(0x0765_4321));
+        message.set_destination_muid(u28::new(0x0123_4567));
+        assert_eq!(
+            message.try_set_information_data(&INFORMATION_DATA[..]),
+            Ok(())
+        );
+        assert_eq!(message, ReplyToEndpointInquiry(BYTES));
+    }
+
+    #[test]
+    fn information_data_bytes() {
+        let message = ReplyToEndpointInquiry::try_from(&BYTES[..]).unwrap();
+        let data = message.information_data();
+        assert_eq!(data.len(), 5);
+        assert_eq!(
+            data.collect::<std::vec::Vec<_>>(),
+            INFORMATION_DATA.to_vec()
+        );
+    }
+
+    #[test]
+    fn information_data_ump() {
+        assert_eq!(
+            ReplyToEndpointInquiry::try_from(&UMP[..])
+                .unwrap()
+                .information_data()
+                .collect::<std::vec::Vec<_>>(),
+            INFORMATION_DATA.to_vec(),
+        );
+    }
+
+    #[test]
+    fn try_from_data_overrun() {
+        let mut buffer = BYTES;
+        buffer[15] = 0x06;
+        assert_eq!(
+            ReplyToEndpointInquiry::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN)),
+        );
+    }
+}
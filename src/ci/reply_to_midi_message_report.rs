@@ -0,0 +1,147 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x43;
+const PAYLOAD_SIZE: usize = 17;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(19))]
+/// Sent by a MIDI-CI device in response to a
+/// [MidiMessageReport](crate::ci::MidiMessageReport) to announce
+/// which messages will make up the report.
+struct ReplyToMidiMessageReport {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::MessageBitmapProperty<ci::SystemMessages, 13>)]
+    system_messages: ci::SystemMessages,
+    #[property(ci::MessageBitmapProperty<ci::ChannelControllerMessages, 15>)]
+    channel_controller_messages: ci::ChannelControllerMessages,
+    #[property(ci::MessageBitmapProperty<ci::NoteDataMessages, 16>)]
+    note_data_messages: ci::NoteDataMessages,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ReplyToMidiMessageReport<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 19] = [
+        0xF0, 0x7E, 0x05, 0x0D, 0x43, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x05,
+        0x00, 0x23, 0x03, 0xF7,
+    ];
+
+    const UMP: [u32; 6] = [
+        0x3316_7E05,
+        0x0D43_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3335_3B05,
+        0x0023_0300,
+    ];
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut>(
+        message: &mut ReplyToMidiMessageReport<B>,
+    ) {
+        message.set_device_id(ci::DeviceId::Channel(u4::new(0x5)));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_system_messages(ci::SystemMessages {
+            mtc_quarter_frame: true,
+            song_select: true,
+            ..Default::default()
+        });
+        message.set_channel_controller_messages(ci::ChannelControllerMessages {
+            pitch_bend: true,
+            control_change: true,
+            channel_pressure: true,
+            ..Default::default()
+        });
+        message.set_note_data_messages(ci::NoteDataMessages {
+            notes: true,
+            key_pressure: true,
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ReplyToMidiMessageReport::<std::vec::Vec<u8>>::new(),
+            ReplyToMidiMessageReport(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x43, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ReplyToMidiMessageReport::<[u8; 19]>::new();
+        build(&mut message);
+        assert_eq!(message, ReplyToMidiMessageReport(BYTES));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ReplyToMidiMessageReport::<[u32; 6]>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, ReplyToMidiMessageReport(UMP));
+    }
+
+    #[test]
+    fn channel_controller_messages() {
+        assert_eq!(
+            ReplyToMidiMessageReport::try_from(&BYTES[..])
+                .unwrap()
+                .channel_controller_messages(),
+            ci::ChannelControllerMessages {
+                pitch_bend: true,
+                control_change: true,
+                channel_pressure: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    #[test]
+    fn note_data_messages() {
+        assert_eq!(
+            ReplyToMidiMessageReport::try_from(&UMP[..])
+                .unwrap()
+                .note_data_messages(),
+            ci::NoteDataMessages {
+                notes: true,
+                key_pressure: true,
+                ..Default::default()
+            },
+        );
+    }
+}
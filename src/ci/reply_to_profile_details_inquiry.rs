@@ -0,0 +1,141 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x29;
+const PAYLOAD_SIZE: usize = 21;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(23))]
+/// Sent by a MIDI-CI device in response to a
+/// [ProfileDetailsInquiry](crate::ci::ProfileDetailsInquiry).
+struct ReplyToProfileDetailsInquiry {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(ci::U7Property<18>)]
+    inquiry_target: u7,
+    #[property(ci::DataWriteProperty<19, 2>)]
+    #[writeonly]
+    #[resize]
+    #[validate]
+    details: &[u7],
+    #[property(ci::DataReadProperty<19, 2>)]
+    #[readonly]
+    details: ci::DataIterator<'_, B::Unit>,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ReplyToProfileDetailsInquiry<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const BYTES: [u8; 27] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x29, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x7E,
+        0x31, 0x00, 0x01, 0x01, 0x01, 0x04, 0x00, 0x10, 0x11, 0x12, 0x13, 0xF7,
+    ];
+
+    const UMP: [u32; 10] = [
+        0x3316_7E7F,
+        0x0D29_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B7E,
+        0x3100_0101,
+        0x3326_0104,
+        0x0010_1112,
+        0x3331_1300,
+        0x0000_0000,
+    ];
+
+    const DETAILS: [u7; 4] = [u7::new(0x10), u7::new(0x11), u7::new(0x12), u7::new(0x13)];
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferResize>(
+        message: &mut ReplyToProfileDetailsInquiry<B>,
+    ) {
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(ci::ProfileId::Standard {
+            bank: u7::new(0x31),
+            number: u7::new(0x0),
+            version: u7::new(0x1),
+            level: u7::new(0x1),
+        });
+        message.set_inquiry_target(u7::new(0x1));
+        message.set_details(&DETAILS[..]).unwrap();
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ReplyToProfileDetailsInquiry::<std::vec::Vec<u8>>::new(),
+            ReplyToProfileDetailsInquiry(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x29, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x7E, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ReplyToProfileDetailsInquiry::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, ReplyToProfileDetailsInquiry(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ReplyToProfileDetailsInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, ReplyToProfileDetailsInquiry(UMP.to_vec()));
+    }
+
+    #[test]
+    fn try_set_details_overflow() {
+        let mut message = ReplyToProfileDetailsInquiry::<[u8; 24]>::new();
+        assert_eq!(
+            message.try_set_details(&DETAILS[..]),
+            Err(crate::error::Error::BufferOverflow),
+        );
+        assert_eq!(message, ReplyToProfileDetailsInquiry::<[u8; 24]>::new());
+    }
+
+    #[test]
+    fn details() {
+        assert_eq!(
+            ReplyToProfileDetailsInquiry::try_from(&UMP[..])
+                .unwrap()
+                .details()
+                .collect::<std::vec::Vec<_>>(),
+            DETAILS.to_vec(),
+        );
+    }
+}
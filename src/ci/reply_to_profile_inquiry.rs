@@ -0,0 +1,369 @@
+use crate::{
+    buffer::{Buffer, BufferMut, BufferResize, BufferTryResize},
+    ci,
+    detail::{
+        common_properties,
+        property::{Property, ReadProperty, ResizeProperty, WriteProperty},
+    },
+    sysex7,
+    ux::{u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x21;
+const PAYLOAD_SIZE: usize = 17;
+
+#[midi2_proc::generate_message(MinSizeUmp(6), MinSizeBytes(19))]
+/// Sent by a MIDI-CI device in response to a
+/// [ProfileInquiry](crate::ci::ProfileInquiry).
+///
+/// Lists the profiles currently enabled and disabled on the device.
+struct ReplyToProfileInquiry {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ProfileListWriteProperty<false>)]
+    #[writeonly]
+    #[resize]
+    enabled_profiles: &[ci::ProfileId],
+    #[property(ProfileListReadProperty<false>)]
+    #[readonly]
+    enabled_profiles: ci::ProfileIdIterator<'_, B::Unit>,
+    #[property(ProfileListWriteProperty<true>)]
+    #[writeonly]
+    #[resize]
+    disabled_profiles: &[ci::ProfileId],
+    #[property(ProfileListReadProperty<true>)]
+    #[readonly]
+    disabled_profiles: ci::ProfileIdIterator<'_, B::Unit>,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for ReplyToProfileInquiry<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+// The enabled list comes first followed by the disabled list.
+// Each list is a two byte count followed by the profile ids.
+
+const ENABLED_PROFILES_INDEX: usize = 13;
+
+fn number_of_profiles<B: Buffer>(buffer: &B, list_index: usize) -> usize {
+    ci::read_length(buffer, list_index, 2)
+}
+
+fn list_index<B: Buffer, const DISABLED: bool>(buffer: &B) -> usize {
+    if DISABLED {
+        ENABLED_PROFILES_INDEX
+            + 2
+            + ci::PROFILE_ID_SIZE * number_of_profiles(buffer, ENABLED_PROFILES_INDEX)
+    } else {
+        ENABLED_PROFILES_INDEX
+    }
+}
+
+fn resize_list<
+    B: Buffer + BufferMut,
+    const DISABLED: bool,
+    ResizePayload: Fn(&mut B, usize) -> Result<(), crate::error::BufferOverflow>,
+>(
+    buffer: &mut B,
+    len: usize,
+    resize_payload: ResizePayload,
+) -> Result<(), crate::error::BufferOverflow> {
+    let index = list_index::<B, DISABLED>(buffer);
+    let end = index + 2 + ci::PROFILE_ID_SIZE * number_of_profiles(buffer, index);
+    let new_end = index + 2 + ci::PROFILE_ID_SIZE * len;
+    ci::resize_field(buffer, end, new_end, resize_payload)
+}
+
+struct ProfileListWriteProperty<'a, const DISABLED: bool>(
+    core::marker::PhantomData<&'a ci::ProfileId>,
+);
+
+impl<'a, const DISABLED: bool, B: Buffer> Property<B> for ProfileListWriteProperty<'a, DISABLED> {
+    type Type = &'a [ci::ProfileId];
+}
+
+impl<'a, const DISABLED: bool, B: Buffer + BufferMut> WriteProperty<B>
+    for ProfileListWriteProperty<'a, DISABLED>
+{
+    fn write(buffer: &mut B, profiles: Self::Type) {
+        let index = list_index::<B, DISABLED>(buffer);
+        ci::write_length(buffer, index, 2, profiles.len());
+        for (i, id) in profiles.iter().enumerate() {
+            let data: [u7; ci::PROFILE_ID_SIZE] = (*id).into();
+            ci::write_u7s(buffer, index + 2 + ci::PROFILE_ID_SIZE * i, &data);
+        }
+    }
+    fn validate(_v: &Self::Type) -> crate::result::Result<()> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        &[]
+    }
+}
+
+impl<'a, const DISABLED: bool, B: Buffer + BufferMut> ResizeProperty<B>
+    for ProfileListWriteProperty<'a, DISABLED>
+{
+    fn resize(buffer: &mut B, value: &Self::Type)
+    where
+        B: BufferResize,
+    {
+        resize_list::<B, DISABLED, _>(buffer, value.len(), ci::resize_payload).unwrap()
+    }
+
+    fn try_resize(buffer: &mut B, value: &Self::Type) -> Result<(), crate::error::BufferOverflow>
+    where
+        B: BufferTryResize,
+    {
+        resize_list::<B, DISABLED, _>(buffer, value.len(), ci::try_resize_payload)
+    }
+}
+
+struct ProfileListReadProperty<'a, const DISABLED: bool>(core::marker::PhantomData<&'a u7>);
+
+impl<'a, const DISABLED: bool, B: 'a + Buffer> Property<B>
+    for ProfileListReadProperty<'a, DISABLED>
+{
+    type Type = ci::ProfileIdIterator<'a, B::Unit>;
+}
+
+impl<'a, const DISABLED: bool, B: 'a + Buffer> ReadProperty<'a, B>
+    for ProfileListReadProperty<'a, DISABLED>
+{
+    fn read(buffer: &'a B) -> Self::Type {
+        let index = list_index::<B, DISABLED>(buffer);
+        ci::ProfileIdIterator::new(buffer, index + 2, number_of_profiles(buffer, index))
+    }
+    fn validate(buffer: &B) -> crate::result::Result<()> {
+        let index = list_index::<B, DISABLED>(buffer);
+        let mut end = index + 2 + ci::PROFILE_ID_SIZE * number_of_profiles(buffer, index);
+        if !DISABLED {
+            // the disabled list count must follow
+            end += 2;
+        }
+        if ci::payload(buffer).len() < end {
+            return Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const ENABLED_PROFILES: [ci::ProfileId; 2] = [
+        ci::ProfileId::Standard {
+            bank: u7::new(0x31),
+            number: u7::new(0x0),
+            version: u7::new(0x1),
+            level: u7::new(0x1),
+        },
+        ci::ProfileId::ManufacturerSpecific {
+            manufacturer: [u7::new(0x00), u7::new(0x21), u7::new(0x09)],
+            data: [u7::new(0x01), u7::new(0x02)],
+        },
+    ];
+
+    const DISABLED_PROFILES: [ci::ProfileId; 1] = [ci::ProfileId::Standard {
+        bank: u7::new(0x31),
+        number: u7::new(0x0),
+        version: u7::new(0x1),
+        level: u7::new(0x2),
+    }];
+
+    const BYTES: [u8; 34] = [
+        0xF0, 0x7E, 0x7F, 0x0D, 0x21, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x02,
+        0x00, 0x7E, 0x31, 0x00, 0x01, 0x01, 0x00, 0x21, 0x09, 0x01, 0x02, 0x01, 0x00, 0x7E, 0x31,
+        0x00, 0x01, 0x02, 0xF7,
+    ];
+
+    const UMP: [u32; 12] = [
+        0x3316_7E7F,
+        0x0D21_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B02,
+        0x007E_3100,
+        0x3326_0101,
+        0x0021_0901,
+        0x3326_0201,
+        0x007E_3100,
+        0x3332_0102,
+        0x0000_0000,
+    ];
+
+    fn build<B: Buffer + BufferMut>(message: &mut ReplyToProfileInquiry<B>) {
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+    }
+
+    #[test]
+    fn new_bytes() {
+        assert_eq!(
+            ReplyToProfileInquiry::<std::vec::Vec<u8>>::new(),
+            ReplyToProfileInquiry(std::vec![
+                0xF0, 0x7E, 0x7F, 0x0D, 0x21, 0x02, 0x00, 0x00, 0x00, 0x00, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x00, 0x00, 0x00, 0x00, 0xF7,
+            ]),
+        );
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            ReplyToProfileInquiry::<std::vec::Vec<u32>>::new(),
+            ReplyToProfileInquiry(std::vec![
+                0x3016_7E7F,
+                0x0D21_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3035_7F00,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = ReplyToProfileInquiry::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        message.set_enabled_profiles(&ENABLED_PROFILES[..]);
+        message.set_disabled_profiles(&DISABLED_PROFILES[..]);
+        assert_eq!(message, ReplyToProfileInquiry(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = ReplyToProfileInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        message.set_enabled_profiles(&ENABLED_PROFILES[..]);
+        message.set_disabled_profiles(&DISABLED_PROFILES[..]);
+        assert_eq!(message, ReplyToProfileInquiry(UMP.to_vec()));
+    }
+
+    #[test]
+    fn builder_disabled_profiles_first_bytes() {
+        let mut message = ReplyToProfileInquiry::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        message.set_disabled_profiles(&DISABLED_PROFILES[..]);
+        message.set_enabled_profiles(&ENABLED_PROFILES[..]);
+        assert_eq!(message, ReplyToProfileInquiry(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_disabled_profiles_first_ump() {
+        let mut message = ReplyToProfileInquiry::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        message.set_disabled_profiles(&DISABLED_PROFILES[..]);
+        message.set_enabled_profiles(&ENABLED_PROFILES[..]);
+        assert_eq!(message, ReplyToProfileInquiry(UMP.to_vec()));
+    }
+
+    #[test]
+    fn shrink_enabled_profiles() {
+        let mut message = ReplyToProfileInquiry::<std::vec::Vec<u32>>::new();
+        build(&mut message);
+        message.set_enabled_profiles(&ENABLED_PROFILES[..]);
+        message.set_disabled_profiles(&DISABLED_PROFILES[..]);
+        message.set_enabled_profiles(&[]);
+        assert_eq!(
+            message,
+            ReplyToProfileInquiry(std::vec![
+                0x3016_7E7F,
+                0x0D21_0267,
+                0x3026_0A0D,
+                0x0921_0615,
+                0x3026_3B00,
+                0x0001_007E,
+                0x3034_3100,
+                0x0102_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn try_set_enabled_profiles_array() {
+        let mut message = ReplyToProfileInquiry::<[u8; 34]>::new();
+        build(&mut message);
+        assert_eq!(
+            message.try_set_disabled_profiles(&DISABLED_PROFILES[..]),
+            Ok(())
+        );
+        assert_eq!(
+            message.try_set_enabled_profiles(&ENABLED_PROFILES[..]),
+            Ok(())
+        );
+        assert_eq!(message, ReplyToProfileInquiry(BYTES));
+    }
+
+    #[test]
+    fn try_set_enabled_profiles_overflow() {
+        let mut message = ReplyToProfileInquiry::<[u8; 30]>::new();
+        build(&mut message);
+        message
+            .try_set_disabled_profiles(&DISABLED_PROFILES[..])
+            .unwrap();
+        let expected = message.clone();
+        assert_eq!(
+            message.try_set_enabled_profiles(&ENABLED_PROFILES[..]),
+            Err(crate::error::BufferOverflow),
+        );
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn enabled_profiles() {
+        let message = ReplyToProfileInquiry::try_from(&BYTES[..]).unwrap();
+        let profiles = message.enabled_profiles();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(
+            profiles.collect::<std::vec::Vec<_>>(),
+            ENABLED_PROFILES.to_vec(),
+        );
+    }
+
+    #[test]
+    fn disabled_profiles() {
+        assert_eq!(
+            ReplyToProfileInquiry::try_from(&UMP[..])
+                .unwrap()
+                .disabled_profiles()
+                .collect::<std::vec::Vec<_>>(),
+            DISABLED_PROFILES.to_vec(),
+        );
+    }
+
+    #[test]
+    fn try_from_profiles_overrun() {
+        let mut buffer = BYTES;
+        buffer[27] = 0x02;
+        assert_eq!(
+            ReplyToProfileInquiry::try_from(&buffer[..]),
+            Err(crate::error::Error::InvalidData(ci::ERR_DATA_OVERRUN)),
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{
+    ci,
+    detail::common_properties,
+    sysex7,
+    ux::{u14, u28, u7},
+};
+
+pub(crate) const SUB_ID_2: u8 = 0x22;
+const PAYLOAD_SIZE: usize = 20;
+
+#[midi2_proc::generate_message(MinSizeUmp(8), MinSizeBytes(22))]
+/// Requests that a MIDI-CI device enables a profile.
+struct SetProfileOn {
+    #[property(common_properties::UmpMessageTypeProperty<{sysex7::UMP_MESSAGE_TYPE}>)]
+    ump_type: (),
+    #[property(ci::CiProperty<SUB_ID_2, PAYLOAD_SIZE>)]
+    ci: (),
+    #[property(sysex7::GroupProperty)]
+    group: crate::ux::u4,
+    #[property(ci::DeviceIdProperty)]
+    device_id: ci::DeviceId,
+    #[property(ci::VersionProperty)]
+    version: u7,
+    #[property(ci::SourceMuidProperty)]
+    source_muid: u28,
+    #[property(ci::DestinationMuidProperty)]
+    destination_muid: u28,
+    #[property(ci::ProfileIdProperty)]
+    profile_id: ci::ProfileId,
+    #[property(ci::U14Property<18>)]
+    number_of_channels: u14,
+    #[property(sysex7::SysexPayloadPlaceholder)]
+    #[readonly]
+    #[writeonly]
+    sysex_payload: (),
+}
+
+impl<B: crate::buffer::Buffer> crate::traits::Size<B> for SetProfileOn<B> {
+    fn size(&self) -> usize {
+        sysex7::size(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use crate::ux::u4;
+    use pretty_assertions::assert_eq;
+
+    const PROFILE_ID: ci::ProfileId = ci::ProfileId::Standard {
+        bank: u7::new(0x31),
+        number: u7::new(0x0),
+        version: u7::new(0x1),
+        level: u7::new(0x1),
+    };
+
+    const BYTES: [u8; 22] = [
+        0xF0, 0x7E, 0x03, 0x0D, 0x22, 0x02, 0x67, 0x0A, 0x0D, 0x09, 0x21, 0x06, 0x15, 0x3B, 0x7E,
+        0x31, 0x00, 0x01, 0x01, 0x10, 0x00, 0xF7,
+    ];
+
+    const UMP: [u32; 8] = [
+        0x3316_7E03,
+        0x0D22_0267,
+        0x3326_0A0D,
+        0x0921_0615,
+        0x3326_3B7E,
+        0x3100_0101,
+        0x3332_1000,
+        0x0000_0000,
+    ];
+
+    fn build<B: crate::buffer::Buffer + crate::buffer::BufferMut>(message: &mut SetProfileOn<B>) {
+        message.set_device_id(ci::DeviceId::Channel(u4::new(0x3)));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        message.set_profile_id(PROFILE_ID);
+        message.set_number_of_channels(u14::new(0x10));
+    }
+
+    #[test]
+    fn new_ump() {
+        assert_eq!(
+            SetProfileOn::<std::vec::Vec<u32>>::new(),
+            SetProfileOn(std::vec![
+                0x3016_7E7F,
+                0x0D22_0200,
+                0x3026_0000,
+                0x007F_7F7F,
+                0x3026_7F7E,
+                0x0000_0000,
+                0x3032_0000,
+                0x0000_0000,
+            ]),
+        );
+    }
+
+    #[test]
+    fn builder_bytes() {
+        let mut message = SetProfileOn::<std::vec::Vec<u8>>::new();
+        build(&mut message);
+        assert_eq!(message, SetProfileOn(BYTES.to_vec()));
+    }
+
+    #[test]
+    fn builder_ump() {
+        let mut message = SetProfileOn::<[u32; 8]>::new();
+        message.set_group(u4::new(0x3));
+        build(&mut message);
+        assert_eq!(message, SetProfileOn(UMP));
+    }
+
+    #[test]
+    fn profile_id() {
+        assert_eq!(
+            SetProfileOn::try_from(&UMP[..]).unwrap().profile_id(),
+            PROFILE_ID,
+        );
+    }
+
+    #[test]
+    fn number_of_channels() {
+        assert_eq!(
+            SetProfileOn::try_from(&BYTES[..])
+                .unwrap()
+                .number_of_channels(),
+            u14::new(0x10),
+        );
+    }
+}
@@ -0,0 +1,847 @@
+use crate::{
+    buffer::{Buffer, BufferDefault, BufferMut, BufferTryResize},
+    ci::{self, Ci},
+    detail::property::{ReadProperty, WriteProperty},
+    error::Error,
+    sysex7,
+    traits::{BufferAccess, Data},
+    ux::{u14, u28, u4, u7},
+};
+
+// the number of outbound messages which can be queued between polls
+const QUEUE_SIZE: usize = 4;
+
+// the payload size of the header shared by all MIDI-CI messages
+const HEADER_SIZE: usize = 13;
+
+// MUIDs from here upwards are reserved
+const MUID_RESERVED: u32 = 0x0FFF_FF00;
+const MUID_MASK: u32 = 0x0FFF_FFFF;
+
+const NAK_MESSAGE_NOT_SUPPORTED: u8 = 0x01;
+
+// the endpoint inquiry status requesting the product instance id
+const ENDPOINT_STATUS_PRODUCT_INSTANCE_ID: u8 = 0x00;
+
+/// A source of random numbers from which the [StateMachine]
+/// generates its MUIDs.
+pub trait Rng {
+    fn next_u32(&mut self) -> u32;
+}
+
+/// Describes the local MIDI-CI device.
+///
+/// The device details are sent in reply to a
+/// [Discovery](crate::ci::Discovery) and an
+/// [EndpointInquiry](crate::ci::EndpointInquiry). The supported categories
+/// determine which inbound inquiries are rejected with a
+/// [Nak](crate::ci::Nak).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub group: u4,
+    pub device_manufacturer: [u7; 3],
+    pub device_family: u14,
+    pub device_family_model_number: u14,
+    pub software_version: [u7; 4],
+    pub supports_profile_configuration: bool,
+    pub supports_property_exchange: bool,
+    pub supports_process_inquiry: bool,
+    pub max_sysex_size: u28,
+    pub function_block: u7,
+    /// Sent in reply to an endpoint inquiry for the product instance id.
+    pub product_instance_id: &'static [u7],
+    /// How long to wait for replies to a discovery, in milliseconds.
+    pub discovery_timeout: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            group: u4::new(0x0),
+            device_manufacturer: Default::default(),
+            device_family: u14::new(0x0),
+            device_family_model_number: u14::new(0x0),
+            software_version: Default::default(),
+            supports_profile_configuration: false,
+            supports_property_exchange: false,
+            supports_process_inquiry: false,
+            max_sysex_size: u28::new(512),
+            function_block: u7::new(0x7F),
+            product_instance_id: &[],
+            discovery_timeout: 3000,
+        }
+    }
+}
+
+/// A remote MIDI-CI device found through discovery.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoteDevice {
+    pub muid: u28,
+    pub device_manufacturer: [u7; 3],
+    pub device_family: u14,
+    pub device_family_model_number: u14,
+    pub software_version: [u7; 4],
+    pub supports_profile_configuration: bool,
+    pub supports_property_exchange: bool,
+    pub supports_process_inquiry: bool,
+    pub max_sysex_size: u28,
+}
+
+macro_rules! remote_device {
+    ($message:expr) => {
+        RemoteDevice {
+            muid: $message.source_muid(),
+            device_manufacturer: $message.device_manufacturer(),
+            device_family: $message.device_family(),
+            device_family_model_number: $message.device_family_model_number(),
+            software_version: $message.software_version(),
+            supports_profile_configuration: $message.supports_profile_configuration(),
+            supports_property_exchange: $message.supports_property_exchange(),
+            supports_process_inquiry: $message.supports_process_inquiry(),
+            max_sysex_size: $message.max_sysex_size(),
+        }
+    };
+}
+
+/// Notable changes in the state of a [StateMachine].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A remote device was added to the table of remote devices.
+    DeviceDiscovered(u28),
+    /// A remote device was removed after its MUID was invalidated.
+    DeviceRemoved(u28),
+    /// The local MUID was regenerated after a collision
+    /// or an invalidation.
+    MuidChanged { old: u28, new: u28 },
+    /// The discovery timeout has elapsed.
+    DiscoveryComplete,
+    /// A message in a supported category which is left
+    /// for the caller to handle.
+    Unhandled,
+}
+
+/// Handles the management side of MIDI-CI for a local device.
+///
+/// Inbound messages are fed in with [handle](StateMachine::handle)
+/// and the resulting replies are drained with [poll](StateMachine::poll).
+/// The state machine
+/// - generates a random MUID with the supplied [Rng],
+/// - replies to [Discovery](crate::ci::Discovery) and
+///   [EndpointInquiry](crate::ci::EndpointInquiry) messages,
+/// - keeps a table of the remote devices it discovers,
+/// - regenerates its MUID and sends an
+///   [InvalidateMuid](crate::ci::InvalidateMuid) on a MUID collision,
+/// - rejects inquiries from unsupported categories with a [Nak](crate::ci::Nak).
+///
+/// The state machine doesn't read the time itself. The caller passes
+/// the current time, in milliseconds from any fixed origin, to
+/// [discover](StateMachine::discover) and [tick](StateMachine::tick),
+/// which reports elapsed timeouts.
+///
+/// Outbound messages are backed by buffers of type `B`.
+/// With fixed size arrays the state machine doesn't allocate.
+/// At most `MAX_REMOTE_DEVICES` remote devices are remembered.
+#[derive(Debug)]
+pub struct StateMachine<
+    B: Buffer + BufferMut + BufferDefault + BufferTryResize,
+    R: Rng,
+    const MAX_REMOTE_DEVICES: usize,
+> {
+    config: Config,
+    rng: R,
+    muid: u28,
+    remote_devices: [RemoteDevice; MAX_REMOTE_DEVICES],
+    number_of_remote_devices: usize,
+    discovery_deadline: Option<u64>,
+    queue: [Option<Ci<B>>; QUEUE_SIZE],
+    queue_head: usize,
+    queue_len: usize,
+}
+
+impl<
+        B: Buffer + BufferMut + BufferDefault + BufferTryResize,
+        R: Rng,
+        const MAX_REMOTE_DEVICES: usize,
+    > StateMachine<B, R, MAX_REMOTE_DEVICES>
+{
+    pub fn new(config: Config, rng: R) -> Self {
+        let mut state_machine = StateMachine {
+            config,
+            rng,
+            muid: ci::BROADCAST_MUID,
+            remote_devices: [RemoteDevice::default(); MAX_REMOTE_DEVICES],
+            number_of_remote_devices: 0,
+            discovery_deadline: None,
+            queue: core::array::from_fn(|_| None),
+            queue_head: 0,
+            queue_len: 0,
+        };
+        state_machine.muid = state_machine.generate_muid();
+        state_machine
+    }
+
+    pub fn muid(&self) -> u28 {
+        self.muid
+    }
+
+    pub fn remote_devices(&self) -> &[RemoteDevice] {
+        &self.remote_devices[..self.number_of_remote_devices]
+    }
+
+    /// Broadcast a [Discovery](crate::ci::Discovery) at time `now`.
+    ///
+    /// [tick](StateMachine::tick) reports [Event::DiscoveryComplete]
+    /// once the discovery timeout has elapsed.
+    pub fn discover(&mut self, now: u64) -> Result<(), Error> {
+        self.queue_discovery()?;
+        self.discovery_deadline = Some(now.saturating_add(self.config.discovery_timeout));
+        Ok(())
+    }
+
+    /// Check for timeouts which have elapsed by time `now`.
+    pub fn tick(&mut self, now: u64) -> Option<Event> {
+        match self.discovery_deadline {
+            Some(deadline) if now >= deadline => {
+                self.discovery_deadline = None;
+                Some(Event::DiscoveryComplete)
+            }
+            _ => None,
+        }
+    }
+
+    /// Take the next outbound message.
+    pub fn poll(&mut self) -> Option<Ci<B>> {
+        if self.queue_len == 0 {
+            return None;
+        }
+        let message = self.queue[self.queue_head].take();
+        self.queue_head = (self.queue_head + 1) % QUEUE_SIZE;
+        self.queue_len -= 1;
+        message
+    }
+
+    /// Process an inbound message.
+    ///
+    /// Sysex messages which aren't MIDI-CI and messages addressed to
+    /// other devices are ignored. Replies and reports in unsupported
+    /// categories are dropped without a [Nak](crate::ci::Nak).
+    /// Fails with [Error::BufferOverflow] when the outbound queue
+    /// or the table of remote devices is full. The outbound queue
+    /// should be drained with [poll](StateMachine::poll) after each call.
+    pub fn handle<I: Buffer>(
+        &mut self,
+        message: &sysex7::Sysex7<I>,
+    ) -> Result<Option<Event>, Error> {
+        use crate::traits::Sysex;
+
+        let mut payload = message.payload().map(u8::from);
+        if payload.next() != Some(ci::UNIVERSAL_SYSEX)
+            || payload.nth(1) != Some(ci::UNIVERSAL_SYSEX_SUB_ID_MIDI_CI)
+        {
+            return Ok(None);
+        }
+
+        let data = message.data();
+        let message = match Ci::try_from(data) {
+            Ok(message) => Some(message),
+            // a MIDI-CI message we don't know about
+            Err(Error::InvalidData(ci::ERR_INCORRECT_SUB_ID_2)) => None,
+            Err(error) => return Err(error),
+        };
+
+        let mut payload = ci::payload(&data);
+        if payload.len() < HEADER_SIZE {
+            return Err(Error::InvalidData(ci::ERR_PAYLOAD_TOO_SHORT));
+        }
+        let sub_id_2 = payload.nth(ci::SUB_ID_2_INDEX).unwrap();
+        let source = <ci::SourceMuidProperty as ReadProperty<&[I::Unit]>>::read(&data);
+        let destination = <ci::DestinationMuidProperty as ReadProperty<&[I::Unit]>>::read(&data);
+
+        if destination != self.muid && destination != ci::BROADCAST_MUID {
+            return Ok(None);
+        }
+        if source == self.muid {
+            return self.change_muid();
+        }
+
+        match message {
+            Some(Ci::Discovery(message)) => {
+                self.reply_to_discovery(&message)?;
+                self.insert_remote_device(remote_device!(message))
+            }
+            Some(Ci::ReplyToDiscovery(message)) => {
+                self.insert_remote_device(remote_device!(message))
+            }
+            Some(Ci::EndpointInquiry(message)) => {
+                self.reply_to_endpoint_inquiry(&message)?;
+                Ok(None)
+            }
+            Some(Ci::InvalidateMuid(message)) if message.target_muid() == self.muid => {
+                self.change_muid()
+            }
+            Some(Ci::InvalidateMuid(message)) => Ok(self
+                .remove_remote_device(message.target_muid())
+                .then_some(Event::DeviceRemoved(message.target_muid()))),
+            Some(_) if self.supports(u8::from(sub_id_2)) => Ok(Some(Event::Unhandled)),
+            Some(message) if !is_inquiry(&message) => Ok(None),
+            // unknown messages are rejected too
+            _ => {
+                let device_id = <ci::DeviceIdProperty as ReadProperty<&[I::Unit]>>::read(&data);
+                self.queue_nak(source, device_id, sub_id_2)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn supports(&self, sub_id_2: u8) -> bool {
+        match sub_id_2 {
+            0x20..=0x2F => self.config.supports_profile_configuration,
+            0x30..=0x3F => self.config.supports_property_exchange,
+            0x40..=0x4F => self.config.supports_process_inquiry,
+            0x70..=0x7F => true,
+            _ => false,
+        }
+    }
+
+    // pick a MUID which is neither reserved, the current MUID
+    // nor in use by a known remote device
+    fn generate_muid(&mut self) -> u28 {
+        loop {
+            let muid = self.rng.next_u32() & MUID_MASK;
+            if muid < MUID_RESERVED
+                && muid != u32::from(self.muid)
+                && !self
+                    .remote_devices()
+                    .iter()
+                    .any(|device| u32::from(device.muid) == muid)
+            {
+                return u28::new(muid);
+            }
+        }
+    }
+
+    fn change_muid(&mut self) -> Result<Option<Event>, Error> {
+        let old = self.muid;
+        self.muid = self.generate_muid();
+
+        let mut message = ci::InvalidateMuid::<B>::try_new()?;
+        self.address(&mut message, ci::BROADCAST_MUID);
+        message.set_target_muid(old);
+        self.push(message.into())?;
+
+        if self.discovery_deadline.is_some() {
+            self.queue_discovery()?;
+        }
+
+        Ok(Some(Event::MuidChanged {
+            old,
+            new: self.muid,
+        }))
+    }
+
+    fn queue_discovery(&mut self) -> Result<(), Error> {
+        let mut message = ci::Discovery::<B>::try_new()?;
+        self.address(&mut message, ci::BROADCAST_MUID);
+        message.set_device_manufacturer(self.config.device_manufacturer);
+        message.set_device_family(self.config.device_family);
+        message.set_device_family_model_number(self.config.device_family_model_number);
+        message.set_software_version(self.config.software_version);
+        message.set_supports_profile_configuration(self.config.supports_profile_configuration);
+        message.set_supports_property_exchange(self.config.supports_property_exchange);
+        message.set_supports_process_inquiry(self.config.supports_process_inquiry);
+        message.set_max_sysex_size(self.config.max_sysex_size);
+        self.push(message.into())
+    }
+
+    fn reply_to_discovery<I: Buffer>(&mut self, discovery: &ci::Discovery<I>) -> Result<(), Error> {
+        let mut message = ci::ReplyToDiscovery::<B>::try_new()?;
+        self.address(&mut message, discovery.source_muid());
+        message.set_device_manufacturer(self.config.device_manufacturer);
+        message.set_device_family(self.config.device_family);
+        message.set_device_family_model_number(self.config.device_family_model_number);
+        message.set_software_version(self.config.software_version);
+        message.set_supports_profile_configuration(self.config.supports_profile_configuration);
+        message.set_supports_property_exchange(self.config.supports_property_exchange);
+        message.set_supports_process_inquiry(self.config.supports_process_inquiry);
+        message.set_max_sysex_size(self.config.max_sysex_size);
+        message.set_output_path_id(discovery.output_path_id());
+        message.set_function_block(self.config.function_block);
+        self.push(message.into())
+    }
+
+    fn reply_to_endpoint_inquiry<I: Buffer>(
+        &mut self,
+        inquiry: &ci::EndpointInquiry<I>,
+    ) -> Result<(), Error> {
+        let mut message = ci::ReplyToEndpointInquiry::<B>::try_new()?;
+        self.address(&mut message, inquiry.source_muid());
+        message.set_status(inquiry.status());
+        // other statuses are reserved, so they're answered without data
+        if u8::from(inquiry.status()) == ENDPOINT_STATUS_PRODUCT_INSTANCE_ID {
+            message.try_set_information_data(self.config.product_instance_id)?;
+        }
+        self.push(message.into())
+    }
+
+    fn queue_nak(
+        &mut self,
+        destination: u28,
+        device_id: ci::DeviceId,
+        original_sub_id_2: u7,
+    ) -> Result<(), Error> {
+        let mut message = ci::Nak::<B>::try_new()?;
+        self.address(&mut message, destination);
+        message.set_device_id(device_id);
+        message.set_original_sub_id_2(original_sub_id_2);
+        message.set_status_code(u7::new(NAK_MESSAGE_NOT_SUPPORTED));
+        self.push(message.into())
+    }
+
+    // write the group and muids common to all outbound messages
+    fn address<M: BufferAccess<B>>(&self, message: &mut M, destination: u28) {
+        let buffer = message.buffer_access_mut();
+        <sysex7::GroupProperty as WriteProperty<B>>::write(buffer, self.config.group);
+        <ci::SourceMuidProperty as WriteProperty<B>>::write(buffer, self.muid);
+        <ci::DestinationMuidProperty as WriteProperty<B>>::write(buffer, destination);
+    }
+
+    fn push(&mut self, message: Ci<B>) -> Result<(), Error> {
+        if self.queue_len == QUEUE_SIZE {
+            return Err(Error::BufferOverflow);
+        }
+        self.queue[(self.queue_head + self.queue_len) % QUEUE_SIZE] = Some(message);
+        self.queue_len += 1;
+        Ok(())
+    }
+
+    fn insert_remote_device(&mut self, device: RemoteDevice) -> Result<Option<Event>, Error> {
+        let number_of_remote_devices = self.number_of_remote_devices;
+        if let Some(existing) = self.remote_devices[..number_of_remote_devices]
+            .iter_mut()
+            .find(|existing| existing.muid == device.muid)
+        {
+            *existing = device;
+            return Ok(None);
+        }
+        if number_of_remote_devices == MAX_REMOTE_DEVICES {
+            return Err(Error::BufferOverflow);
+        }
+        self.remote_devices[number_of_remote_devices] = device;
+        self.number_of_remote_devices += 1;
+        Ok(Some(Event::DeviceDiscovered(device.muid)))
+    }
+
+    // returns true when the device was known
+    fn remove_remote_device(&mut self, muid: u28) -> bool {
+        let Some(index) = self
+            .remote_devices()
+            .iter()
+            .position(|device| device.muid == muid)
+        else {
+            return false;
+        };
+        self.remote_devices
+            .copy_within(index + 1..self.number_of_remote_devices, index);
+        self.number_of_remote_devices -= 1;
+        true
+    }
+}
+
+// the messages which expect a reply
+fn is_inquiry<B: Buffer>(message: &Ci<B>) -> bool {
+    use Ci::*;
+    matches!(
+        message,
+        EndpointInquiry(_)
+            | GetPropertyData(_)
+            | MidiMessageReport(_)
+            | ProcessInquiryCapabilities(_)
+            | ProfileDetailsInquiry(_)
+            | ProfileInquiry(_)
+            | PropertyExchangeCapabilities(_)
+            | SetProfileOff(_)
+            | SetProfileOn(_)
+            | SetPropertyData(_)
+            | Subscription(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use pretty_assertions::assert_eq;
+
+    struct TestRng(u32);
+
+    impl Rng for TestRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0 = self.0.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            self.0
+        }
+    }
+
+    type TestStateMachine = StateMachine<[u32; 16], TestRng, 4>;
+
+    fn state_machine(seed: u32) -> TestStateMachine {
+        StateMachine::new(
+            Config {
+                device_family: u14::new(seed as u16),
+                ..Default::default()
+            },
+            TestRng(seed),
+        )
+    }
+
+    // deliver every queued message from one state machine to the other
+    fn deliver(from: &mut TestStateMachine, to: &mut TestStateMachine) -> std::vec::Vec<Event> {
+        let mut events = std::vec::Vec::new();
+        while let Some(message) = from.poll() {
+            let message = sysex7::Sysex7::try_from(message.data()).unwrap();
+            if let Some(event) = to.handle(&message).unwrap() {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn handle(state_machine: &mut TestStateMachine, data: &[u32]) -> Option<Event> {
+        state_machine
+            .handle(&sysex7::Sysex7::try_from(data).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn muid() {
+        let muid = u32::from(state_machine(1).muid());
+        assert!(muid < MUID_RESERVED);
+    }
+
+    #[test]
+    fn discovery() {
+        let mut initiator = state_machine(1);
+        let mut responder = state_machine(2);
+
+        initiator.discover(0).unwrap();
+        assert_eq!(
+            deliver(&mut initiator, &mut responder),
+            std::vec![Event::DeviceDiscovered(initiator.muid())],
+        );
+        assert_eq!(
+            deliver(&mut responder, &mut initiator),
+            std::vec![Event::DeviceDiscovered(responder.muid())],
+        );
+
+        assert_eq!(initiator.remote_devices().len(), 1);
+        assert_eq!(initiator.remote_devices()[0].muid, responder.muid());
+        assert_eq!(initiator.remote_devices()[0].device_family, u14::new(2));
+        assert_eq!(responder.remote_devices().len(), 1);
+        assert_eq!(responder.remote_devices()[0].muid, initiator.muid());
+        assert_eq!(responder.remote_devices()[0].device_family, u14::new(1));
+    }
+
+    #[test]
+    fn rediscovery() {
+        let mut initiator = state_machine(1);
+        let mut responder = state_machine(2);
+
+        initiator.discover(0).unwrap();
+        deliver(&mut initiator, &mut responder);
+        deliver(&mut responder, &mut initiator);
+        initiator.discover(0).unwrap();
+        assert_eq!(deliver(&mut initiator, &mut responder), std::vec![]);
+        assert_eq!(deliver(&mut responder, &mut initiator), std::vec![]);
+        assert_eq!(initiator.remote_devices().len(), 1);
+    }
+
+    #[test]
+    fn discovery_timeout() {
+        let mut initiator = state_machine(1);
+
+        initiator.discover(100).unwrap();
+        assert_eq!(initiator.tick(100), None);
+        assert_eq!(initiator.tick(3099), None);
+        assert_eq!(initiator.tick(3100), Some(Event::DiscoveryComplete));
+        assert_eq!(initiator.tick(3200), None);
+    }
+
+    #[test]
+    fn muid_collision() {
+        let mut initiator = state_machine(1);
+        let mut responder = state_machine(1);
+        let muid = initiator.muid();
+        assert_eq!(responder.muid(), muid);
+        // the next muids must differ
+        responder.rng = TestRng(2);
+
+        initiator.discover(0).unwrap();
+        assert_eq!(
+            deliver(&mut initiator, &mut responder),
+            std::vec![Event::MuidChanged {
+                old: muid,
+                new: responder.muid(),
+            }],
+        );
+        assert_ne!(responder.muid(), muid);
+
+        // the initiator is told to forget its muid
+        match responder.poll() {
+            Some(Ci::InvalidateMuid(message)) => {
+                assert_eq!(message.source_muid(), responder.muid());
+                assert_eq!(message.destination_muid(), ci::BROADCAST_MUID);
+                assert_eq!(message.target_muid(), muid);
+                handle(&mut initiator, message.data());
+            }
+            _ => panic!(),
+        }
+        assert_ne!(initiator.muid(), muid);
+        assert_ne!(initiator.muid(), responder.muid());
+
+        // the initiator invalidates its old muid and rediscovers
+        loop {
+            let events = deliver(&mut initiator, &mut responder);
+            deliver(&mut responder, &mut initiator);
+            if events.is_empty() {
+                break;
+            }
+        }
+        assert_eq!(initiator.remote_devices().len(), 1);
+        assert_eq!(initiator.remote_devices()[0].muid, responder.muid());
+        assert_eq!(responder.remote_devices().len(), 1);
+        assert_eq!(responder.remote_devices()[0].muid, initiator.muid());
+    }
+
+    #[test]
+    fn invalidate_remote_device() {
+        let mut initiator = state_machine(1);
+        let mut responder = state_machine(2);
+
+        initiator.discover(0).unwrap();
+        deliver(&mut initiator, &mut responder);
+
+        let mut message = ci::InvalidateMuid::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_target_muid(initiator.muid());
+        assert_eq!(
+            handle(&mut responder, message.data()),
+            Some(Event::DeviceRemoved(initiator.muid())),
+        );
+        assert_eq!(responder.remote_devices(), &[]);
+        assert_eq!(handle(&mut responder, message.data()), None);
+    }
+
+    #[test]
+    fn endpoint_inquiry() {
+        const PRODUCT_INSTANCE_ID: [u7; 3] = [u7::new(0x10), u7::new(0x11), u7::new(0x12)];
+        let mut initiator = state_machine(1);
+        let mut responder = StateMachine::<[u32; 16], _, 4>::new(
+            Config {
+                product_instance_id: &PRODUCT_INSTANCE_ID,
+                ..Default::default()
+            },
+            TestRng(2),
+        );
+
+        let mut message = ci::EndpointInquiry::<[u32; 16]>::new();
+        message.set_source_muid(initiator.muid());
+        message.set_destination_muid(responder.muid());
+        initiator.push(message.into()).unwrap();
+        assert_eq!(deliver(&mut initiator, &mut responder), std::vec![]);
+
+        match responder.poll() {
+            Some(Ci::ReplyToEndpointInquiry(message)) => {
+                assert_eq!(message.source_muid(), responder.muid());
+                assert_eq!(message.destination_muid(), initiator.muid());
+                assert_eq!(message.status(), u7::new(0x00));
+                assert_eq!(
+                    message.information_data().collect::<std::vec::Vec<u7>>(),
+                    PRODUCT_INSTANCE_ID,
+                );
+                // the reply makes its way back to the initiator
+                assert_eq!(
+                    handle(&mut initiator, message.data()),
+                    Some(Event::Unhandled)
+                );
+            }
+            _ => panic!(),
+        }
+        assert!(responder.poll().is_none());
+    }
+
+    #[test]
+    fn endpoint_inquiry_reserved_status() {
+        const PRODUCT_INSTANCE_ID: [u7; 1] = [u7::new(0x10)];
+        let mut responder = StateMachine::<[u32; 16], _, 4>::new(
+            Config {
+                product_instance_id: &PRODUCT_INSTANCE_ID,
+                ..Default::default()
+            },
+            TestRng(2),
+        );
+
+        let mut message = ci::EndpointInquiry::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(responder.muid());
+        message.set_status(u7::new(0x01));
+        assert_eq!(handle(&mut responder, message.data()), None);
+
+        match responder.poll() {
+            Some(Ci::ReplyToEndpointInquiry(message)) => {
+                assert_eq!(message.status(), u7::new(0x01));
+                assert_eq!(message.information_data().count(), 0);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn ignores_messages_for_other_devices() {
+        let mut responder = state_machine(2);
+
+        let mut message = ci::GetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(u28::new(0x0765_4321));
+        assert_eq!(handle(&mut responder, message.data()), None);
+        assert!(responder.poll().is_none());
+    }
+
+    #[test]
+    fn nak_unsupported_category() {
+        let mut responder = state_machine(2);
+
+        let mut message = ci::GetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_group(u4::new(0x3));
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(responder.muid());
+        assert_eq!(handle(&mut responder, message.data()), None);
+
+        match responder.poll() {
+            Some(Ci::Nak(message)) => {
+                assert_eq!(message.source_muid(), responder.muid());
+                assert_eq!(message.destination_muid(), u28::new(0x0123_4567));
+                assert_eq!(message.original_sub_id_2(), u7::new(0x34));
+                assert_eq!(message.status_code(), u7::new(0x01));
+            }
+            _ => panic!(),
+        }
+        assert!(responder.poll().is_none());
+    }
+
+    #[test]
+    fn nak_unknown_message() {
+        let mut responder = state_machine(2);
+
+        let muid = u32::from(responder.muid());
+        let buffer = [
+            0xF0,
+            0x7E,
+            0x7F,
+            0x0D,
+            0x10,
+            0x02,
+            0x67,
+            0x0A,
+            0x0D,
+            0x09,
+            (muid & 0x7F) as u8,
+            ((muid >> 7) & 0x7F) as u8,
+            ((muid >> 14) & 0x7F) as u8,
+            ((muid >> 21) & 0x7F) as u8,
+            0xF7,
+        ];
+        assert_eq!(
+            responder.handle(&sysex7::Sysex7::try_from(&buffer[..]).unwrap()),
+            Ok(None),
+        );
+        match responder.poll() {
+            Some(Ci::Nak(message)) => {
+                assert_eq!(message.destination_muid(), u28::new(0x0123_4567));
+                assert_eq!(message.original_sub_id_2(), u7::new(0x10));
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn no_nak_for_reply_in_unsupported_category() {
+        let mut responder = state_machine(2);
+
+        let mut message = ci::ReplyToGetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(responder.muid());
+        assert_eq!(handle(&mut responder, message.data()), None);
+        assert!(responder.poll().is_none());
+    }
+
+    #[test]
+    fn ignores_other_sysex() {
+        let mut responder = state_machine(2);
+
+        for buffer in [
+            &[0xF0_u8, 0x43, 0x10, 0x4C, 0x00, 0x00, 0x7E, 0x00, 0xF7][..],
+            &[0xF0, 0x7E, 0x7F, 0x06, 0x01, 0xF7][..],
+            &[0xF0, 0xF7][..],
+        ] {
+            assert_eq!(
+                responder.handle(&sysex7::Sysex7::try_from(buffer).unwrap()),
+                Ok(None),
+            );
+        }
+        assert!(responder.poll().is_none());
+    }
+
+    #[test]
+    fn supported_category_is_unhandled() {
+        let mut responder = StateMachine::<[u32; 16], _, 4>::new(
+            Config {
+                supports_property_exchange: true,
+                ..Default::default()
+            },
+            TestRng(2),
+        );
+
+        let mut message = ci::GetPropertyData::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        message.set_destination_muid(responder.muid());
+        assert_eq!(
+            handle(&mut responder, message.data()),
+            Some(Event::Unhandled)
+        );
+        assert!(responder.poll().is_none());
+    }
+
+    #[test]
+    fn remote_device_table_full() {
+        let mut responder = StateMachine::<[u32; 16], _, 1>::new(Config::default(), TestRng(2));
+
+        let mut message = ci::Discovery::<std::vec::Vec<u32>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        assert_eq!(
+            responder.handle(&sysex7::Sysex7::try_from(message.data()).unwrap()),
+            Ok(Some(Event::DeviceDiscovered(u28::new(0x0123_4567)))),
+        );
+        message.set_source_muid(u28::new(0x0765_4321));
+        assert_eq!(
+            responder.handle(&sysex7::Sysex7::try_from(message.data()).unwrap()),
+            Err(Error::BufferOverflow),
+        );
+    }
+
+    #[test]
+    fn bytes_buffer() {
+        let mut responder = StateMachine::<[u8; 64], _, 4>::new(Config::default(), TestRng(2));
+
+        let mut message = ci::Discovery::<std::vec::Vec<u8>>::new();
+        message.set_source_muid(u28::new(0x0123_4567));
+        responder
+            .handle(&sysex7::Sysex7::try_from(message.data()).unwrap())
+            .unwrap();
+        match responder.poll() {
+            Some(Ci::ReplyToDiscovery(message)) => {
+                assert_eq!(message.destination_muid(), u28::new(0x0123_4567));
+                assert_eq!(message.function_block(), u7::new(0x7F));
+            }
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,627 @@
+//! Reading and writing MIDI Clip Files.
+//!
+//! A clip file holds a sequence of ump messages timed in ticks.
+//! After the `SMF2CLIP` file header comes the clip configuration header,
+//! which sets the resolution of the clip with a
+//! [DeltaClockstampTPQ](crate::utility::DeltaClockstampTPQ) message and
+//! may carry other setup messages such as the initial tempo and time
+//! signature, all at tick zero. The sequence data follows, bracketed by
+//! [StartOfClip](crate::ump_stream::StartOfClip) and
+//! [EndOfClip](crate::ump_stream::EndOfClip) messages, with each message
+//! timed by the preceding [DeltaClockstamp](crate::utility::DeltaClockstamp)
+//! messages.
+//!
+//! A [ClipFile] stores the messages of the sequence along with their
+//! absolute time in ticks.
+//!
+//! ```rust
+//! use midi2::{prelude::*, clip_file::ClipFile, BufferSink};
+//!
+//! let mut clip = ClipFile::default();
+//! clip.ticks_per_quarter_note = 480;
+//!
+//! let mut tempo = flex_data::SetTempo::<Vec<u32>>::new();
+//! tempo.set_number_of_10_nanosecond_units_per_quarter_note(50_000);
+//! clip.set_tempo(tempo);
+//!
+//! let mut note_on = channel_voice2::NoteOn::<Vec<u32>>::new();
+//! note_on.set_note(u7::new(0x3C));
+//! note_on.set_velocity(0xFFFF);
+//! clip.events.push((480, UmpMessage::ChannelVoice2(note_on.into())));
+//! clip.length = 960;
+//!
+//! let mut sink = BufferSink::new(Vec::new());
+//! clip.write(&mut sink).unwrap();
+//! assert_eq!(&sink.data()[..8], b"SMF2CLIP");
+//!
+//! let read = ClipFile::parse(sink.data()).unwrap();
+//! assert_eq!(read, clip);
+//! assert_eq!(
+//!     read.tempo().unwrap().number_of_10_nanosecond_units_per_quarter_note(),
+//!     50_000,
+//! );
+//! ```
+
+use crate::{
+    error::{BufferOverflow, ClipFileError, Error},
+    flex_data::{FlexData, SetTempo, SetTimeSignature, TempoMap},
+    message::UmpMessage,
+    stream::{self, BytesSink, UmpStreamIter},
+    traits::{Data, RebufferInto},
+    ump_stream::{EndOfClip, StartOfClip, UmpStream},
+    utility::{DeltaClockstamp, DeltaClockstampTPQ, Utility},
+};
+use std::vec::Vec;
+
+const FILE_HEADER: &[u8; 8] = b"SMF2CLIP";
+
+const ERR_NOT_CLIP_FILE: &str = "Missing the SMF2CLIP file header";
+const ERR_TRUNCATED_WORD: &str = "File ends part way through a ump word";
+const ERR_MISSING_TICKS_PER_QUARTER_NOTE: &str =
+    "Clip configuration header doesn't begin with a DeltaClockstampTPQ message";
+const ERR_UNEXPECTED_TICKS_PER_QUARTER_NOTE: &str =
+    "DeltaClockstampTPQ message outside the start of the clip configuration header";
+const ERR_DELAYED_CONFIG: &str =
+    "DeltaClockstamp with a non-zero delta in the clip configuration header";
+const ERR_MISSING_START_OF_CLIP: &str = "File contains no StartOfClip message";
+const ERR_UNEXPECTED_START_OF_CLIP: &str = "StartOfClip message within the clip sequence data";
+const ERR_MISSING_END_OF_CLIP: &str = "File ends before the EndOfClip message";
+const ERR_UNEXPECTED_END_OF_CLIP: &str = "EndOfClip message before the StartOfClip message";
+const ERR_DATA_AFTER_END_OF_CLIP: &str = "Data follows the EndOfClip message";
+
+// the largest delta a single DeltaClockstamp message can carry
+const MAX_DELTA: u64 = u16::MAX as u64;
+
+/// The contents of a MIDI Clip File.
+///
+/// Events are expected to be in time order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClipFile {
+    pub ticks_per_quarter_note: u16,
+    /// The messages of the clip configuration header.
+    pub config: Vec<UmpMessage<Vec<u32>>>,
+    /// The messages of the clip sequence data with their time in ticks.
+    pub events: Vec<(u64, UmpMessage<Vec<u32>>)>,
+    /// The time in ticks of the end of the clip.
+    ///
+    /// When writing this is extended out to the last event if needed.
+    pub length: u64,
+}
+
+impl Default for ClipFile {
+    fn default() -> Self {
+        ClipFile {
+            ticks_per_quarter_note: 96,
+            config: Vec::new(),
+            events: Vec::new(),
+            length: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Config,
+    Sequence,
+    Ended,
+}
+
+impl ClipFile {
+    pub fn parse(data: &[u8]) -> Result<Self, ClipFileError> {
+        let Some(body) = data.strip_prefix(&FILE_HEADER[..]) else {
+            return Err(error(0, ERR_NOT_CLIP_FILE));
+        };
+        if body.len() % 4 != 0 {
+            return Err(error(data.len() - body.len() % 4, ERR_TRUNCATED_WORD));
+        }
+        let words: Vec<u32> = body
+            .chunks_exact(4)
+            .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let byte_offset = |word_offset: usize| FILE_HEADER.len() + 4 * word_offset;
+
+        let mut ticks_per_quarter_note = None;
+        let mut config = Vec::new();
+        let mut events = Vec::new();
+        let mut ticks = 0_u64;
+        let mut section = Section::Config;
+
+        let mut iter = UmpStreamIter::new(&words[..]);
+        loop {
+            let offset = byte_offset(iter.offset());
+            let Some(message) = iter.next() else {
+                break;
+            };
+            let message = message.map_err(|e| ClipFileError {
+                offset: byte_offset(e.offset),
+                error: e.error,
+            })?;
+
+            use Section::*;
+            match (section, message) {
+                (Ended, _) => return Err(error(offset, ERR_DATA_AFTER_END_OF_CLIP)),
+                (Config, UmpMessage::Utility(Utility::DeltaClockstampTpq(m)))
+                    if ticks_per_quarter_note.is_none() =>
+                {
+                    ticks_per_quarter_note = Some(m.time_data());
+                }
+                _ if ticks_per_quarter_note.is_none() => {
+                    return Err(error(offset, ERR_MISSING_TICKS_PER_QUARTER_NOTE));
+                }
+                (_, UmpMessage::Utility(Utility::DeltaClockstampTpq(_))) => {
+                    return Err(error(offset, ERR_UNEXPECTED_TICKS_PER_QUARTER_NOTE));
+                }
+                // the configuration messages all sit at the start of the clip
+                (Config, UmpMessage::Utility(Utility::DeltaClockstamp(m)))
+                    if m.time_data() != 0 =>
+                {
+                    return Err(error(offset, ERR_DELAYED_CONFIG));
+                }
+                (Config, UmpMessage::Utility(Utility::DeltaClockstamp(_))) => {}
+                (Config, UmpMessage::UmpStream(UmpStream::StartOfClip(_))) => {
+                    section = Sequence;
+                }
+                (Config, UmpMessage::UmpStream(UmpStream::EndOfClip(_))) => {
+                    return Err(error(offset, ERR_UNEXPECTED_END_OF_CLIP));
+                }
+                (Config, m) => config.push(m.rebuffer_into()),
+                (Sequence, UmpMessage::Utility(Utility::DeltaClockstamp(m))) => {
+                    ticks += u64::from(m.time_data());
+                }
+                (Sequence, UmpMessage::UmpStream(UmpStream::StartOfClip(_))) => {
+                    return Err(error(offset, ERR_UNEXPECTED_START_OF_CLIP));
+                }
+                (Sequence, UmpMessage::UmpStream(UmpStream::EndOfClip(_))) => {
+                    section = Ended;
+                }
+                (Sequence, m) => events.push((ticks, m.rebuffer_into())),
+            }
+        }
+
+        match section {
+            Section::Config if ticks_per_quarter_note.is_none() => {
+                Err(error(data.len(), ERR_MISSING_TICKS_PER_QUARTER_NOTE))
+            }
+            Section::Config => Err(error(data.len(), ERR_MISSING_START_OF_CLIP)),
+            Section::Sequence => Err(error(data.len(), ERR_MISSING_END_OF_CLIP)),
+            Section::Ended => Ok(ClipFile {
+                ticks_per_quarter_note: ticks_per_quarter_note.unwrap_or_default(),
+                config,
+                events,
+                length: ticks,
+            }),
+        }
+    }
+
+    /// Write out the complete file.
+    ///
+    /// Every message is preceded by a delta clockstamp, and deltas which
+    /// don't fit into a single delta clockstamp are split over several.
+    pub fn write<S: BytesSink>(&self, sink: &mut S) -> Result<(), BufferOverflow> {
+        sink.write_bytes(FILE_HEADER)?;
+
+        let mut tpq = DeltaClockstampTPQ::<[u32; 1]>::new();
+        tpq.set_time_data(self.ticks_per_quarter_note);
+        write_message(tpq.data(), sink)?;
+        for message in self.config.iter() {
+            write_delta(0, sink)?;
+            write_message(message.data(), sink)?;
+        }
+
+        write_delta(0, sink)?;
+        write_message(StartOfClip::<[u32; 4]>::new().data(), sink)?;
+
+        let mut ticks = 0;
+        for (time, message) in self.events.iter() {
+            write_delta(time.saturating_sub(ticks), sink)?;
+            write_message(message.data(), sink)?;
+            ticks = ticks.max(*time);
+        }
+
+        write_delta(self.length.saturating_sub(ticks), sink)?;
+        write_message(EndOfClip::<[u32; 4]>::new().data(), sink)
+    }
+
+    /// The tempo set in the clip configuration header.
+    pub fn tempo(&self) -> Option<&SetTempo<Vec<u32>>> {
+        self.config.iter().find_map(|m| match m {
+            UmpMessage::FlexData(FlexData::SetTempo(m)) => Some(m),
+            _ => None,
+        })
+    }
+
+    /// Set the tempo in the clip configuration header,
+    /// replacing any existing tempo message.
+    pub fn set_tempo(&mut self, tempo: SetTempo<Vec<u32>>) {
+        self.replace_config(UmpMessage::FlexData(tempo.into()), |m| {
+            matches!(m, UmpMessage::FlexData(FlexData::SetTempo(_)))
+        });
+    }
+
+    /// The time signature set in the clip configuration header.
+    pub fn time_signature(&self) -> Option<&SetTimeSignature<Vec<u32>>> {
+        self.config.iter().find_map(|m| match m {
+            UmpMessage::FlexData(FlexData::SetTimeSignature(m)) => Some(m),
+            _ => None,
+        })
+    }
+
+    /// Set the time signature in the clip configuration header,
+    /// replacing any existing time signature message.
+    pub fn set_time_signature(&mut self, time_signature: SetTimeSignature<Vec<u32>>) {
+        self.replace_config(UmpMessage::FlexData(time_signature.into()), |m| {
+            matches!(m, UmpMessage::FlexData(FlexData::SetTimeSignature(_)))
+        });
+    }
+
+    /// A [TempoMap] built from the tempo and time signature
+    /// messages of the clip.
+    pub fn tempo_map(&self) -> TempoMap {
+        let mut map = TempoMap::from_messages(
+            self.config
+                .iter()
+                .map(|m| (0, m))
+                .chain(self.events.iter().map(|(ticks, m)| (*ticks, m))),
+        );
+        map.set_ticks_per_quarter_note(self.ticks_per_quarter_note);
+        map
+    }
+
+    fn replace_config(
+        &mut self,
+        message: UmpMessage<Vec<u32>>,
+        is_same_kind: fn(&UmpMessage<Vec<u32>>) -> bool,
+    ) {
+        match self.config.iter().position(is_same_kind) {
+            Some(i) => self.config[i] = message,
+            None => self.config.push(message),
+        }
+    }
+}
+
+fn error(offset: usize, message: &'static str) -> ClipFileError {
+    ClipFileError {
+        offset,
+        error: Error::InvalidData(message),
+    }
+}
+
+fn write_delta<S: BytesSink>(mut delta: u64, sink: &mut S) -> Result<(), BufferOverflow> {
+    loop {
+        let mut message = DeltaClockstamp::<[u32; 1]>::new();
+        message.set_time_data(delta.min(MAX_DELTA) as u16);
+        write_message(message.data(), sink)?;
+        delta = delta.saturating_sub(MAX_DELTA);
+        if delta == 0 {
+            return Ok(());
+        }
+    }
+}
+
+// messages are written out as whole packets, so short
+// messages are padded out with zeros
+fn write_message<S: BytesSink>(data: &[u32], sink: &mut S) -> Result<(), BufferOverflow> {
+    use crate::detail::BitOps;
+    for word in data {
+        sink.write_bytes(&word.to_be_bytes())?;
+    }
+    let packet_size = stream::ump_packet_size(data[0].nibble(0));
+    for _ in 0..(packet_size - data.len() % packet_size) % packet_size {
+        sink.write_bytes(&[0x0; 4])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::BufferSink;
+    use pretty_assertions::assert_eq;
+
+    fn words(data: &[u32]) -> Vec<u8> {
+        let mut bytes = FILE_HEADER.to_vec();
+        for word in data {
+            bytes.extend_from_slice(&word.to_be_bytes());
+        }
+        bytes
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    fn note_on(note: u8) -> UmpMessage<Vec<u32>> {
+        let mut message = crate::channel_voice2::NoteOn::<Vec<u32>>::new();
+        message.set_note(crate::ux::u7::new(note));
+        message.set_velocity(0x8000);
+        UmpMessage::ChannelVoice2(message.into())
+    }
+
+    fn tempo() -> SetTempo<Vec<u32>> {
+        let mut message = SetTempo::<Vec<u32>>::new();
+        message.set_number_of_10_nanosecond_units_per_quarter_note(50_000);
+        message
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn parse() {
+        assert_eq!(
+            ClipFile::parse(&words(&[
+                0x0030_01E0, // ticks per quarter note
+                0x0040_0000, // delta clockstamp
+                0xD010_0000, // set tempo
+                0x0000_C350,
+                0x0000_0000,
+                0x0000_0000,
+                0x0040_0000,
+                0xF020_0000, // start of clip
+                0x0000_0000,
+                0x0000_0000,
+                0x0000_0000,
+                0x0040_0010,
+                0x4090_3C00, // note on
+                0x8000_0000,
+                0x0040_0020,
+                0x4090_3E00, // note on
+                0x8000_0000,
+                0x0040_0030,
+                0xF021_0000, // end of clip
+                0x0000_0000,
+                0x0000_0000,
+                0x0000_0000,
+            ])),
+            Ok(ClipFile {
+                ticks_per_quarter_note: 480,
+                config: std::vec![UmpMessage::FlexData(tempo().into())],
+                events: std::vec![(0x10, note_on(0x3C)), (0x30, note_on(0x3E))],
+                length: 0x60,
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_not_clip_file() {
+        assert_eq!(
+            ClipFile::parse(b"MThd\x00\x00\x00\x06"),
+            Err(error(0, ERR_NOT_CLIP_FILE)),
+        );
+    }
+
+    #[test]
+    fn parse_truncated_word() {
+        let mut data = words(&[0x0030_01E0]);
+        data.push(0x00);
+        assert_eq!(ClipFile::parse(&data), Err(error(12, ERR_TRUNCATED_WORD)),);
+    }
+
+    #[test]
+    fn parse_missing_ticks_per_quarter_note() {
+        assert_eq!(
+            ClipFile::parse(&words(&[0x0040_0000])),
+            Err(error(8, ERR_MISSING_TICKS_PER_QUARTER_NOTE)),
+        );
+    }
+
+    #[test]
+    fn parse_empty() {
+        assert_eq!(
+            ClipFile::parse(&words(&[])),
+            Err(error(8, ERR_MISSING_TICKS_PER_QUARTER_NOTE)),
+        );
+    }
+
+    #[test]
+    fn parse_missing_start_of_clip() {
+        assert_eq!(
+            ClipFile::parse(&words(&[0x0030_01E0, 0x0040_0000])),
+            Err(error(16, ERR_MISSING_START_OF_CLIP)),
+        );
+    }
+
+    #[test]
+    fn parse_delayed_config() {
+        assert_eq!(
+            ClipFile::parse(&words(&[
+                0x0030_01E0,
+                0x0040_0010,
+                0xF020_0000,
+                0x0,
+                0x0,
+                0x0
+            ])),
+            Err(error(12, ERR_DELAYED_CONFIG)),
+        );
+    }
+
+    #[test]
+    fn parse_unexpected_end_of_clip() {
+        assert_eq!(
+            ClipFile::parse(&words(&[0x0030_01E0, 0xF021_0000, 0x0, 0x0, 0x0])),
+            Err(error(12, ERR_UNEXPECTED_END_OF_CLIP)),
+        );
+    }
+
+    #[test]
+    fn parse_missing_end_of_clip() {
+        assert_eq!(
+            ClipFile::parse(&words(&[
+                0x0030_01E0,
+                0xF020_0000,
+                0x0,
+                0x0,
+                0x0,
+                0x0040_0010
+            ])),
+            Err(error(32, ERR_MISSING_END_OF_CLIP)),
+        );
+    }
+
+    #[test]
+    fn parse_repeated_start_of_clip() {
+        assert_eq!(
+            ClipFile::parse(&words(&[
+                0x0030_01E0,
+                0xF020_0000,
+                0x0,
+                0x0,
+                0x0,
+                0xF020_0000,
+                0x0,
+                0x0,
+                0x0,
+            ])),
+            Err(error(28, ERR_UNEXPECTED_START_OF_CLIP)),
+        );
+    }
+
+    #[test]
+    fn parse_ticks_per_quarter_note_in_sequence() {
+        assert_eq!(
+            ClipFile::parse(&words(&[
+                0x0030_01E0,
+                0xF020_0000,
+                0x0,
+                0x0,
+                0x0,
+                0x0030_0060
+            ])),
+            Err(error(28, ERR_UNEXPECTED_TICKS_PER_QUARTER_NOTE)),
+        );
+    }
+
+    #[test]
+    fn parse_data_after_end_of_clip() {
+        assert_eq!(
+            ClipFile::parse(&words(&[
+                0x0030_01E0,
+                0xF020_0000,
+                0x0,
+                0x0,
+                0x0,
+                0xF021_0000,
+                0x0,
+                0x0,
+                0x0,
+                0x0040_0000,
+            ])),
+            Err(error(44, ERR_DATA_AFTER_END_OF_CLIP)),
+        );
+    }
+
+    #[test]
+    fn parse_truncated_packet() {
+        assert_eq!(
+            ClipFile::parse(&words(&[0x0030_01E0, 0xF020_0000, 0x0])),
+            Err(ClipFileError {
+                offset: 12,
+                error: Error::InvalidData("Buffer ends part way through a packet"),
+            }),
+        );
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn write() {
+        let clip = ClipFile {
+            ticks_per_quarter_note: 480,
+            config: std::vec![UmpMessage::FlexData(tempo().into())],
+            events: std::vec![(0x10, note_on(0x3C)), (0x30, note_on(0x3E))],
+            length: 0x60,
+        };
+        let mut sink = BufferSink::new(Vec::new());
+        clip.write(&mut sink).unwrap();
+        assert_eq!(
+            sink.data(),
+            &words(&[
+                0x0030_01E0,
+                0x0040_0000,
+                0xD010_0000,
+                0x0000_C350,
+                0x0000_0000,
+                0x0000_0000,
+                0x0040_0000,
+                0xF020_0000,
+                0x0000_0000,
+                0x0000_0000,
+                0x0000_0000,
+                0x0040_0010,
+                0x4090_3C00,
+                0x8000_0000,
+                0x0040_0020,
+                0x4090_3E00,
+                0x8000_0000,
+                0x0040_0030,
+                0xF021_0000,
+                0x0000_0000,
+                0x0000_0000,
+                0x0000_0000,
+            ])[..],
+        );
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn write_long_delta() {
+        let clip = ClipFile {
+            events: std::vec![(0x1_0010, note_on(0x3C))],
+            ..Default::default()
+        };
+        let mut sink = BufferSink::new(Vec::new());
+        clip.write(&mut sink).unwrap();
+        assert_eq!(
+            &sink.data()[32..48],
+            &words(&[0x0040_FFFF, 0x0040_0011, 0x4090_3C00, 0x8000_0000])[8..],
+        );
+        assert_eq!(ClipFile::parse(sink.data()).unwrap().events, clip.events);
+    }
+
+    #[test]
+    fn write_overflow() {
+        let mut sink = BufferSink::new([0x0_u8; 16]);
+        assert_eq!(ClipFile::default().write(&mut sink), Err(BufferOverflow));
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn round_trip() {
+        let mut time_signature = SetTimeSignature::<Vec<u32>>::new();
+        time_signature.set_numerator(6);
+        time_signature.set_denominator(3);
+        time_signature.set_number_of_32nd_notes(8);
+
+        let mut clip = ClipFile::default();
+        clip.set_tempo(tempo());
+        clip.set_time_signature(time_signature.clone());
+        clip.events = std::vec![(0, note_on(0x3C)), (0, note_on(0x3E)), (96, note_on(0x40))];
+        clip.length = 192;
+
+        let mut sink = BufferSink::new(Vec::new());
+        clip.write(&mut sink).unwrap();
+        let read = ClipFile::parse(sink.data()).unwrap();
+        assert_eq!(read, clip);
+        assert_eq!(read.tempo(), Some(&tempo()));
+        assert_eq!(read.time_signature(), Some(&time_signature));
+    }
+
+    #[test]
+    fn tempo_map() {
+        let mut slower = SetTempo::<Vec<u32>>::new();
+        slower.set_bpm(60.0);
+
+        let mut clip = ClipFile::default();
+        clip.set_tempo(tempo());
+        clip.events = std::vec![(96, UmpMessage::FlexData(slower.into()))];
+
+        // half a millisecond for the first quarter note, then 60 bpm
+        let map = clip.tempo_map();
+        assert_eq!(map.ticks_per_quarter_note(), 96);
+        assert_eq!(map.ticks_to_nanos(192), 1_000_500_000);
+    }
+
+    #[test]
+    fn set_tempo_replaces() {
+        let mut clip = ClipFile::default();
+        clip.set_tempo(SetTempo::<Vec<u32>>::new());
+        clip.set_tempo(tempo());
+        assert_eq!(clip.config.len(), 1);
+        assert_eq!(clip.tempo(), Some(&tempo()));
+    }
+}
@@ -16,9 +16,7 @@ impl<const TYPE: u8, B: Buffer> Property<B> for UmpMessageTypeProperty<TYPE> {
 }
 
 impl<'a, const TYPE: u8, B: Buffer> ReadProperty<'a, B> for UmpMessageTypeProperty<TYPE> {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if <B::Unit as UnitPrivate>::UNIT_ID == UNIT_ID_U32 {
             let b = buffer.buffer().specialise_u32()[0];
@@ -43,9 +41,7 @@ impl<const TYPE: u8, B: Buffer + crate::buffer::BufferMut> WriteProperty<B>
     fn validate(_value: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
 pub struct ChannelVoiceStatusProperty<const STATUS: u8>;
@@ -55,9 +51,7 @@ impl<const STATUS: u8, B: Buffer> Property<B> for ChannelVoiceStatusProperty<STA
 }
 
 impl<'a, const STATUS: u8, B: Buffer> ReadProperty<'a, B> for ChannelVoiceStatusProperty<STATUS> {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         let status = match <B::Unit as UnitPrivate>::UNIT_ID {
             UNIT_ID_U32 => {
@@ -97,11 +91,10 @@ impl<const STATUS: u8, B: Buffer + crate::buffer::BufferMut> WriteProperty<B>
     fn validate(_value: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
+#[cfg(feature = "channel-voice1")]
 pub type ChannelProperty = HybridSchemaProperty<
     u4,
     schema::Bytes<0x0F, 0x0, 0x0>,
@@ -23,6 +23,7 @@ pub trait WriteProperty<B: crate::buffer::Buffer + crate::buffer::BufferMut>: Pr
 
 // properties which may require resizing the underlying buffer
 // before writing the value
+#[allow(dead_code)]
 pub trait ResizeProperty<B: crate::buffer::Buffer + crate::buffer::BufferMut>:
     WriteProperty<B>
 {
@@ -10,6 +10,35 @@ pub enum Error {
     InvalidData(&'static str),
 }
 
+/// A malformed message found while iterating over a stream of ump data.
+///
+/// `offset` is the index of the first offending word in the stream
+/// and `len` is the number of words which were skipped over.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamError {
+    pub offset: usize,
+    pub len: usize,
+    pub error: Error,
+}
+
+/// A malformed Standard MIDI File.
+///
+/// `offset` is the index of the offending byte within the file data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmfError {
+    pub offset: usize,
+    pub error: Error,
+}
+
+/// A malformed MIDI Clip File.
+///
+/// `offset` is the index of the offending byte within the file data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClipFileError {
+    pub offset: usize,
+    pub error: Error,
+}
+
 impl core::convert::From<BufferOverflow> for Error {
     fn from(_: BufferOverflow) -> Self {
         Error::BufferOverflow
@@ -51,3 +80,33 @@ impl std::fmt::Display for Error {
         <Self as std::fmt::Debug>::fmt(self, f)
     }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for StreamError {}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for StreamError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        <Self as std::fmt::Debug>::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SmfError {}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for SmfError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        <Self as std::fmt::Debug>::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClipFileError {}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ClipFileError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        <Self as std::fmt::Debug>::fmt(self, f)
+    }
+}
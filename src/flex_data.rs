@@ -13,6 +13,8 @@ mod set_key_signature;
 mod set_metronome;
 mod set_tempo;
 mod set_time_signature;
+#[cfg(feature = "std")]
+mod tempo_map;
 mod tonic;
 mod unknown_metadata_text;
 mod project_name {
@@ -736,6 +738,8 @@ pub use set_key_signature::{SetKeySignature, SharpsFlats as SetKeySignatureSharp
 pub use set_metronome::*;
 pub use set_tempo::*;
 pub use set_time_signature::*;
+#[cfg(feature = "std")]
+pub use tempo_map::*;
 pub use tonic::Tonic;
 pub use unknown_metadata_text::*;
 pub use unknown_performance_text::*;
@@ -753,8 +757,10 @@ pub(crate) const PERFORMANCE_TEXT_BANK: u8 = 0x2;
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Grouped,
+    midi2_proc::OptionalChanneled,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -762,74 +768,96 @@ pub(crate) const PERFORMANCE_TEXT_BANK: u8 = 0x2;
 )]
 #[non_exhaustive]
 pub enum FlexData<B: crate::buffer::Ump> {
+    #[optionally_channeled]
     SetChordName(set_chord_name::SetChordName<B>),
+    #[optionally_channeled]
     SetKeySignature(set_key_signature::SetKeySignature<B>),
     SetMetronome(set_metronome::SetMetronome<B>),
     SetTempo(set_tempo::SetTempo<B>),
     SetTimeSignature(set_time_signature::SetTimeSignature<B>),
+    #[optionally_channeled]
     UnknownMetadataText(unknown_metadata_text::UnknownMetadataText<B>),
+    #[optionally_channeled]
     ProjectName(project_name::ProjectName<B>),
+    #[optionally_channeled]
     CompositionName(composition_name::CompositionName<B>),
+    #[optionally_channeled]
     MidiClipName(midi_clip_name::MidiClipName<B>),
+    #[optionally_channeled]
     CopyrightNotice(copyright_notice::CopyrightNotice<B>),
+    #[optionally_channeled]
     ComposerName(composer_name::ComposerName<B>),
+    #[optionally_channeled]
     LyricistName(lyricist_name::LyricistName<B>),
+    #[optionally_channeled]
     ArrangerName(arranger_name::ArrangerName<B>),
+    #[optionally_channeled]
     PublisherName(publisher_name::PublisherName<B>),
+    #[optionally_channeled]
     PrimaryPerformerName(primary_performer_name::PrimaryPerformerName<B>),
+    #[optionally_channeled]
     AccompanyingPerformerName(accompanying_performer_name::AccompanyingPerformerName<B>),
+    #[optionally_channeled]
     RecordingDate(recording_date::RecordingDate<B>),
+    #[optionally_channeled]
     RecordingLocation(recording_location::RecordingLocation<B>),
+    #[optionally_channeled]
     UnknownPerformanceText(unknown_performance_text::UnknownPerformanceText<B>),
+    #[optionally_channeled]
     Lyrics(lyrics::Lyrics<B>),
+    #[optionally_channeled]
     LyricsLanguage(lyrics_language::LyricsLanguage<B>),
+    #[optionally_channeled]
     Ruby(ruby::Ruby<B>),
+    #[optionally_channeled]
     RubyLanguage(ruby_language::RubyLanguage<B>),
+    #[cfg(feature = "unknown")]
+    #[optionally_grouped]
+    Unknown(crate::UnknownUmp<B>),
 }
 
 impl<'a> TryFrom<&'a [u32]> for FlexData<&'a [u32]> {
     type Error = crate::error::Error;
     fn try_from(value: &'a [u32]) -> Result<Self, Self::Error> {
         use FlexData::*;
-        if value.len() < 1 {
+        if value.is_empty() {
             return Err(crate::error::Error::InvalidData("Slice is too short"));
         };
         Ok(match value[0].word(1) {
-            0x00_00 => SetTempo(set_tempo::SetTempo::try_from(value)?.into()),
-            0x00_01 => {
-                SetTimeSignature(set_time_signature::SetTimeSignature::try_from(value)?.into())
+            0x00_00 => SetTempo(set_tempo::SetTempo::try_from(value)?),
+            0x00_01 => SetTimeSignature(set_time_signature::SetTimeSignature::try_from(value)?),
+            0x00_02 => SetMetronome(set_metronome::SetMetronome::try_from(value)?),
+            0x00_03 => SetKeySignature(set_key_signature::SetKeySignature::try_from(value)?),
+            0x00_04 => SetChordName(set_chord_name::SetChordName::try_from(value)?),
+            0x01_00 => {
+                UnknownMetadataText(unknown_metadata_text::UnknownMetadataText::try_from(value)?)
             }
-            0x00_02 => SetMetronome(set_metronome::SetMetronome::try_from(value)?.into()),
-            0x00_03 => SetKeySignature(set_key_signature::SetKeySignature::try_from(value)?.into()),
-            0x00_04 => SetChordName(set_chord_name::SetChordName::try_from(value)?.into()),
-            0x01_00 => UnknownMetadataText(
-                unknown_metadata_text::UnknownMetadataText::try_from(value)?.into(),
-            ),
-            0x01_01 => ProjectName(project_name::ProjectName::try_from(value)?.into()),
-            0x01_02 => CompositionName(composition_name::CompositionName::try_from(value)?.into()),
-            0x01_03 => MidiClipName(midi_clip_name::MidiClipName::try_from(value)?.into()),
-            0x01_04 => CopyrightNotice(copyright_notice::CopyrightNotice::try_from(value)?.into()),
-            0x01_05 => ComposerName(composer_name::ComposerName::try_from(value)?.into()),
-            0x01_06 => LyricistName(lyricist_name::LyricistName::try_from(value)?.into()),
-            0x01_07 => ArrangerName(arranger_name::ArrangerName::try_from(value)?.into()),
-            0x01_08 => PublisherName(publisher_name::PublisherName::try_from(value)?.into()),
+            0x01_01 => ProjectName(project_name::ProjectName::try_from(value)?),
+            0x01_02 => CompositionName(composition_name::CompositionName::try_from(value)?),
+            0x01_03 => MidiClipName(midi_clip_name::MidiClipName::try_from(value)?),
+            0x01_04 => CopyrightNotice(copyright_notice::CopyrightNotice::try_from(value)?),
+            0x01_05 => ComposerName(composer_name::ComposerName::try_from(value)?),
+            0x01_06 => LyricistName(lyricist_name::LyricistName::try_from(value)?),
+            0x01_07 => ArrangerName(arranger_name::ArrangerName::try_from(value)?),
+            0x01_08 => PublisherName(publisher_name::PublisherName::try_from(value)?),
             0x01_09 => PrimaryPerformerName(
-                primary_performer_name::PrimaryPerformerName::try_from(value)?.into(),
+                primary_performer_name::PrimaryPerformerName::try_from(value)?,
             ),
             0x01_10 => AccompanyingPerformerName(
-                accompanying_performer_name::AccompanyingPerformerName::try_from(value)?.into(),
+                accompanying_performer_name::AccompanyingPerformerName::try_from(value)?,
             ),
-            0x01_11 => RecordingDate(recording_date::RecordingDate::try_from(value)?.into()),
-            0x01_12 => {
-                RecordingLocation(recording_location::RecordingLocation::try_from(value)?.into())
-            }
+            0x01_11 => RecordingDate(recording_date::RecordingDate::try_from(value)?),
+            0x01_12 => RecordingLocation(recording_location::RecordingLocation::try_from(value)?),
             0x02_00 => UnknownPerformanceText(
-                unknown_performance_text::UnknownPerformanceText::try_from(value)?.into(),
+                unknown_performance_text::UnknownPerformanceText::try_from(value)?,
             ),
-            0x02_01 => Lyrics(lyrics::Lyrics::try_from(value)?.into()),
-            0x02_02 => LyricsLanguage(lyrics_language::LyricsLanguage::try_from(value)?.into()),
-            0x02_03 => Ruby(ruby::Ruby::try_from(value)?.into()),
-            0x02_04 => RubyLanguage(ruby_language::RubyLanguage::try_from(value)?.into()),
+            0x02_01 => Lyrics(lyrics::Lyrics::try_from(value)?),
+            0x02_02 => LyricsLanguage(lyrics_language::LyricsLanguage::try_from(value)?),
+            0x02_03 => Ruby(ruby::Ruby::try_from(value)?),
+            0x02_04 => RubyLanguage(ruby_language::RubyLanguage::try_from(value)?),
+            #[cfg(feature = "unknown")]
+            _ => Unknown(crate::UnknownUmp::try_from(value)?),
+            #[cfg(not(feature = "unknown"))]
             _ => Err(crate::error::Error::InvalidData(
                 "Couldn't interpret flex data status / bank fields",
             ))?,
@@ -868,9 +896,7 @@ impl<const STATUS: u8, B: Ump> Property<B> for StatusProperty<STATUS> {
 }
 
 impl<'a, const STATUS: u8, B: Ump> ReadProperty<'a, B> for StatusProperty<STATUS> {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if buffer
             .buffer()
@@ -891,9 +917,7 @@ impl<const STATUS: u8, B: Ump + BufferMut> WriteProperty<B> for StatusProperty<S
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
 struct BankProperty<const BANK: u8>;
@@ -903,9 +927,7 @@ impl<const BANK: u8, B: Ump> Property<B> for BankProperty<BANK> {
 }
 
 impl<'a, const BANK: u8, B: Ump> ReadProperty<'a, B> for BankProperty<BANK> {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if buffer
             .buffer()
@@ -926,9 +948,7 @@ impl<const BANK: u8, B: Ump + BufferMut> WriteProperty<B> for BankProperty<BANK>
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
 struct FormatProperty<const FORMAT: u8>;
@@ -938,9 +958,7 @@ impl<const FORMAT: u8, B: Ump> Property<B> for FormatProperty<FORMAT> {
 }
 
 impl<'a, const FORMAT: u8, B: Ump> ReadProperty<'a, B> for FormatProperty<FORMAT> {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if FORMAT == buffer.buffer()[0].crumb(4).into() {
             Ok(())
@@ -957,9 +975,7 @@ impl<const FORMAT: u8, B: Ump + BufferMut> WriteProperty<B> for FormatProperty<F
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
 struct OptionalChannelProperty;
@@ -1020,9 +1036,7 @@ impl<B: Ump> Property<B> for NoChannelProperty {
 }
 
 impl<'a, B: Ump> ReadProperty<'a, B> for NoChannelProperty {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         use crate::ux::u2;
         if buffer.buffer()[0].crumb(5) != u2::new(0x0) {
@@ -1059,9 +1073,7 @@ impl<B: Ump> Property<B> for ConsistentFormatsProperty {
 }
 
 impl<'a, B: Ump> ReadProperty<'a, B> for ConsistentFormatsProperty {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
 
     fn validate(buffer: &B) -> crate::result::Result<()> {
         use crate::detail::helpers::validate_sysex_group_statuses;
@@ -1153,6 +1165,21 @@ fn clear_payload(buffer: &mut [u32]) {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "unknown")]
+    #[test]
+    fn unknown_status() {
+        use crate::traits::{Data, RebufferInto};
+
+        let buffer = [0xD310_0305, 0x0, 0x0, 0x0];
+        let message = FlexData::try_from(&buffer[..]).unwrap();
+        assert_eq!(
+            message,
+            FlexData::Unknown(crate::UnknownUmp::try_from(&buffer[..]).unwrap()),
+        );
+        let rebuffered: FlexData<std::vec::Vec<u32>> = message.rebuffer_into();
+        assert_eq!(rebuffered.data(), &buffer[..]);
+    }
+
     #[test]
     fn try_from_empty() {
         let buffer = [];
@@ -1193,6 +1220,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn optional_channel() {
+        use crate::traits::OptionalChanneled;
+
+        assert_eq!(
+            FlexData::try_from(&[0xD00D_0101, 0x0, 0x0, 0x0][..])
+                .unwrap()
+                .channel(),
+            Some(crate::ux::u4::new(0xD)),
+        );
+    }
+
+    #[test]
+    fn no_channel() {
+        use crate::traits::OptionalChanneled;
+
+        let mut message: FlexData<[u32; 4]> = set_tempo::SetTempo::<[u32; 4]>::new().into();
+        message.set_channel(crate::ux::u4::new(0x3));
+        assert_eq!(message.channel(), None);
+    }
+
     #[test]
     fn read_bank() {
         assert_eq!(
@@ -28,6 +28,29 @@ struct SetTempo {
 
 impl<B: crate::buffer::Ump> flex_data::FlexDataMessage<B> for SetTempo<B> {}
 
+// 10 nanosecond units in one minute
+const UNITS_PER_MINUTE: f64 = 6_000_000_000.0;
+
+impl<B: crate::buffer::Ump> SetTempo<B> {
+    /// The tempo in quarter notes per minute.
+    pub fn bpm(&self) -> f64 {
+        UNITS_PER_MINUTE / self.number_of_10_nanosecond_units_per_quarter_note() as f64
+    }
+}
+
+impl<B: crate::buffer::Ump + crate::buffer::BufferMut> SetTempo<B> {
+    /// Set the tempo in quarter notes per minute.
+    ///
+    /// The value is rounded to the nearest whole number of 10 nanosecond
+    /// units, so that a tempo read with [SetTempo::bpm] is written back
+    /// unchanged. Values out of range saturate.
+    pub fn set_bpm(&mut self, bpm: f64) {
+        // float to int casts saturate
+        let units = (UNITS_PER_MINUTE / bpm + 0.5) as u32;
+        self.set_number_of_10_nanosecond_units_per_quarter_note(units);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,4 +74,48 @@ mod tests {
             0xF751FE05,
         );
     }
+
+    #[test]
+    fn bpm() {
+        assert_eq!(
+            SetTempo::try_from(&[0xD010_0000, 50_000_000][..])
+                .unwrap()
+                .bpm(),
+            120.0,
+        );
+    }
+
+    #[test]
+    fn set_bpm() {
+        let mut message = SetTempo::<[u32; 4]>::new();
+        message.set_bpm(140.0);
+        assert_eq!(
+            message.number_of_10_nanosecond_units_per_quarter_note(),
+            42_857_143,
+        );
+    }
+
+    #[test]
+    fn bpm_round_trip() {
+        let mut message = SetTempo::<[u32; 4]>::new();
+        for units in [1, 7, 42_857_143, 50_000_000, 0x7FFF_FFFF, 0xFFFF_FFFF] {
+            message.set_number_of_10_nanosecond_units_per_quarter_note(units);
+            let bpm = message.bpm();
+            message.set_bpm(bpm);
+            assert_eq!(
+                message.number_of_10_nanosecond_units_per_quarter_note(),
+                units
+            );
+        }
+    }
+
+    #[test]
+    fn set_bpm_saturates() {
+        let mut message = SetTempo::<[u32; 4]>::new();
+        message.set_bpm(0.0);
+        assert_eq!(
+            message.number_of_10_nanosecond_units_per_quarter_note(),
+            0xFFFF_FFFF,
+        );
+    }
 }
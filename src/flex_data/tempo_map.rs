@@ -0,0 +1,475 @@
+use crate::{
+    buffer::Ump,
+    flex_data::{FlexData, SetTempo, SetTimeSignature},
+    message::UmpMessage,
+};
+use std::vec::Vec;
+
+const DEFAULT_TICKS_PER_QUARTER_NOTE: u16 = 96;
+// 120 bpm
+const DEFAULT_TEMPO: u32 = 50_000_000;
+const NANOSECONDS_PER_UNIT: u128 = 10;
+
+/// A musical position, counted from zero.
+///
+/// The first beat of the first bar is `bar: 0, beat: 0, tick: 0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BarsBeatsTicks {
+    pub bar: u32,
+    pub beat: u32,
+    pub tick: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TempoChange {
+    ticks: u64,
+    // 10 nanosecond units per quarter note
+    tempo: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TimeSignatureChange {
+    ticks: u64,
+    numerator: u8,
+    // as a power of two
+    denominator: u8,
+}
+
+/// Converts between ticks, musical positions and wall clock time.
+///
+/// The map is built from the tempo and time signature flex data messages
+/// of a sequence, along with the resolution given by a
+/// [DeltaClockstampTPQ](crate::utility::DeltaClockstampTPQ) message.
+/// Until the first messages the tempo is 120 bpm and the time signature
+/// is 4/4.
+///
+/// Converting a tick to nanoseconds rounds up and converting nanoseconds
+/// to ticks rounds down, so that ticks survive the round trip exactly
+/// as long as every tick lasts at least a nanosecond. At tempos where
+/// several ticks fall within one nanosecond, the round trip gives the
+/// last of them.
+///
+/// ```rust
+/// use midi2::{prelude::*, flex_data::{BarsBeatsTicks, TempoMap}};
+///
+/// let mut tempo = flex_data::SetTempo::<[u32; 4]>::new();
+/// tempo.set_bpm(60.0);
+/// let mut time_signature = flex_data::SetTimeSignature::<[u32; 4]>::new();
+/// time_signature.set_numerator(3);
+/// time_signature.set_denominator(2);
+///
+/// let mut map = TempoMap::new(480);
+/// map.insert_tempo(960, &tempo);
+/// map.insert_time_signature(0, &time_signature);
+///
+/// // two quarter notes at 120 bpm, then one at 60 bpm
+/// assert_eq!(map.ticks_to_nanos(1440), 2_000_000_000);
+/// assert_eq!(map.nanos_to_ticks(2_000_000_000), 1440);
+/// assert_eq!(
+///     map.ticks_to_bars_beats_ticks(1440 + 240),
+///     BarsBeatsTicks { bar: 1, beat: 0, tick: 240 },
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TempoMap {
+    ticks_per_quarter_note: u16,
+    tempos: Vec<TempoChange>,
+    time_signatures: Vec<TimeSignatureChange>,
+}
+
+impl Default for TempoMap {
+    fn default() -> Self {
+        Self::new(DEFAULT_TICKS_PER_QUARTER_NOTE)
+    }
+}
+
+impl TempoMap {
+    pub fn new(ticks_per_quarter_note: u16) -> Self {
+        TempoMap {
+            ticks_per_quarter_note,
+            tempos: Vec::new(),
+            time_signatures: Vec::new(),
+        }
+    }
+
+    /// Build a map from a sequence of messages and their time in ticks.
+    ///
+    /// Messages other than tempo, time signature and delta clockstamp
+    /// ticks per quarter note are ignored.
+    pub fn from_messages<'a, B, I>(messages: I) -> Self
+    where
+        B: Ump + 'a,
+        I: core::iter::IntoIterator<Item = (u64, &'a UmpMessage<B>)>,
+    {
+        let mut map = Self::default();
+        for (ticks, message) in messages {
+            match message {
+                UmpMessage::FlexData(FlexData::SetTempo(m)) => map.insert_tempo(ticks, m),
+                UmpMessage::FlexData(FlexData::SetTimeSignature(m)) => {
+                    map.insert_time_signature(ticks, m)
+                }
+                #[cfg(feature = "utility")]
+                UmpMessage::Utility(crate::utility::Utility::DeltaClockstampTpq(m)) => {
+                    map.set_ticks_per_quarter_note(m.time_data())
+                }
+                _ => {}
+            }
+        }
+        map
+    }
+
+    pub fn ticks_per_quarter_note(&self) -> u16 {
+        self.ticks_per_quarter_note
+    }
+
+    pub fn set_ticks_per_quarter_note(&mut self, v: u16) {
+        self.ticks_per_quarter_note = v;
+    }
+
+    /// Change the tempo from the given tick onwards.
+    ///
+    /// A previous tempo change at the same tick is replaced.
+    pub fn insert_tempo<B: Ump>(&mut self, ticks: u64, message: &SetTempo<B>) {
+        let change = TempoChange {
+            ticks,
+            tempo: message.number_of_10_nanosecond_units_per_quarter_note(),
+        };
+        match self.tempos.binary_search_by_key(&ticks, |c| c.ticks) {
+            Ok(i) => self.tempos[i] = change,
+            Err(i) => self.tempos.insert(i, change),
+        }
+    }
+
+    /// Change the time signature from the given tick onwards.
+    ///
+    /// A previous time signature change at the same tick is replaced.
+    /// A change part way through a bar starts a new bar.
+    pub fn insert_time_signature<B: Ump>(&mut self, ticks: u64, message: &SetTimeSignature<B>) {
+        let change = TimeSignatureChange {
+            ticks,
+            numerator: message.numerator(),
+            denominator: message.denominator(),
+        };
+        match self
+            .time_signatures
+            .binary_search_by_key(&ticks, |c| c.ticks)
+        {
+            Ok(i) => self.time_signatures[i] = change,
+            Err(i) => self.time_signatures.insert(i, change),
+        }
+    }
+
+    /// The tempo at the given tick in quarter notes per minute.
+    pub fn bpm_at(&self, ticks: u64) -> f64 {
+        let mut message = SetTempo::<[u32; 4]>::new();
+        message.set_number_of_10_nanosecond_units_per_quarter_note(self.tempo_at(ticks));
+        message.bpm()
+    }
+
+    pub fn ticks_to_nanos(&self, ticks: u64) -> u64 {
+        let mut segment = TempoSegment::default();
+        for next in self.tempo_segments() {
+            if next.ticks > ticks {
+                break;
+            }
+            segment = next;
+        }
+        segment
+            .nanos
+            .saturating_add(self.segment_nanos(segment.tempo, ticks - segment.ticks))
+    }
+
+    pub fn nanos_to_ticks(&self, nanos: u64) -> u64 {
+        let mut segment = TempoSegment::default();
+        for next in self.tempo_segments() {
+            if next.nanos > nanos {
+                break;
+            }
+            segment = next;
+        }
+        let quarter_note = u128::from(segment.tempo.max(1)) * NANOSECONDS_PER_UNIT;
+        let delta =
+            u128::from(nanos - segment.nanos) * u128::from(self.resolution()) / quarter_note;
+        segment.ticks.saturating_add(saturate(delta))
+    }
+
+    pub fn ticks_to_bars_beats_ticks(&self, ticks: u64) -> BarsBeatsTicks {
+        let mut segment = BarSegment::default();
+        for next in self.bar_segments() {
+            if next.ticks > ticks {
+                break;
+            }
+            segment = next;
+        }
+        let beat_length = self.beat_length(segment.denominator);
+        let bar_length = beat_length * u64::from(segment.numerator.max(1));
+        let delta = ticks - segment.ticks;
+        // beats and ticks are bounded by the bar length
+        BarsBeatsTicks {
+            bar: u32::try_from(segment.bar + delta / bar_length).unwrap_or(u32::MAX),
+            beat: (delta % bar_length / beat_length) as u32,
+            tick: (delta % beat_length) as u32,
+        }
+    }
+
+    pub fn bars_beats_ticks_to_ticks(&self, position: BarsBeatsTicks) -> u64 {
+        let mut segment = BarSegment::default();
+        for next in self.bar_segments() {
+            if next.bar > u64::from(position.bar) {
+                break;
+            }
+            segment = next;
+        }
+        let beat_length = self.beat_length(segment.denominator);
+        let bar_length = beat_length * u64::from(segment.numerator.max(1));
+        segment
+            .ticks
+            .saturating_add((u64::from(position.bar) - segment.bar).saturating_mul(bar_length))
+            .saturating_add(u64::from(position.beat).saturating_mul(beat_length))
+            .saturating_add(u64::from(position.tick))
+    }
+
+    fn tempo_at(&self, ticks: u64) -> u32 {
+        self.tempos
+            .iter()
+            .take_while(|c| c.ticks <= ticks)
+            .last()
+            .map_or(DEFAULT_TEMPO, |c| c.tempo)
+    }
+
+    // the wall clock time taken by the given number of ticks, rounded up
+    fn segment_nanos(&self, tempo: u32, ticks: u64) -> u64 {
+        let tpq = u128::from(self.resolution());
+        let nanos = u128::from(ticks) * u128::from(tempo) * NANOSECONDS_PER_UNIT;
+        saturate(nanos / tpq + u128::from(nanos % tpq != 0))
+    }
+
+    fn tempo_segments(&self) -> impl Iterator<Item = TempoSegment> + '_ {
+        let mut current = TempoSegment::default();
+        self.tempos.iter().map(move |change| {
+            current = TempoSegment {
+                ticks: change.ticks,
+                nanos: current.nanos.saturating_add(
+                    self.segment_nanos(current.tempo, change.ticks - current.ticks),
+                ),
+                tempo: change.tempo,
+            };
+            current
+        })
+    }
+
+    fn bar_segments(&self) -> impl Iterator<Item = BarSegment> + '_ {
+        let mut current = BarSegment::default();
+        self.time_signatures.iter().map(move |change| {
+            let bar_length =
+                self.beat_length(current.denominator) * u64::from(current.numerator.max(1));
+            let ticks = change.ticks - current.ticks;
+            current = BarSegment {
+                ticks: change.ticks,
+                // a partial bar is padded out before the change
+                bar: current.bar + ticks / bar_length + u64::from(ticks % bar_length != 0),
+                numerator: change.numerator,
+                denominator: change.denominator,
+            };
+            current
+        })
+    }
+
+    fn beat_length(&self, denominator: u8) -> u64 {
+        let whole_note = 4 * u64::from(self.resolution());
+        whole_note
+            .checked_shr(denominator.into())
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    // guards against division by zero
+    fn resolution(&self) -> u16 {
+        self.ticks_per_quarter_note.max(1)
+    }
+}
+
+// the start of a run of ticks at a single tempo
+#[derive(Clone, Copy)]
+struct TempoSegment {
+    ticks: u64,
+    nanos: u64,
+    tempo: u32,
+}
+
+impl Default for TempoSegment {
+    fn default() -> Self {
+        TempoSegment {
+            ticks: 0,
+            nanos: 0,
+            tempo: DEFAULT_TEMPO,
+        }
+    }
+}
+
+// the start of a run of bars in a single time signature
+#[derive(Clone, Copy)]
+struct BarSegment {
+    ticks: u64,
+    bar: u64,
+    numerator: u8,
+    denominator: u8,
+}
+
+impl Default for BarSegment {
+    fn default() -> Self {
+        BarSegment {
+            ticks: 0,
+            bar: 0,
+            numerator: 4,
+            denominator: 2,
+        }
+    }
+}
+
+fn saturate(v: u128) -> u64 {
+    u64::try_from(v).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn tempo(bpm: f64) -> SetTempo<[u32; 4]> {
+        let mut message = SetTempo::<[u32; 4]>::new();
+        message.set_bpm(bpm);
+        message
+    }
+
+    fn time_signature(numerator: u8, denominator: u8) -> SetTimeSignature<[u32; 4]> {
+        let mut message = SetTimeSignature::<[u32; 4]>::new();
+        message.set_numerator(numerator);
+        message.set_denominator(denominator);
+        message
+    }
+
+    #[test]
+    fn default_tempo() {
+        let map = TempoMap::new(480);
+        assert_eq!(map.ticks_to_nanos(480), 500_000_000);
+        assert_eq!(map.nanos_to_ticks(500_000_000), 480);
+        assert_eq!(map.bpm_at(0), 120.0);
+    }
+
+    #[test]
+    fn tempo_changes() {
+        let mut map = TempoMap::new(96);
+        map.insert_tempo(96, &tempo(60.0));
+        map.insert_tempo(192, &tempo(240.0));
+        assert_eq!(map.ticks_to_nanos(96), 500_000_000);
+        assert_eq!(map.ticks_to_nanos(192), 1_500_000_000);
+        assert_eq!(map.ticks_to_nanos(288), 1_750_000_000);
+        assert_eq!(map.nanos_to_ticks(1_000_000_000), 144);
+        assert_eq!(map.nanos_to_ticks(1_750_000_000), 288);
+        assert_eq!(map.bpm_at(100), 60.0);
+        assert_eq!(map.bpm_at(192), 240.0);
+    }
+
+    #[test]
+    fn tempo_inserted_out_of_order() {
+        let mut map = TempoMap::new(96);
+        map.insert_tempo(192, &tempo(240.0));
+        map.insert_tempo(96, &tempo(60.0));
+        assert_eq!(map.ticks_to_nanos(288), 1_750_000_000);
+    }
+
+    #[test]
+    fn tempo_replaced() {
+        let mut map = TempoMap::new(96);
+        map.insert_tempo(0, &tempo(60.0));
+        map.insert_tempo(0, &tempo(30.0));
+        assert_eq!(map.ticks_to_nanos(96), 2_000_000_000);
+    }
+
+    #[test]
+    fn ticks_round_trip() {
+        let mut map = TempoMap::new(960);
+        map.insert_tempo(1000, &tempo(133.0));
+        map.insert_tempo(5000, &tempo(47.3));
+        for ticks in [0, 1, 999, 1000, 1001, 4999, 5000, 5001, 1_000_000] {
+            assert_eq!(map.nanos_to_ticks(map.ticks_to_nanos(ticks)), ticks);
+        }
+    }
+
+    #[test]
+    fn ticks_shorter_than_a_nanosecond() {
+        let mut map = TempoMap::new(480);
+        let mut message = SetTempo::<[u32; 4]>::new();
+        message.set_number_of_10_nanosecond_units_per_quarter_note(1);
+        map.insert_tempo(0, &message);
+        assert_eq!(map.ticks_to_nanos(1), 1);
+        assert_eq!(map.nanos_to_ticks(1), 48);
+    }
+
+    #[test]
+    fn nanos_round_down() {
+        let map = TempoMap::new(480);
+        assert_eq!(map.nanos_to_ticks(499_999_999), 479);
+    }
+
+    #[test]
+    fn bars_beats_ticks() {
+        let map = TempoMap::new(96);
+        assert_eq!(
+            map.ticks_to_bars_beats_ticks(96 * 9 + 10),
+            BarsBeatsTicks {
+                bar: 2,
+                beat: 1,
+                tick: 10,
+            },
+        );
+    }
+
+    #[test]
+    fn time_signature_changes() {
+        let mut map = TempoMap::new(96);
+        // a bar of 4/4, then 6/8
+        map.insert_time_signature(0, &time_signature(4, 2));
+        map.insert_time_signature(384, &time_signature(6, 3));
+        let position = BarsBeatsTicks {
+            bar: 2,
+            beat: 5,
+            tick: 47,
+        };
+        assert_eq!(map.ticks_to_bars_beats_ticks(384 + 288 + 287), position);
+        assert_eq!(map.bars_beats_ticks_to_ticks(position), 384 + 288 + 287);
+    }
+
+    #[test]
+    fn time_signature_change_mid_bar() {
+        let mut map = TempoMap::new(96);
+        map.insert_time_signature(96, &time_signature(3, 2));
+        assert_eq!(
+            map.ticks_to_bars_beats_ticks(96),
+            BarsBeatsTicks {
+                bar: 1,
+                beat: 0,
+                tick: 0,
+            },
+        );
+    }
+
+    #[test]
+    fn from_messages() {
+        let messages: [UmpMessage<[u32; 4]>; 2] = [
+            UmpMessage::FlexData(tempo(60.0).into()),
+            UmpMessage::FlexData(time_signature(3, 2).into()),
+        ];
+        let map = TempoMap::from_messages(messages.iter().map(|m| (0, m)));
+        assert_eq!(map.ticks_to_nanos(96), 1_000_000_000);
+        assert_eq!(
+            map.ticks_to_bars_beats_ticks(96 * 3),
+            BarsBeatsTicks {
+                bar: 1,
+                beat: 0,
+                tick: 0,
+            },
+        );
+    }
+}
@@ -62,7 +62,7 @@ impl<'a, B: Ump + BufferMut> ResizeProperty<B> for TextWriteStrProperty<'a> {
 }
 
 fn ump_buffer_size_for_str(s: &str) -> usize {
-    let str_size = s.as_bytes().len();
+    let str_size = s.len();
     if str_size % 12 == 0 {
         if str_size == 0 {
             4
@@ -1,5 +1,16 @@
 #![no_std]
-#![doc = include_str!("../README.md")]
+// the readme examples exercise optional message types
+#![cfg_attr(
+    any(
+        not(doctest),
+        all(
+            feature = "channel-voice1",
+            feature = "flex-data",
+            feature = "sysex8"
+        )
+    ),
+    doc = include_str!("../README.md")
+)]
 
 #[cfg(any(feature = "std", test))]
 extern crate std;
@@ -10,14 +21,21 @@ pub mod channel_voice1;
 pub mod channel_voice2;
 #[cfg(feature = "ci")]
 pub mod ci;
+#[cfg(feature = "clip-file")]
+pub mod clip_file;
 #[cfg(feature = "flex-data")]
 pub mod flex_data;
+#[cfg(feature = "smf")]
+pub mod smf;
 #[cfg(feature = "sysex7")]
 pub mod sysex7;
 #[cfg(feature = "sysex8")]
 pub mod sysex8;
 #[cfg(feature = "system-common")]
 pub mod system_common;
+pub mod transform;
+#[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+pub mod translation;
 #[cfg(feature = "ump-stream")]
 pub mod ump_stream;
 #[cfg(feature = "utility")]
@@ -26,15 +44,22 @@ pub mod utility;
 pub mod buffer;
 pub mod error;
 pub mod result;
+pub mod scale;
 
 mod detail;
 mod message;
+mod stream;
 mod traits;
+#[cfg(feature = "unknown")]
+mod unknown_ump;
 
 pub use ux;
 
 pub use message::*;
+pub use stream::*;
 pub use traits::*;
+#[cfg(feature = "unknown")]
+pub use unknown_ump::*;
 
 pub mod prelude {
     pub use super::*;
@@ -1,8 +1,11 @@
 #[derive(
     derive_more::From,
     midi2_proc::Data,
+    midi2_proc::OptionalGrouped,
+    midi2_proc::OptionalChanneled,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -11,21 +14,37 @@
 #[non_exhaustive]
 pub enum UmpMessage<B: crate::buffer::Ump> {
     #[cfg(feature = "flex-data")]
+    #[grouped]
+    #[optionally_channeled]
     FlexData(crate::flex_data::FlexData<B>),
     #[cfg(feature = "channel-voice1")]
+    #[grouped]
+    #[channeled]
     ChannelVoice1(crate::channel_voice1::ChannelVoice1<B>),
     #[cfg(feature = "channel-voice2")]
+    #[grouped]
+    #[channeled]
     ChannelVoice2(crate::channel_voice2::ChannelVoice2<B>),
     #[cfg(feature = "sysex7")]
+    #[grouped]
     Sysex7(crate::sysex7::Sysex7<B>),
     #[cfg(feature = "sysex8")]
+    #[grouped]
     Sysex8(crate::sysex8::Sysex8<B>),
+    #[cfg(feature = "sysex8")]
+    #[grouped]
+    MixedDataSet(crate::sysex8::MixedDataSet<B>),
     #[cfg(feature = "system-common")]
+    #[grouped]
     SystemCommon(crate::system_common::SystemCommon<B>),
     #[cfg(feature = "ump-stream")]
     UmpStream(crate::ump_stream::UmpStream<B>),
     #[cfg(feature = "utility")]
     Utility(crate::utility::Utility<B>),
+    #[cfg(feature = "unknown")]
+    #[optionally_grouped]
+    #[optionally_channeled]
+    Unknown(crate::UnknownUmp<B>),
 }
 
 impl<'a> core::convert::TryFrom<&'a [u32]> for UmpMessage<&'a [u32]> {
@@ -34,7 +53,7 @@ impl<'a> core::convert::TryFrom<&'a [u32]> for UmpMessage<&'a [u32]> {
         use crate::detail::BitOps;
         use UmpMessage::*;
 
-        if buffer.len() < 1 {
+        if buffer.is_empty() {
             return Err(crate::error::Error::InvalidData(
                 "Ump message slice is empty",
             ));
@@ -43,36 +62,39 @@ impl<'a> core::convert::TryFrom<&'a [u32]> for UmpMessage<&'a [u32]> {
         Ok(match u8::from(buffer[0].nibble(0)) {
             #[cfg(feature = "flex-data")]
             crate::flex_data::UMP_MESSAGE_TYPE => {
-                FlexData(crate::flex_data::FlexData::try_from(buffer)?.into())
+                FlexData(crate::flex_data::FlexData::try_from(buffer)?)
             }
             #[cfg(feature = "channel-voice1")]
             crate::channel_voice1::UMP_MESSAGE_TYPE => {
-                ChannelVoice1(crate::channel_voice1::ChannelVoice1::try_from(buffer)?.into())
+                ChannelVoice1(crate::channel_voice1::ChannelVoice1::try_from(buffer)?)
             }
             #[cfg(feature = "channel-voice2")]
             crate::channel_voice2::UMP_MESSAGE_TYPE => {
-                ChannelVoice2(crate::channel_voice2::ChannelVoice2::try_from(buffer)?.into())
+                ChannelVoice2(crate::channel_voice2::ChannelVoice2::try_from(buffer)?)
             }
             #[cfg(feature = "sysex7")]
-            crate::sysex7::UMP_MESSAGE_TYPE => {
-                Sysex7(crate::sysex7::Sysex7::try_from(buffer)?.into())
-            }
+            crate::sysex7::UMP_MESSAGE_TYPE => Sysex7(crate::sysex7::Sysex7::try_from(buffer)?),
             #[cfg(feature = "sysex8")]
             crate::sysex8::UMP_MESSAGE_TYPE => {
-                Sysex8(crate::sysex8::Sysex8::try_from(buffer)?.into())
+                if crate::sysex8::is_mixed_data_set(buffer) {
+                    MixedDataSet(crate::sysex8::MixedDataSet::try_from(buffer)?)
+                } else {
+                    Sysex8(crate::sysex8::Sysex8::try_from(buffer)?)
+                }
             }
             #[cfg(feature = "system-common")]
             crate::system_common::UMP_MESSAGE_TYPE => {
-                SystemCommon(crate::system_common::SystemCommon::try_from(buffer)?.into())
+                SystemCommon(crate::system_common::SystemCommon::try_from(buffer)?)
             }
             #[cfg(feature = "ump-stream")]
             crate::ump_stream::UMP_MESSAGE_TYPE => {
-                UmpStream(crate::ump_stream::UmpStream::try_from(buffer)?.into())
+                UmpStream(crate::ump_stream::UmpStream::try_from(buffer)?)
             }
             #[cfg(feature = "utility")]
-            crate::utility::UMP_MESSAGE_TYPE => {
-                Utility(crate::utility::Utility::try_from(buffer)?.into())
-            }
+            crate::utility::UMP_MESSAGE_TYPE => Utility(crate::utility::Utility::try_from(buffer)?),
+            #[cfg(feature = "unknown")]
+            _ => Unknown(crate::UnknownUmp::try_from(buffer)?),
+            #[cfg(not(feature = "unknown"))]
             _ => Err(crate::error::Error::InvalidData(
                 "Couldn't interpret ump message type",
             ))?,
@@ -83,8 +105,10 @@ impl<'a> core::convert::TryFrom<&'a [u32]> for UmpMessage<&'a [u32]> {
 #[derive(
     derive_more::From,
     midi2_proc::Data,
+    midi2_proc::OptionalChanneled,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -98,6 +122,7 @@ impl<'a> core::convert::TryFrom<&'a [u32]> for UmpMessage<&'a [u32]> {
 ))]
 pub enum BytesMessage<B: crate::buffer::Bytes> {
     #[cfg(feature = "channel-voice1")]
+    #[channeled]
     ChannelVoice1(crate::channel_voice1::ChannelVoice1<B>),
     #[cfg(feature = "sysex7")]
     Sysex7(crate::sysex7::Sysex7<B>),
@@ -113,21 +138,19 @@ pub enum BytesMessage<B: crate::buffer::Bytes> {
 impl<'a> core::convert::TryFrom<&'a [u8]> for BytesMessage<&'a [u8]> {
     type Error = crate::error::Error;
     fn try_from(buffer: &'a [u8]) -> Result<Self, Self::Error> {
-        if buffer.len() < 1 {
+        if buffer.is_empty() {
             return Err(crate::error::Error::InvalidData("Bytes slice is empty"));
         }
         use BytesMessage::*;
 
         Ok(match buffer[0] {
             #[cfg(feature = "channel-voice1")]
-            0x80..=0xEF => {
-                ChannelVoice1(crate::channel_voice1::ChannelVoice1::try_from(buffer)?.into())
-            }
+            0x80..=0xEF => ChannelVoice1(crate::channel_voice1::ChannelVoice1::try_from(buffer)?),
             #[cfg(feature = "sysex7")]
-            0xF0 => Sysex7(crate::sysex7::Sysex7::try_from(buffer)?.into()),
+            0xF0 => Sysex7(crate::sysex7::Sysex7::try_from(buffer)?),
             #[cfg(feature = "system-common")]
             0xF1..=0xF6 | 0xF8..=0xFF => {
-                SystemCommon(crate::system_common::SystemCommon::try_from(buffer)?.into())
+                SystemCommon(crate::system_common::SystemCommon::try_from(buffer)?)
             }
             _ => Err(crate::error::Error::InvalidData(
                 "Couldn't interpret bytes message type",
@@ -155,6 +178,97 @@ mod tests {
         };
     }
 
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn from_mut_byte_data() {
+        use crate::{channel_voice1::ChannelVoice1, traits::Channeled};
+
+        let mut buffer = [0xAB, 0x60, 0x33];
+        let Ok(BytesMessage::ChannelVoice1(mut message)) = BytesMessage::try_from(&mut buffer[..])
+        else {
+            panic!();
+        };
+        message.set_channel(ux::u4::new(0x2));
+        assert!(matches!(message, ChannelVoice1::KeyPressure(_)));
+        assert_eq!(buffer, [0xA2, 0x60, 0x33]);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice2")]
+    fn from_mut_ump_data() {
+        use crate::{channel_voice2::ChannelVoice2, traits::Channeled};
+
+        let mut buffer = [0x4BAC_5900, 0xC0B8_3064];
+        let Ok(UmpMessage::ChannelVoice2(mut message)) = UmpMessage::try_from(&mut buffer[..])
+        else {
+            panic!();
+        };
+        message.set_channel(ux::u4::new(0x3));
+        assert!(matches!(message, ChannelVoice2::KeyPressure(_)));
+        assert_eq!(buffer, [0x4BA3_5900, 0xC0B8_3064]);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice2")]
+    fn from_mut_ump_data_invalid() {
+        let mut buffer = [0x4B7C_5900];
+        assert!(UmpMessage::try_from(&mut buffer[..]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn bytes_message_channel() {
+        use crate::traits::OptionalChanneled;
+
+        let mut buffer = [0xAB, 0x60, 0x33];
+        let mut message = BytesMessage::try_from(&mut buffer[..]).unwrap();
+        assert_eq!(message.channel(), Some(ux::u4::new(0xB)));
+        message.set_channel(ux::u4::new(0x2));
+        assert_eq!(buffer, [0xA2, 0x60, 0x33]);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice2")]
+    fn optional_group_and_channel() {
+        use crate::traits::{OptionalChanneled, OptionalGrouped};
+
+        let mut buffer = [0x4BAC_5900, 0xC0B8_3064];
+        let mut message = UmpMessage::try_from(&mut buffer[..]).unwrap();
+        assert_eq!(message.group(), Some(ux::u4::new(0xB)));
+        assert_eq!(message.channel(), Some(ux::u4::new(0xC)));
+        message.set_group(ux::u4::new(0x1));
+        message.set_channel(ux::u4::new(0x2));
+        assert_eq!(buffer, [0x41A2_5900, 0xC0B8_3064]);
+    }
+
+    #[test]
+    #[cfg(feature = "utility")]
+    fn utility_has_no_group() {
+        use crate::traits::{OptionalChanneled, OptionalGrouped};
+
+        let mut buffer = [0x0020_1234, 0x0, 0x0, 0x0];
+        let mut message = UmpMessage::try_from(&mut buffer[..]).unwrap();
+        assert_eq!(message.group(), None);
+        assert_eq!(message.channel(), None);
+        message.set_group(ux::u4::new(0x1));
+        message.set_channel(ux::u4::new(0x2));
+        assert_eq!(buffer, [0x0020_1234, 0x0, 0x0, 0x0]);
+    }
+
+    #[test]
+    #[cfg(feature = "flex-data")]
+    fn flex_data_optional_channel() {
+        use crate::traits::{OptionalChanneled, OptionalGrouped};
+
+        let mut buffer = [0xD410_0105, 0x54C3_A172, 0x0, 0x0];
+        let mut message = UmpMessage::try_from(&mut buffer[..]).unwrap();
+        assert_eq!(message.group(), Some(ux::u4::new(0x4)));
+        assert_eq!(message.channel(), None);
+        message.set_channel(ux::u4::new(0x7));
+        assert_eq!(message.channel(), Some(ux::u4::new(0x7)));
+        assert_eq!(buffer, [0xD407_0105, 0x54C3_A172, 0x0, 0x0]);
+    }
+
     #[cfg(feature = "ump-stream")]
     #[test]
     fn ump_stream() {
@@ -215,6 +329,30 @@ mod tests {
         };
     }
 
+    #[cfg(feature = "sysex8")]
+    #[test]
+    fn mixed_data_set_header() {
+        use crate::sysex8::MixedDataSet;
+
+        let buffer = [0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234];
+        let message = UmpMessage::try_from(&buffer[..]);
+        let Ok(UmpMessage::MixedDataSet(MixedDataSet::Header(_))) = message else {
+            panic!();
+        };
+    }
+
+    #[cfg(feature = "sysex8")]
+    #[test]
+    fn mixed_data_set_payload() {
+        use crate::sysex8::MixedDataSet;
+
+        let buffer = [0x539A_0001, 0x0203_0405, 0x0607_0809, 0x0A0B_0C0D];
+        let message = UmpMessage::try_from(&buffer[..]);
+        let Ok(UmpMessage::MixedDataSet(MixedDataSet::Payload(_))) = message else {
+            panic!();
+        };
+    }
+
     #[cfg(feature = "sysex7")]
     #[test]
     fn sysex7() {
@@ -268,6 +406,22 @@ mod tests {
         };
     }
 
+    #[cfg(feature = "unknown")]
+    #[test]
+    fn unknown_message_type() {
+        use crate::traits::Data;
+
+        let buffer = [0xB123_4567, 0x89AB_CDEF, 0x0123_4567, 0x0];
+        let message = UmpMessage::try_from(&buffer[..]);
+        assert_eq!(
+            message,
+            Ok(UmpMessage::Unknown(
+                crate::UnknownUmp::try_from(&buffer[..]).unwrap()
+            ))
+        );
+        assert_eq!(message.unwrap().data(), &buffer[..3]);
+    }
+
     #[cfg(feature = "channel-voice1")]
     #[test]
     fn from_level2() {
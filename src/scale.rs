@@ -0,0 +1,191 @@
+//! Conversion of values between the different resolutions used by
+//! MIDI 1.0 and MIDI 2.0 messages.
+//!
+//! Upscaling follows the min-center-max scheme described in the
+//! MIDI 2.0 specification. The minimum, center and maximum values of the
+//! narrower range are mapped onto the minimum, center and maximum of the
+//! wider range. Values at or below the center are simply shifted,
+//! while values above the center have their lower bits repeated into
+//! the expanded space.
+//!
+//! Downscaling drops the low bits. Upscaling and then downscaling
+//! always gives back the original value.
+//!
+//! ```rust
+//! use midi2::{scale, ux::u7};
+//!
+//! assert_eq!(scale::u7_to_u16(u7::new(0x0)), 0x0);
+//! assert_eq!(scale::u7_to_u16(u7::new(0x40)), 0x8000);
+//! assert_eq!(scale::u7_to_u16(u7::new(0x7F)), 0xFFFF);
+//!
+//! assert_eq!(scale::u16_to_u7(0x8000), u7::new(0x40));
+//! ```
+
+use crate::ux::{u14, u7};
+
+/// Upscale a 7 bit value to 16 bits.
+pub fn u7_to_u16(value: u7) -> u16 {
+    scale_up(u8::from(value).into(), 7, 16) as u16
+}
+
+/// Upscale a 7 bit value to 32 bits.
+pub fn u7_to_u32(value: u7) -> u32 {
+    scale_up(u8::from(value).into(), 7, 32)
+}
+
+/// Upscale a 14 bit value to 32 bits.
+pub fn u14_to_u32(value: u14) -> u32 {
+    scale_up(u16::from(value).into(), 14, 32)
+}
+
+/// Downscale a 16 bit value to 7 bits.
+pub fn u16_to_u7(value: u16) -> u7 {
+    u7::new(scale_down(value.into(), 16, 7) as u8)
+}
+
+/// Downscale a 32 bit value to 7 bits.
+pub fn u32_to_u7(value: u32) -> u7 {
+    u7::new(scale_down(value, 32, 7) as u8)
+}
+
+/// Downscale a 32 bit value to 14 bits.
+pub fn u32_to_u14(value: u32) -> u14 {
+    u14::new(scale_down(value, 32, 14) as u16)
+}
+
+fn scale_up(value: u32, src_bits: u32, dst_bits: u32) -> u32 {
+    let scale_bits = dst_bits - src_bits;
+    let result = value << scale_bits;
+    if value <= 1 << (src_bits - 1) {
+        return result;
+    }
+
+    // fill the expanded space with copies of the bits below the msb
+    let repeat_bits = src_bits - 1;
+    let repeat_value = value & ((1 << repeat_bits) - 1);
+    let mut fill = 0;
+    let mut shift = scale_bits as i32 - repeat_bits as i32;
+    while shift > -(repeat_bits as i32) {
+        fill |= if shift >= 0 {
+            repeat_value << shift
+        } else {
+            repeat_value >> -shift
+        };
+        shift -= repeat_bits as i32;
+    }
+    result | fill
+}
+
+fn scale_down(value: u32, src_bits: u32, dst_bits: u32) -> u32 {
+    value >> (src_bits - dst_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    // A direct transcription of the reference implementation
+    // given in the MIDI 2.0 specification.
+    fn reference_scale_up(src_val: u32, src_bits: u8, dst_bits: u8) -> u32 {
+        let scale_bits = dst_bits - src_bits;
+        let mut bit_shifted_value = src_val << scale_bits;
+        let src_center = 1_u32 << (src_bits - 1);
+        if src_val <= src_center {
+            return bit_shifted_value;
+        }
+        let repeat_bits = src_bits - 1;
+        let repeat_mask = (1_u32 << repeat_bits) - 1;
+        let mut repeat_value = src_val & repeat_mask;
+        if scale_bits > repeat_bits {
+            repeat_value <<= scale_bits - repeat_bits;
+        } else {
+            repeat_value >>= repeat_bits - scale_bits;
+        }
+        while repeat_value != 0 {
+            bit_shifted_value |= repeat_value;
+            repeat_value >>= repeat_bits;
+        }
+        bit_shifted_value
+    }
+
+    #[test]
+    fn u7_to_u16_key_values() {
+        assert_eq!(u7_to_u16(u7::new(0x0)), 0x0);
+        assert_eq!(u7_to_u16(u7::new(0x1)), 0x200);
+        assert_eq!(u7_to_u16(u7::new(0x40)), 0x8000);
+        assert_eq!(u7_to_u16(u7::new(0x41)), 0x8208);
+        assert_eq!(u7_to_u16(u7::new(0x7F)), 0xFFFF);
+    }
+
+    #[test]
+    fn u7_to_u32_key_values() {
+        assert_eq!(u7_to_u32(u7::new(0x0)), 0x0);
+        assert_eq!(u7_to_u32(u7::new(0x40)), 0x8000_0000);
+        assert_eq!(u7_to_u32(u7::new(0x7F)), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn u14_to_u32_key_values() {
+        assert_eq!(u14_to_u32(u14::new(0x0)), 0x0);
+        assert_eq!(u14_to_u32(u14::new(0x2000)), 0x8000_0000);
+        assert_eq!(u14_to_u32(u14::new(0x3FFF)), 0xFFFF_FFFF);
+    }
+
+    proptest! {
+        #[test]
+        fn u7_to_u16_matches_reference(v in 0_u8..0x80) {
+            prop_assert_eq!(u32::from(u7_to_u16(u7::new(v))), reference_scale_up(v.into(), 7, 16));
+        }
+
+        #[test]
+        fn u7_to_u32_matches_reference(v in 0_u8..0x80) {
+            prop_assert_eq!(u7_to_u32(u7::new(v)), reference_scale_up(v.into(), 7, 32));
+        }
+
+        #[test]
+        fn u14_to_u32_matches_reference(v in 0_u16..0x4000) {
+            prop_assert_eq!(u14_to_u32(u14::new(v)), reference_scale_up(v.into(), 14, 32));
+        }
+
+        #[test]
+        fn u7_u16_round_trip(v in 0_u8..0x80) {
+            prop_assert_eq!(u16_to_u7(u7_to_u16(u7::new(v))), u7::new(v));
+        }
+
+        #[test]
+        fn u7_u32_round_trip(v in 0_u8..0x80) {
+            prop_assert_eq!(u32_to_u7(u7_to_u32(u7::new(v))), u7::new(v));
+        }
+
+        #[test]
+        fn u14_u32_round_trip(v in 0_u16..0x4000) {
+            prop_assert_eq!(u32_to_u14(u14_to_u32(u14::new(v))), u14::new(v));
+        }
+
+        #[test]
+        fn u16_to_u7_is_monotonic(a: u16, b: u16) {
+            let (a, b) = (a.min(b), a.max(b));
+            prop_assert!(u16_to_u7(a) <= u16_to_u7(b));
+        }
+
+        #[test]
+        fn u32_to_u7_is_monotonic(a: u32, b: u32) {
+            let (a, b) = (a.min(b), a.max(b));
+            prop_assert!(u32_to_u7(a) <= u32_to_u7(b));
+        }
+
+        #[test]
+        fn u32_to_u14_is_monotonic(a: u32, b: u32) {
+            let (a, b) = (a.min(b), a.max(b));
+            prop_assert!(u32_to_u14(a) <= u32_to_u14(b));
+        }
+
+        #[test]
+        fn u14_to_u32_is_monotonic(a in 0_u16..0x4000, b in 0_u16..0x4000) {
+            let (a, b) = (a.min(b), a.max(b));
+            prop_assert!(u14_to_u32(u14::new(a)) <= u14_to_u32(u14::new(b)));
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! Reading and writing Standard MIDI Files.
+//!
+//! Files of format 0, 1 and 2 are supported. A file is read with
+//! [Smf::parse], which checks the chunk structure of the whole file.
+//! The events of each track are then read lazily through an iterator.
+//! Reading doesn't allocate: sysex, escape and meta event data
+//! are borrowed from the file data.
+//!
+//! Channel voice events are read into the
+//! [ChannelVoice1](crate::channel_voice1::ChannelVoice1) aggregate, with running status
+//! resolved, and meta events into the typed [MetaEvent] enum.
+//!
+//! ```rust
+//! use midi2::{prelude::*, smf, BufferSink};
+//!
+//! let header = smf::Header {
+//!     format: smf::Format::SingleTrack,
+//!     number_of_tracks: 1,
+//!     division: smf::Division::TicksPerQuarterNote(u15::new(96)),
+//! };
+//! let mut note_on = channel_voice1::NoteOn::<[u8; 3]>::new();
+//! note_on.set_note(u7::new(0x3C));
+//! note_on.set_velocity(u7::new(0x40));
+//! let track = [
+//!     smf::TrackEvent {
+//!         delta_time: 0,
+//!         event: smf::Event::Meta(smf::MetaEvent::Tempo(u24::new(500_000))),
+//!     },
+//!     smf::TrackEvent {
+//!         delta_time: 96,
+//!         event: smf::Event::Midi(note_on.into()),
+//!     },
+//! ];
+//!
+//! let writer = smf::SmfWriter::new();
+//! let mut sink = BufferSink::new(Vec::new());
+//! writer.write_header(&header, &mut sink).unwrap();
+//! writer.write_track(&track, &mut sink).unwrap();
+//!
+//! let file = smf::Smf::parse(sink.data()).unwrap();
+//! assert_eq!(file.header(), header);
+//!
+//! let track = file.tracks().next().unwrap();
+//! let mut events = track.events();
+//! assert_eq!(
+//!     events.next(),
+//!     Some(Ok(smf::TrackEvent {
+//!         delta_time: 0,
+//!         event: smf::Event::Meta(smf::MetaEvent::Tempo(u24::new(500_000))),
+//!     })),
+//! );
+//! assert_eq!(events.next().unwrap().unwrap().delta_time, 96);
+//! assert_eq!(
+//!     events.next(),
+//!     Some(Ok(smf::TrackEvent {
+//!         delta_time: 0,
+//!         event: smf::Event::Meta(smf::MetaEvent::EndOfTrack),
+//!     })),
+//! );
+//! assert_eq!(events.next(), None);
+//! ```
+
+use crate::ux::u15;
+
+mod event;
+mod reader;
+mod writer;
+
+pub use event::*;
+pub use reader::*;
+pub use writer::*;
+
+const HEADER_CHUNK_ID: &[u8; 4] = b"MThd";
+const TRACK_CHUNK_ID: &[u8; 4] = b"MTrk";
+const CHUNK_HEADER_SIZE: usize = 8;
+const HEADER_LENGTH: usize = 6;
+
+const SMPTE_DIVISION: u16 = 0x8000;
+
+// the largest value a variable length quantity can hold
+const MAX_VARIABLE_LENGTH: u32 = 0x0FFF_FFFF;
+
+const ERR_VARIABLE_LENGTH_TOO_LONG: &str = "Variable length quantity longer than four bytes";
+const ERR_VARIABLE_LENGTH_TOO_LARGE: &str = "Value too large for a variable length quantity";
+
+/// How the tracks of a file relate to each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// A single multi-channel track.
+    #[default]
+    SingleTrack,
+    /// Simultaneous tracks of a single sequence.
+    MultipleTracks,
+    /// Independent single track sequences.
+    MultipleSongs,
+}
+
+/// The meaning of the delta times in a file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Division {
+    TicksPerQuarterNote(u15),
+    /// Time code based division.
+    ///
+    /// `frames_per_second` is one of 24, 25, 29 (30 drop frame) or 30.
+    Smpte {
+        frames_per_second: u8,
+        ticks_per_frame: u8,
+    },
+}
+
+impl Default for Division {
+    fn default() -> Self {
+        Division::TicksPerQuarterNote(u15::new(96))
+    }
+}
+
+/// The contents of the header chunk of a file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Header {
+    pub format: Format,
+    pub number_of_tracks: u16,
+    pub division: Division,
+}
+
+// read a variable length quantity, advancing the index past it
+fn read_variable_length(data: &[u8], index: &mut usize) -> Result<Option<u32>, &'static str> {
+    let mut value = 0_u32;
+    for i in 0..4 {
+        let Some(&byte) = data.get(*index + i) else {
+            return Ok(None);
+        };
+        value = (value << 7) | u32::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            *index += i + 1;
+            return Ok(Some(value));
+        }
+    }
+    Err(ERR_VARIABLE_LENGTH_TOO_LONG)
+}
+
+// encode a variable length quantity, returning the buffer and the
+// number of bytes used
+fn variable_length(value: u32) -> Result<([u8; 4], usize), &'static str> {
+    if value > MAX_VARIABLE_LENGTH {
+        return Err(ERR_VARIABLE_LENGTH_TOO_LARGE);
+    }
+    let mut len = 1;
+    while len < 4 && value >> (7 * len) != 0 {
+        len += 1;
+    }
+    let mut data = [0x0; 4];
+    for (i, byte) in data[..len].iter_mut().enumerate() {
+        let shift = 7 * (len - 1 - i);
+        *byte = ((value >> shift) & 0x7F) as u8;
+        if i != len - 1 {
+            *byte |= 0x80;
+        }
+    }
+    Ok((data, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_variable_length_examples() {
+        for (data, value) in [
+            (&[0x00][..], 0x0),
+            (&[0x7F][..], 0x7F),
+            (&[0x81, 0x00][..], 0x80),
+            (&[0xC0, 0x00][..], 0x2000),
+            (&[0xFF, 0x7F][..], 0x3FFF),
+            (&[0x81, 0x80, 0x00][..], 0x4000),
+            (&[0xFF, 0xFF, 0xFF, 0x7F][..], 0x0FFF_FFFF),
+        ] {
+            let mut index = 0;
+            assert_eq!(read_variable_length(data, &mut index), Ok(Some(value)));
+            assert_eq!(index, data.len());
+        }
+    }
+
+    #[test]
+    fn read_variable_length_too_long() {
+        let mut index = 0;
+        assert_eq!(
+            read_variable_length(&[0x80, 0x80, 0x80, 0x80, 0x00], &mut index),
+            Err(ERR_VARIABLE_LENGTH_TOO_LONG),
+        );
+    }
+
+    #[test]
+    fn read_variable_length_truncated() {
+        let mut index = 0;
+        assert_eq!(read_variable_length(&[0x81], &mut index), Ok(None));
+        assert_eq!(index, 0);
+    }
+
+    #[test]
+    fn variable_length_round_trip() {
+        for value in [0x0, 0x7F, 0x80, 0x2000, 0x3FFF, 0x4000, 0x0FFF_FFFF] {
+            let (data, len) = variable_length(value).unwrap();
+            let mut index = 0;
+            assert_eq!(
+                read_variable_length(&data[..len], &mut index),
+                Ok(Some(value))
+            );
+            assert_eq!(index, len);
+        }
+    }
+    #[test]
+    fn variable_length_too_large() {
+        assert_eq!(
+            variable_length(0x1000_0000),
+            Err(ERR_VARIABLE_LENGTH_TOO_LARGE)
+        );
+    }
+}
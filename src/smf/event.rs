@@ -0,0 +1,258 @@
+use crate::{channel_voice1::ChannelVoice1, error::Error, ux::u24};
+
+const ERR_INVALID_META_LENGTH: &str = "Meta event data has the wrong length for its type";
+
+const META_TEXT: u8 = 0x01;
+const META_COPYRIGHT: u8 = 0x02;
+const META_TRACK_NAME: u8 = 0x03;
+const META_INSTRUMENT_NAME: u8 = 0x04;
+const META_LYRIC: u8 = 0x05;
+const META_MARKER: u8 = 0x06;
+const META_CUE_POINT: u8 = 0x07;
+const META_END_OF_TRACK: u8 = 0x2F;
+const META_TEMPO: u8 = 0x51;
+const META_TIME_SIGNATURE: u8 = 0x58;
+const META_KEY_SIGNATURE: u8 = 0x59;
+
+/// An event within a track, preceded by the number of ticks
+/// since the previous event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrackEvent<'a> {
+    pub delta_time: u32,
+    pub event: Event<'a>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event<'a> {
+    /// A channel voice message.
+    ///
+    /// System common and real time messages have no place of their own
+    /// in a track and are written as [Event::Escape] events.
+    Midi(ChannelVoice1<[u8; 3]>),
+    /// A sysex message, without the leading `0xF0`.
+    ///
+    /// A complete message ends in `0xF7`. Messages which are split
+    /// into packets continue in the following [Event::Escape] events.
+    Sysex(&'a [u8]),
+    /// Arbitrary data to be sent as is, such as a sysex continuation
+    /// packet or system real time messages.
+    Escape(&'a [u8]),
+    Meta(MetaEvent<'a>),
+}
+
+/// The kinds of text carried by [MetaEvent::Text].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextType {
+    Text,
+    Copyright,
+    TrackName,
+    InstrumentName,
+    Lyric,
+    Marker,
+    CuePoint,
+}
+
+/// Non-MIDI data within a track.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MetaEvent<'a> {
+    /// Microseconds per quarter note.
+    Tempo(u24),
+    /// The denominator is given as a power of two,
+    /// so a value of 3 is an eighth note.
+    TimeSignature {
+        numerator: u8,
+        denominator: u8,
+        clocks_per_metronome_click: u8,
+        number_of_32nd_notes: u8,
+    },
+    /// Positive values count sharps and negative values count flats.
+    KeySignature {
+        sharps_flats: i8,
+        minor: bool,
+    },
+    Text {
+        text_type: TextType,
+        text: &'a [u8],
+    },
+    EndOfTrack,
+    /// Any other meta event, left uninterpreted.
+    Other {
+        meta_type: u8,
+        data: &'a [u8],
+    },
+}
+
+impl<'a> MetaEvent<'a> {
+    pub(crate) fn parse(meta_type: u8, data: &'a [u8]) -> Result<Self, Error> {
+        let text = |text_type| {
+            Ok(MetaEvent::Text {
+                text_type,
+                text: data,
+            })
+        };
+        match (meta_type, data) {
+            (META_TEXT, _) => text(TextType::Text),
+            (META_COPYRIGHT, _) => text(TextType::Copyright),
+            (META_TRACK_NAME, _) => text(TextType::TrackName),
+            (META_INSTRUMENT_NAME, _) => text(TextType::InstrumentName),
+            (META_LYRIC, _) => text(TextType::Lyric),
+            (META_MARKER, _) => text(TextType::Marker),
+            (META_CUE_POINT, _) => text(TextType::CuePoint),
+            (META_END_OF_TRACK, []) => Ok(MetaEvent::EndOfTrack),
+            (META_TEMPO, &[b0, b1, b2]) => Ok(MetaEvent::Tempo(u24::new(
+                u32::from(b0) << 16 | u32::from(b1) << 8 | u32::from(b2),
+            ))),
+            (META_TIME_SIGNATURE, &[numerator, denominator, clocks, number_of_32nd_notes]) => {
+                Ok(MetaEvent::TimeSignature {
+                    numerator,
+                    denominator,
+                    clocks_per_metronome_click: clocks,
+                    number_of_32nd_notes,
+                })
+            }
+            (META_KEY_SIGNATURE, &[sharps_flats, minor]) => Ok(MetaEvent::KeySignature {
+                sharps_flats: sharps_flats as i8,
+                minor: minor != 0,
+            }),
+            (META_END_OF_TRACK | META_TEMPO | META_TIME_SIGNATURE | META_KEY_SIGNATURE, _) => {
+                Err(Error::InvalidData(ERR_INVALID_META_LENGTH))
+            }
+            _ => Ok(MetaEvent::Other { meta_type, data }),
+        }
+    }
+
+    pub fn meta_type(&self) -> u8 {
+        match self {
+            MetaEvent::Tempo(_) => META_TEMPO,
+            MetaEvent::TimeSignature { .. } => META_TIME_SIGNATURE,
+            MetaEvent::KeySignature { .. } => META_KEY_SIGNATURE,
+            MetaEvent::Text { text_type, .. } => match text_type {
+                TextType::Text => META_TEXT,
+                TextType::Copyright => META_COPYRIGHT,
+                TextType::TrackName => META_TRACK_NAME,
+                TextType::InstrumentName => META_INSTRUMENT_NAME,
+                TextType::Lyric => META_LYRIC,
+                TextType::Marker => META_MARKER,
+                TextType::CuePoint => META_CUE_POINT,
+            },
+            MetaEvent::EndOfTrack => META_END_OF_TRACK,
+            MetaEvent::Other { meta_type, .. } => *meta_type,
+        }
+    }
+
+    // the data of the event, written into the scratch buffer
+    // for the fixed size events
+    pub(crate) fn data<'b>(&'b self, scratch: &'b mut [u8; 4]) -> &'b [u8] {
+        match self {
+            MetaEvent::Tempo(tempo) => {
+                let tempo = u32::from(*tempo);
+                scratch[..3].copy_from_slice(&tempo.to_be_bytes()[1..]);
+                &scratch[..3]
+            }
+            MetaEvent::TimeSignature {
+                numerator,
+                denominator,
+                clocks_per_metronome_click,
+                number_of_32nd_notes,
+            } => {
+                *scratch = [
+                    *numerator,
+                    *denominator,
+                    *clocks_per_metronome_click,
+                    *number_of_32nd_notes,
+                ];
+                &scratch[..]
+            }
+            MetaEvent::KeySignature {
+                sharps_flats,
+                minor,
+            } => {
+                scratch[0] = *sharps_flats as u8;
+                scratch[1] = u8::from(*minor);
+                &scratch[..2]
+            }
+            MetaEvent::Text { text, .. } => text,
+            MetaEvent::EndOfTrack => &[],
+            MetaEvent::Other { data, .. } => data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_tempo() {
+        assert_eq!(
+            MetaEvent::parse(0x51, &[0x07, 0xA1, 0x20]),
+            Ok(MetaEvent::Tempo(u24::new(500_000))),
+        );
+    }
+
+    #[test]
+    fn parse_key_signature() {
+        assert_eq!(
+            MetaEvent::parse(0x59, &[0xFD, 0x01]),
+            Ok(MetaEvent::KeySignature {
+                sharps_flats: -3,
+                minor: true,
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_text() {
+        assert_eq!(
+            MetaEvent::parse(0x03, b"Piano"),
+            Ok(MetaEvent::Text {
+                text_type: TextType::TrackName,
+                text: b"Piano",
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_other() {
+        assert_eq!(
+            MetaEvent::parse(0x7F, &[0x1, 0x2]),
+            Ok(MetaEvent::Other {
+                meta_type: 0x7F,
+                data: &[0x1, 0x2],
+            }),
+        );
+    }
+
+    #[test]
+    fn parse_invalid_length() {
+        assert_eq!(
+            MetaEvent::parse(0x58, &[0x04, 0x02, 0x18]),
+            Err(Error::InvalidData(ERR_INVALID_META_LENGTH)),
+        );
+    }
+
+    #[test]
+    fn data_round_trip() {
+        for event in [
+            MetaEvent::Tempo(u24::new(500_000)),
+            MetaEvent::TimeSignature {
+                numerator: 6,
+                denominator: 3,
+                clocks_per_metronome_click: 36,
+                number_of_32nd_notes: 8,
+            },
+            MetaEvent::KeySignature {
+                sharps_flats: 2,
+                minor: false,
+            },
+            MetaEvent::EndOfTrack,
+        ] {
+            let mut scratch = [0x0; 4];
+            assert_eq!(
+                MetaEvent::parse(event.meta_type(), event.data(&mut scratch)),
+                Ok(event.clone()),
+            );
+        }
+    }
+}
@@ -0,0 +1,589 @@
+use crate::{
+    channel_voice1::ChannelVoice1,
+    error::{Error, SmfError},
+    smf::{
+        self, Division, Event, Format, Header, MetaEvent, TrackEvent, CHUNK_HEADER_SIZE,
+        HEADER_CHUNK_ID, HEADER_LENGTH, SMPTE_DIVISION, TRACK_CHUNK_ID,
+    },
+    traits::TryRebufferInto,
+    ux::u15,
+};
+
+const ERR_NOT_SMF: &str = "Missing the MThd header chunk";
+const ERR_INVALID_HEADER_LENGTH: &str = "Header chunk too short";
+const ERR_INVALID_FORMAT: &str = "Unknown file format";
+const ERR_INVALID_NUMBER_OF_TRACKS: &str = "Format 0 file with more than one track";
+const ERR_MISSING_TRACKS: &str = "File contains fewer tracks than its header declares";
+const ERR_TRUNCATED_CHUNK: &str = "File ends part way through a chunk header";
+const ERR_CHUNK_OVERRUN: &str = "Chunk length exceeds the file size";
+const ERR_TRUNCATED_EVENT: &str = "Track ends part way through an event";
+const ERR_DATA_WITHOUT_STATUS: &str = "Data byte without a preceding status byte";
+const ERR_UNEXPECTED_STATUS: &str = "Status byte part way through a channel message";
+const ERR_INVALID_STATUS: &str = "Status byte not allowed in track data";
+
+const SYSEX: u8 = 0xF0;
+const ESCAPE: u8 = 0xF7;
+const META: u8 = 0xFF;
+
+/// A Standard MIDI File read from a slice of bytes.
+///
+/// The chunk structure of the file is validated by [Smf::parse]
+/// while the events of the tracks are read on demand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Smf<'a> {
+    header: Header,
+    data: &'a [u8],
+    tracks_offset: usize,
+}
+
+impl<'a> Smf<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, SmfError> {
+        let (id, body, tracks_offset) = chunk(data, 0)?;
+        if id != HEADER_CHUNK_ID {
+            return Err(error(0, ERR_NOT_SMF));
+        }
+        if body.len() < HEADER_LENGTH {
+            return Err(error(4, ERR_INVALID_HEADER_LENGTH));
+        }
+
+        let format = match u16::from_be_bytes([body[0], body[1]]) {
+            0 => Format::SingleTrack,
+            1 => Format::MultipleTracks,
+            2 => Format::MultipleSongs,
+            _ => return Err(error(CHUNK_HEADER_SIZE, ERR_INVALID_FORMAT)),
+        };
+        let number_of_tracks = u16::from_be_bytes([body[2], body[3]]);
+        if format == Format::SingleTrack && number_of_tracks > 1 {
+            return Err(error(CHUNK_HEADER_SIZE + 2, ERR_INVALID_NUMBER_OF_TRACKS));
+        }
+        let division = match u16::from_be_bytes([body[4], body[5]]) {
+            v if v & SMPTE_DIVISION == 0 => Division::TicksPerQuarterNote(u15::new(v)),
+            _ => Division::Smpte {
+                frames_per_second: (body[4] as i8).unsigned_abs(),
+                ticks_per_frame: body[5],
+            },
+        };
+
+        // check every chunk fits within the file
+        let mut found_tracks = 0;
+        let mut offset = tracks_offset;
+        while offset < data.len() {
+            let (id, _, next) = chunk(data, offset)?;
+            if id == TRACK_CHUNK_ID {
+                found_tracks += 1;
+            }
+            offset = next;
+        }
+        if found_tracks < number_of_tracks {
+            return Err(error(data.len(), ERR_MISSING_TRACKS));
+        }
+
+        Ok(Smf {
+            header: Header {
+                format,
+                number_of_tracks,
+                division,
+            },
+            data,
+            tracks_offset,
+        })
+    }
+
+    pub fn header(&self) -> Header {
+        self.header
+    }
+
+    /// The track chunks of the file.
+    ///
+    /// Chunks of unknown type are skipped over.
+    pub fn tracks(&self) -> TrackIterator<'a> {
+        TrackIterator {
+            data: self.data,
+            offset: self.tracks_offset,
+        }
+    }
+}
+
+fn error(offset: usize, message: &'static str) -> SmfError {
+    SmfError {
+        offset,
+        error: Error::InvalidData(message),
+    }
+}
+
+// returns the chunk id and body, and the offset of the following chunk
+fn chunk(data: &[u8], offset: usize) -> Result<(&[u8], &[u8], usize), SmfError> {
+    let Some(header) = data.get(offset..offset + CHUNK_HEADER_SIZE) else {
+        return Err(error(offset, ERR_TRUNCATED_CHUNK));
+    };
+    let length = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let start = offset + CHUNK_HEADER_SIZE;
+    let Some(body) = start
+        .checked_add(length)
+        .and_then(|end| data.get(start..end))
+    else {
+        return Err(error(offset + 4, ERR_CHUNK_OVERRUN));
+    };
+    Ok((&header[..4], body, start + length))
+}
+
+/// Iterates over the tracks of an [Smf].
+#[derive(Clone, Debug)]
+pub struct TrackIterator<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> core::iter::Iterator for TrackIterator<'a> {
+    type Item = Track<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.offset < self.data.len() {
+            // the chunk structure is validated on parse
+            let (id, body, next) = chunk(self.data, self.offset).ok()?;
+            let offset = self.offset;
+            self.offset = next;
+            if id == TRACK_CHUNK_ID {
+                return Some(Track {
+                    data: body,
+                    offset: offset + CHUNK_HEADER_SIZE,
+                });
+            }
+        }
+        None
+    }
+}
+
+impl<'a> core::iter::FusedIterator for TrackIterator<'a> {}
+
+/// A track chunk of an [Smf].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Track<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Track<'a> {
+    /// The raw event data of the track.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Iterate over the events of the track.
+    ///
+    /// Iteration stops after an end of track event
+    /// or at the first malformed event.
+    pub fn events(&self) -> EventIterator<'a> {
+        EventIterator {
+            data: self.data,
+            offset: self.offset,
+            index: 0,
+            running_status: None,
+            done: false,
+        }
+    }
+}
+
+/// Iterates over the events of a [Track].
+#[derive(Clone, Debug)]
+pub struct EventIterator<'a> {
+    data: &'a [u8],
+    offset: usize,
+    index: usize,
+    running_status: Option<u8>,
+    done: bool,
+}
+
+impl<'a> EventIterator<'a> {
+    fn read_event(&mut self) -> Result<TrackEvent<'a>, &'static str> {
+        let delta_time =
+            smf::read_variable_length(self.data, &mut self.index)?.ok_or(ERR_TRUNCATED_EVENT)?;
+        let status = *self.data.get(self.index).ok_or(ERR_TRUNCATED_EVENT)?;
+
+        let event = match status {
+            0x00..=0x7F => {
+                let status = self.running_status.ok_or(ERR_DATA_WITHOUT_STATUS)?;
+                self.read_channel_message(status)?
+            }
+            0x80..=0xEF => {
+                self.index += 1;
+                self.running_status = Some(status);
+                self.read_channel_message(status)?
+            }
+            SYSEX => {
+                self.index += 1;
+                self.running_status = None;
+                Event::Sysex(self.read_data()?)
+            }
+            ESCAPE => {
+                self.index += 1;
+                self.running_status = None;
+                Event::Escape(self.read_data()?)
+            }
+            META => {
+                self.index += 1;
+                self.running_status = None;
+                let meta_type = *self.data.get(self.index).ok_or(ERR_TRUNCATED_EVENT)?;
+                self.index += 1;
+                let data = self.read_data()?;
+                match MetaEvent::parse(meta_type, data) {
+                    Ok(event) => Event::Meta(event),
+                    Err(Error::InvalidData(message)) => return Err(message),
+                    Err(Error::BufferOverflow) => unreachable!(),
+                }
+            }
+            _ => return Err(ERR_INVALID_STATUS),
+        };
+
+        Ok(TrackEvent { delta_time, event })
+    }
+
+    fn read_channel_message(&mut self, status: u8) -> Result<Event<'a>, &'static str> {
+        let size = match status {
+            0xC0..=0xDF => 2,
+            _ => 3,
+        };
+        let mut buffer = [status, 0x0, 0x0];
+        for byte in buffer[1..size].iter_mut() {
+            *byte = *self.data.get(self.index).ok_or(ERR_TRUNCATED_EVENT)?;
+            if *byte & 0x80 != 0 {
+                return Err(ERR_UNEXPECTED_STATUS);
+            }
+            self.index += 1;
+        }
+        let message = ChannelVoice1::try_from(&buffer[..size])
+            .and_then(|message| message.try_rebuffer_into().map_err(Error::from))
+            .map_err(|_| ERR_INVALID_STATUS)?;
+        Ok(Event::Midi(message))
+    }
+
+    // read data preceded by its variable length
+    fn read_data(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = smf::read_variable_length(self.data, &mut self.index)?
+            .ok_or(ERR_TRUNCATED_EVENT)? as usize;
+        let data = self
+            .data
+            .get(self.index..self.index + len)
+            .ok_or(ERR_TRUNCATED_EVENT)?;
+        self.index += len;
+        Ok(data)
+    }
+}
+
+impl<'a> core::iter::Iterator for EventIterator<'a> {
+    type Item = Result<TrackEvent<'a>, SmfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.index >= self.data.len() {
+            return None;
+        }
+        match self.read_event() {
+            Ok(event) => {
+                self.done = event.event == Event::Meta(MetaEvent::EndOfTrack);
+                Some(Ok(event))
+            }
+            Err(message) => {
+                self.done = true;
+                Some(Err(error(self.offset + self.index, message)))
+            }
+        }
+    }
+}
+
+impl<'a> core::iter::FusedIterator for EventIterator<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ux::u24;
+    use pretty_assertions::assert_eq;
+
+    fn header(format: u8, number_of_tracks: u8) -> [u8; 14] {
+        [
+            0x4D,
+            0x54,
+            0x68,
+            0x64,
+            0x00,
+            0x00,
+            0x00,
+            0x06,
+            0x00,
+            format,
+            0x00,
+            number_of_tracks,
+            0x00,
+            0x60,
+        ]
+    }
+
+    fn file(format: u8, tracks: &[&[u8]]) -> std::vec::Vec<u8> {
+        let mut data = header(format, tracks.len() as u8).to_vec();
+        for track in tracks {
+            data.extend_from_slice(b"MTrk");
+            data.extend_from_slice(&(track.len() as u32).to_be_bytes());
+            data.extend_from_slice(track);
+        }
+        data
+    }
+
+    fn events(track: &[u8]) -> std::vec::Vec<Result<TrackEvent<'_>, SmfError>> {
+        Track {
+            data: track,
+            offset: 0,
+        }
+        .events()
+        .collect()
+    }
+
+    fn midi(data: &[u8]) -> Event<'static> {
+        Event::Midi(
+            ChannelVoice1::try_from(data)
+                .unwrap()
+                .try_rebuffer_into()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn parse_header() {
+        let data = file(1, &[&[0x00, 0xFF, 0x2F, 0x00], &[0x00, 0xFF, 0x2F, 0x00]]);
+        assert_eq!(
+            Smf::parse(&data).unwrap().header(),
+            Header {
+                format: Format::MultipleTracks,
+                number_of_tracks: 2,
+                division: Division::TicksPerQuarterNote(u15::new(96)),
+            },
+        );
+    }
+
+    #[test]
+    fn parse_smpte_division() {
+        let mut data = file(0, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        data[12] = 0xE7;
+        data[13] = 0x28;
+        assert_eq!(
+            Smf::parse(&data).unwrap().header().division,
+            Division::Smpte {
+                frames_per_second: 25,
+                ticks_per_frame: 40,
+            },
+        );
+    }
+
+    #[test]
+    fn tracks() {
+        let data = file(2, &[&[0x00, 0xFF, 0x2F, 0x00], &[0x10, 0xFF, 0x2F, 0x00]]);
+        let smf = Smf::parse(&data).unwrap();
+        let tracks = smf.tracks().collect::<std::vec::Vec<_>>();
+        assert_eq!(tracks.len(), 2);
+        assert_eq!(tracks[1].data(), &[0x10, 0xFF, 0x2F, 0x00]);
+    }
+
+    #[test]
+    fn skips_unknown_chunks() {
+        let mut data = header(0, 1).to_vec();
+        data.extend_from_slice(&[0x4D, 0x54, 0x78, 0x78, 0x00, 0x00, 0x00, 0x02, 0x01, 0x02]);
+        data.extend_from_slice(&[0x4D, 0x54, 0x72, 0x6B, 0x00, 0x00, 0x00, 0x04]);
+        data.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+        let smf = Smf::parse(&data).unwrap();
+        assert_eq!(smf.tracks().count(), 1);
+    }
+
+    #[test]
+    fn not_smf() {
+        let mut data = file(0, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        data[0] = 0x0;
+        assert_eq!(Smf::parse(&data), Err(error(0, ERR_NOT_SMF)));
+    }
+
+    #[test]
+    fn invalid_format() {
+        let data = file(3, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        assert_eq!(Smf::parse(&data), Err(error(8, ERR_INVALID_FORMAT)));
+    }
+
+    #[test]
+    fn format_0_with_multiple_tracks() {
+        let data = file(0, &[&[0x00, 0xFF, 0x2F, 0x00], &[0x00, 0xFF, 0x2F, 0x00]]);
+        assert_eq!(
+            Smf::parse(&data),
+            Err(error(10, ERR_INVALID_NUMBER_OF_TRACKS))
+        );
+    }
+
+    #[test]
+    fn missing_tracks() {
+        let mut data = file(1, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        data[11] = 0x2;
+        assert_eq!(Smf::parse(&data), Err(error(26, ERR_MISSING_TRACKS)));
+    }
+
+    #[test]
+    fn chunk_overrun() {
+        let mut data = file(0, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        data[21] = 0x05;
+        assert_eq!(Smf::parse(&data), Err(error(18, ERR_CHUNK_OVERRUN)));
+    }
+
+    #[test]
+    fn huge_chunk_length() {
+        let mut data = file(0, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        data[18..22].copy_from_slice(&[0xFF; 4]);
+        assert_eq!(Smf::parse(&data), Err(error(18, ERR_CHUNK_OVERRUN)));
+    }
+
+    #[test]
+    fn truncated_chunk_header() {
+        let mut data = file(0, &[&[0x00, 0xFF, 0x2F, 0x00]]);
+        data.extend_from_slice(&[0x4D, 0x54]);
+        assert_eq!(Smf::parse(&data), Err(error(26, ERR_TRUNCATED_CHUNK)));
+    }
+
+    #[test]
+    fn short_header() {
+        let data = [0x4D, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00];
+        assert_eq!(Smf::parse(&data), Err(error(4, ERR_INVALID_HEADER_LENGTH)));
+    }
+
+    #[test]
+    fn channel_messages() {
+        assert_eq!(
+            events(&[0x00, 0x90, 0x3C, 0x40, 0x60, 0xC1, 0x05, 0x00, 0xFF, 0x2F, 0x00]),
+            std::vec![
+                Ok(TrackEvent {
+                    delta_time: 0,
+                    event: midi(&[0x90, 0x3C, 0x40]),
+                }),
+                Ok(TrackEvent {
+                    delta_time: 0x60,
+                    event: midi(&[0xC1, 0x05]),
+                }),
+                Ok(TrackEvent {
+                    delta_time: 0,
+                    event: Event::Meta(MetaEvent::EndOfTrack),
+                }),
+            ],
+        );
+    }
+
+    #[test]
+    fn running_status() {
+        let events = events(&[0x00, 0x90, 0x3C, 0x40, 0x81, 0x00, 0x3C, 0x00]);
+        assert_eq!(
+            events[1],
+            Ok(TrackEvent {
+                delta_time: 0x80,
+                event: midi(&[0x90, 0x3C, 0x00]),
+            }),
+        );
+        let Ok(TrackEvent {
+            event: Event::Midi(ChannelVoice1::NoteOn(_)),
+            ..
+        }) = events[1]
+        else {
+            panic!();
+        };
+    }
+
+    #[test]
+    fn meta_cancels_running_status() {
+        assert_eq!(
+            events(&[0x00, 0x90, 0x3C, 0x40, 0x00, 0xFF, 0x01, 0x00, 0x00, 0x3C, 0x00])[2],
+            Err(error(9, ERR_DATA_WITHOUT_STATUS)),
+        );
+    }
+
+    #[test]
+    fn sysex_and_escape() {
+        assert_eq!(
+            events(&[0x00, 0xF0, 0x03, 0x01, 0x02, 0xF7, 0x00, 0xF7, 0x01, 0xFA]),
+            std::vec![
+                Ok(TrackEvent {
+                    delta_time: 0,
+                    event: Event::Sysex(&[0x01, 0x02, 0xF7]),
+                }),
+                Ok(TrackEvent {
+                    delta_time: 0,
+                    event: Event::Escape(&[0xFA]),
+                }),
+            ],
+        );
+    }
+
+    #[test]
+    fn meta_events() {
+        assert_eq!(
+            events(&[
+                0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20, 0x00, 0xFF, 0x58, 0x04, 0x04, 0x02, 0x18,
+                0x08
+            ]),
+            std::vec![
+                Ok(TrackEvent {
+                    delta_time: 0,
+                    event: Event::Meta(MetaEvent::Tempo(u24::new(500_000))),
+                }),
+                Ok(TrackEvent {
+                    delta_time: 0,
+                    event: Event::Meta(MetaEvent::TimeSignature {
+                        numerator: 4,
+                        denominator: 2,
+                        clocks_per_metronome_click: 24,
+                        number_of_32nd_notes: 8,
+                    }),
+                }),
+            ],
+        );
+    }
+
+    #[test]
+    fn stops_after_end_of_track() {
+        assert_eq!(
+            events(&[0x00, 0xFF, 0x2F, 0x00, 0x00, 0x90, 0x3C, 0x40]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn truncated_event() {
+        assert_eq!(
+            events(&[0x00, 0x90, 0x3C]),
+            std::vec![Err(error(3, ERR_TRUNCATED_EVENT))],
+        );
+    }
+
+    #[test]
+    fn truncated_sysex() {
+        assert_eq!(
+            events(&[0x00, 0xF0, 0x05, 0x01, 0x02]),
+            std::vec![Err(error(3, ERR_TRUNCATED_EVENT))],
+        );
+    }
+
+    #[test]
+    fn status_part_way_through_message() {
+        assert_eq!(
+            events(&[0x00, 0x90, 0x3C, 0x80, 0x40]),
+            std::vec![Err(error(3, ERR_UNEXPECTED_STATUS))],
+        );
+    }
+
+    #[test]
+    fn system_common_status() {
+        assert_eq!(
+            events(&[0x00, 0xF2, 0x00, 0x00]),
+            std::vec![Err(error(1, ERR_INVALID_STATUS))],
+        );
+    }
+
+    #[test]
+    fn error_offset_within_file() {
+        let data = file(0, &[&[0x00, 0x90, 0x3C]]);
+        let smf = Smf::parse(&data).unwrap();
+        assert_eq!(
+            smf.tracks().next().unwrap().events().next(),
+            Some(Err(error(25, ERR_TRUNCATED_EVENT))),
+        );
+    }
+}
@@ -0,0 +1,427 @@
+use crate::{
+    error::{BufferOverflow, Error},
+    smf::{
+        self, Division, Event, Format, Header, MetaEvent, TrackEvent, HEADER_CHUNK_ID,
+        HEADER_LENGTH, SMPTE_DIVISION, TRACK_CHUNK_ID,
+    },
+    stream::BytesSink,
+    traits::Data,
+};
+
+const SYSEX: u8 = 0xF0;
+const ESCAPE: u8 = 0xF7;
+const META: u8 = 0xFF;
+
+/// Writes Standard MIDI File data out to a [BytesSink].
+///
+/// A file is written as a header followed by each of its tracks.
+/// Running status compression of the channel messages in a track
+/// is optional.
+///
+/// See the [module docs](crate::smf) for an example.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SmfWriter {
+    use_running_status: bool,
+}
+
+impl SmfWriter {
+    /// Create a writer with running status compression disabled.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn running_status(&self) -> bool {
+        self.use_running_status
+    }
+
+    pub fn set_running_status(&mut self, v: bool) {
+        self.use_running_status = v;
+    }
+
+    pub fn write_header<S: BytesSink>(
+        &self,
+        header: &Header,
+        sink: &mut S,
+    ) -> Result<(), BufferOverflow> {
+        let format: u16 = match header.format {
+            Format::SingleTrack => 0,
+            Format::MultipleTracks => 1,
+            Format::MultipleSongs => 2,
+        };
+        let division = match header.division {
+            Division::TicksPerQuarterNote(ticks) => u16::from(ticks),
+            Division::Smpte {
+                frames_per_second,
+                ticks_per_frame,
+            } => {
+                SMPTE_DIVISION
+                    | u16::from((frames_per_second as i8).wrapping_neg() as u8) << 8
+                    | u16::from(ticks_per_frame)
+            }
+        };
+        sink.write_bytes(HEADER_CHUNK_ID)?;
+        sink.write_bytes(&(HEADER_LENGTH as u32).to_be_bytes())?;
+        sink.write_bytes(&format.to_be_bytes())?;
+        sink.write_bytes(&header.number_of_tracks.to_be_bytes())?;
+        sink.write_bytes(&division.to_be_bytes())
+    }
+
+    /// Write a track chunk containing the given events.
+    ///
+    /// An end of track event is appended when the
+    /// events don't already end with one.
+    /// Delta times and data lengths above `0x0FFF_FFFF` can't be
+    /// encoded and fail with [Error::InvalidData].
+    pub fn write_track<S: BytesSink>(
+        &self,
+        events: &[TrackEvent],
+        sink: &mut S,
+    ) -> Result<(), Error> {
+        // measure the track before writing it out
+        let mut counter = Counter(0);
+        self.write_events(events, &mut counter)?;
+
+        sink.write_bytes(TRACK_CHUNK_ID)?;
+        sink.write_bytes(&(counter.0 as u32).to_be_bytes())?;
+        self.write_events(events, sink)
+    }
+
+    fn write_events<S: BytesSink>(&self, events: &[TrackEvent], sink: &mut S) -> Result<(), Error> {
+        let mut running_status = None;
+        for event in events {
+            write_variable_length(event.delta_time, sink)?;
+            match &event.event {
+                Event::Midi(message) => {
+                    let data = message.data();
+                    let status = data[0];
+                    if self.use_running_status && running_status == Some(status) {
+                        sink.write_bytes(&data[1..])?;
+                    } else {
+                        sink.write_bytes(data)?;
+                    }
+                    running_status = Some(status);
+                }
+                Event::Sysex(data) => {
+                    sink.write_bytes(&[SYSEX])?;
+                    write_data(data, sink)?;
+                    running_status = None;
+                }
+                Event::Escape(data) => {
+                    sink.write_bytes(&[ESCAPE])?;
+                    write_data(data, sink)?;
+                    running_status = None;
+                }
+                Event::Meta(meta) => {
+                    write_meta(meta, sink)?;
+                    running_status = None;
+                }
+            }
+        }
+        if !matches!(
+            events.last(),
+            Some(TrackEvent {
+                event: Event::Meta(MetaEvent::EndOfTrack),
+                ..
+            })
+        ) {
+            write_variable_length(0, sink)?;
+            write_meta(&MetaEvent::EndOfTrack, sink)?;
+        }
+        Ok(())
+    }
+}
+
+// a sink which only counts the bytes written
+struct Counter(usize);
+
+impl BytesSink for Counter {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), BufferOverflow> {
+        self.0 += bytes.len();
+        Ok(())
+    }
+}
+
+fn write_variable_length<S: BytesSink>(value: u32, sink: &mut S) -> Result<(), Error> {
+    let (data, len) = smf::variable_length(value).map_err(Error::InvalidData)?;
+    Ok(sink.write_bytes(&data[..len])?)
+}
+
+fn write_data<S: BytesSink>(data: &[u8], sink: &mut S) -> Result<(), Error> {
+    let len = u32::try_from(data.len()).unwrap_or(u32::MAX);
+    write_variable_length(len, sink)?;
+    Ok(sink.write_bytes(data)?)
+}
+
+fn write_meta<S: BytesSink>(meta: &MetaEvent, sink: &mut S) -> Result<(), Error> {
+    let mut scratch = [0x0; 4];
+    sink.write_bytes(&[META, meta.meta_type()])?;
+    write_data(meta.data(&mut scratch), sink)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        channel_voice1::{NoteOff, NoteOn},
+        smf::{Smf, TextType},
+        stream::BufferSink,
+        ux::{u15, u24, u7},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn note_on(note: u8) -> Event<'static> {
+        let mut message = NoteOn::<[u8; 3]>::new();
+        message.set_note(u7::new(note));
+        message.set_velocity(u7::new(0x40));
+        Event::Midi(message.into())
+    }
+
+    fn note_off(note: u8) -> Event<'static> {
+        let mut message = NoteOn::<[u8; 3]>::new();
+        message.set_note(u7::new(note));
+        Event::Midi(message.into())
+    }
+
+    #[test]
+    fn header() {
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        SmfWriter::new()
+            .write_header(
+                &Header {
+                    format: Format::MultipleTracks,
+                    number_of_tracks: 3,
+                    division: Division::TicksPerQuarterNote(u15::new(480)),
+                },
+                &mut sink,
+            )
+            .unwrap();
+        assert_eq!(
+            sink.data(),
+            &[0x4D, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x03, 0x01, 0xE0],
+        );
+    }
+
+    #[test]
+    fn smpte_header() {
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        SmfWriter::new()
+            .write_header(
+                &Header {
+                    format: Format::SingleTrack,
+                    number_of_tracks: 1,
+                    division: Division::Smpte {
+                        frames_per_second: 25,
+                        ticks_per_frame: 40,
+                    },
+                },
+                &mut sink,
+            )
+            .unwrap();
+        assert_eq!(&sink.data()[12..], &[0xE7, 0x28]);
+    }
+
+    #[test]
+    fn track() {
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        SmfWriter::new()
+            .write_track(
+                &[
+                    TrackEvent {
+                        delta_time: 0,
+                        event: note_on(0x3C),
+                    },
+                    TrackEvent {
+                        delta_time: 0x80,
+                        event: note_off(0x3C),
+                    },
+                ],
+                &mut sink,
+            )
+            .unwrap();
+        assert_eq!(
+            sink.data(),
+            &[
+                0x4D, 0x54, 0x72, 0x6B, 0x00, 0x00, 0x00, 0x0D, 0x00, 0x90, 0x3C, 0x40, 0x81, 0x00,
+                0x90, 0x3C, 0x00, 0x00, 0xFF, 0x2F, 0x00,
+            ],
+        );
+    }
+
+    #[test]
+    fn running_status() {
+        let mut writer = SmfWriter::new();
+        writer.set_running_status(true);
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        writer
+            .write_track(
+                &[
+                    TrackEvent {
+                        delta_time: 0,
+                        event: note_on(0x3C),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: note_on(0x3E),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: Event::Meta(MetaEvent::Text {
+                            text_type: TextType::Marker,
+                            text: b"A",
+                        }),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: note_on(0x40),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: Event::Meta(MetaEvent::EndOfTrack),
+                    },
+                ],
+                &mut sink,
+            )
+            .unwrap();
+        assert_eq!(
+            &sink.data()[8..],
+            &[
+                0x00, 0x90, 0x3C, 0x40, 0x00, 0x3E, 0x40, 0x00, 0xFF, 0x06, 0x01, 0x41, 0x00, 0x90,
+                0x40, 0x40, 0x00, 0xFF, 0x2F, 0x00,
+            ],
+        );
+    }
+
+    #[test]
+    fn running_status_with_escaped_real_time() {
+        let mut writer = SmfWriter::new();
+        writer.set_running_status(true);
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        writer
+            .write_track(
+                &[
+                    TrackEvent {
+                        delta_time: 0,
+                        event: note_on(0x3C),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: Event::Escape(&[0xF8]),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: Event::Escape(&[0xF8]),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: note_on(0x3E),
+                    },
+                    TrackEvent {
+                        delta_time: 0,
+                        event: Event::Meta(MetaEvent::EndOfTrack),
+                    },
+                ],
+                &mut sink,
+            )
+            .unwrap();
+        assert_eq!(
+            &sink.data()[8..],
+            &[
+                0x00, 0x90, 0x3C, 0x40, 0x00, 0xF7, 0x01, 0xF8, 0x00, 0xF7, 0x01, 0xF8, 0x00, 0x90,
+                0x3E, 0x40, 0x00, 0xFF, 0x2F, 0x00,
+            ],
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let mut sink = BufferSink::new([0x0_u8; 16]);
+        assert_eq!(
+            SmfWriter::new().write_track(
+                &[TrackEvent {
+                    delta_time: 0,
+                    event: Event::Sysex(&[0x0; 16]),
+                }],
+                &mut sink,
+            ),
+            Err(Error::BufferOverflow),
+        );
+    }
+
+    #[test]
+    fn delta_time_too_large() {
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        assert_eq!(
+            SmfWriter::new().write_track(
+                &[TrackEvent {
+                    delta_time: 0x1000_0000,
+                    event: note_on(0x3C),
+                }],
+                &mut sink,
+            ),
+            Err(Error::InvalidData(smf::ERR_VARIABLE_LENGTH_TOO_LARGE)),
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let note_off = {
+            let mut message = NoteOff::<[u8; 3]>::new();
+            message.set_note(u7::new(0x3C));
+            Event::Midi(message.into())
+        };
+        let track = [
+            TrackEvent {
+                delta_time: 0,
+                event: Event::Meta(MetaEvent::Tempo(u24::new(500_000))),
+            },
+            TrackEvent {
+                delta_time: 0,
+                event: Event::Meta(MetaEvent::KeySignature {
+                    sharps_flats: -2,
+                    minor: false,
+                }),
+            },
+            TrackEvent {
+                delta_time: 0,
+                event: Event::Sysex(&[0x7E, 0x7F, 0x09, 0x01, 0xF7]),
+            },
+            TrackEvent {
+                delta_time: 0,
+                event: note_on(0x3C),
+            },
+            TrackEvent {
+                delta_time: 0x0FFF_FFFF,
+                event: note_off,
+            },
+            TrackEvent {
+                delta_time: 10,
+                event: Event::Escape(&[0xF8]),
+            },
+            TrackEvent {
+                delta_time: 0,
+                event: Event::Meta(MetaEvent::EndOfTrack),
+            },
+        ];
+        let header = Header {
+            format: Format::MultipleSongs,
+            number_of_tracks: 2,
+            division: Division::TicksPerQuarterNote(u15::new(96)),
+        };
+
+        let mut writer = SmfWriter::new();
+        writer.set_running_status(true);
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        writer.write_header(&header, &mut sink).unwrap();
+        writer.write_track(&track, &mut sink).unwrap();
+        writer.write_track(&track, &mut sink).unwrap();
+
+        let smf = Smf::parse(sink.data()).unwrap();
+        assert_eq!(smf.header(), header);
+        for read in smf.tracks() {
+            assert_eq!(
+                read.events().collect::<Result<std::vec::Vec<_>, _>>(),
+                Ok(track.to_vec()),
+            );
+        }
+    }
+}
@@ -0,0 +1,590 @@
+use crate::{
+    detail::{helpers as message_helpers, BitOps},
+    error::{Error, StreamError},
+    message::UmpMessage,
+    ux::u4,
+};
+
+#[cfg(feature = "sysex7")]
+mod bytes_parser;
+mod bytes_writer;
+#[cfg(all(feature = "ump-stream", feature = "std"))]
+mod group_router;
+#[cfg(all(feature = "sysex8", feature = "std"))]
+mod mixed_data_set_assembler;
+#[cfg(feature = "sysex7")]
+mod sysex7_reassembler;
+#[cfg(feature = "sysex8")]
+mod sysex8_reassembler;
+
+#[cfg(feature = "sysex7")]
+pub use bytes_parser::*;
+pub use bytes_writer::*;
+#[cfg(all(feature = "ump-stream", feature = "std"))]
+pub use group_router::*;
+#[cfg(all(feature = "sysex8", feature = "std"))]
+pub use mixed_data_set_assembler::*;
+#[cfg(feature = "sysex7")]
+pub use sysex7_reassembler::*;
+#[cfg(feature = "sysex8")]
+pub use sysex8_reassembler::*;
+
+const ERR_TRUNCATED_PACKET: &str = "Buffer ends part way through a packet";
+
+/// The number of 32 bit words in a single ump packet of the given message type.
+///
+/// Sizes for the reserved message types are those set out by the
+/// MIDI 2.0 specification, so that unrecognised packets can still be
+/// stepped over.
+pub fn ump_packet_size(ump_type: u4) -> usize {
+    match u8::from(ump_type) {
+        0x0 | 0x1 | 0x2 | 0x6 | 0x7 => 1,
+        0x3 | 0x4 | 0x8 | 0x9 | 0xA => 2,
+        0xB | 0xC => 3,
+        0x5 | 0xD | 0xE | 0xF => 4,
+        _ => unreachable!(),
+    }
+}
+
+/// The events produced by a single packet fed into one of the
+/// reassemblers. A packet yields at most two events.
+#[cfg(any(feature = "sysex7", feature = "sysex8"))]
+#[derive(Clone, Debug)]
+pub struct PacketEvents<T>(core::array::IntoIter<Option<T>, 2>);
+
+#[cfg(any(feature = "sysex7", feature = "sysex8"))]
+impl<T> PacketEvents<T> {
+    pub(crate) fn new(first: Option<T>, second: Option<T>) -> Self {
+        PacketEvents([first, second].into_iter())
+    }
+}
+
+#[cfg(any(feature = "sysex7", feature = "sysex8"))]
+impl<T> core::iter::Iterator for PacketEvents<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.by_ref().flatten().next()
+    }
+}
+
+#[cfg(any(feature = "sysex7", feature = "sysex8"))]
+impl<T> core::iter::FusedIterator for PacketEvents<T> {}
+
+/// Iterates over the messages contained in a flat buffer of ump data.
+///
+/// Messages which span multiple packets (sysex7, sysex8, flex data and
+/// ump stream messages) are yielded as a single [UmpMessage]. The packets
+/// of such a message must be contiguous in the buffer.
+///
+/// Malformed data is reported as a [StreamError] carrying the word offset
+/// of the offending data. Iteration then continues from the next packet.
+///
+/// ```rust
+/// use midi2::prelude::*;
+///
+/// let buffer = [
+///     0x4090_3C00, 0xC000_0000, // channel voice 2 note on
+///     0x3016_0001, 0x0203_0405, // sysex7 start
+///     0x3035_0607, 0x0809_0A00, // sysex7 end
+/// ];
+///
+/// let mut iter = UmpStreamIter::new(&buffer[..]);
+/// assert!(matches!(iter.next(), Some(Ok(UmpMessage::ChannelVoice2(_)))));
+/// assert!(matches!(iter.next(), Some(Ok(UmpMessage::Sysex7(_)))));
+/// assert!(iter.next().is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct UmpStreamIter<'a> {
+    buffer: &'a [u32],
+    offset: usize,
+}
+
+impl<'a> UmpStreamIter<'a> {
+    pub fn new(buffer: &'a [u32]) -> Self {
+        UmpStreamIter { buffer, offset: 0 }
+    }
+
+    /// The word offset in the buffer of the next message to be read.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> core::iter::Iterator for UmpStreamIter<'a> {
+    type Item = core::result::Result<UmpMessage<&'a [u32]>, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let buffer = &self.buffer[self.offset..];
+        if buffer.is_empty() {
+            return None;
+        }
+
+        let offset = self.offset;
+        let (len, result) = match message_size(buffer) {
+            Ok(len) => (len, UmpMessage::try_from(&buffer[..len])),
+            Err((len, error)) => (len, Err(error)),
+        };
+        self.offset += len;
+
+        Some(result.map_err(|error| StreamError { offset, len, error }))
+    }
+}
+
+impl<'a> core::iter::FusedIterator for UmpStreamIter<'a> {}
+
+/// Iterates over the messages contained in a flat buffer of ump data,
+/// giving mutable access to each of them.
+///
+/// This behaves like [UmpStreamIter], but the messages borrow the buffer
+/// mutably so that they can be modified in place.
+///
+/// ```rust
+/// use midi2::{channel_voice2::ChannelVoice2, prelude::*};
+///
+/// let mut buffer = [
+///     0x4090_3C00, 0xC000_0000, // channel voice 2 note on
+///     0x4080_3C00, 0x0000_0000, // channel voice 2 note off
+/// ];
+///
+/// for message in UmpStreamIterMut::new(&mut buffer[..]) {
+///     if let Ok(UmpMessage::ChannelVoice2(ChannelVoice2::NoteOn(mut note_on))) = message {
+///         note_on.set_note(note_on.note() + u7::new(12));
+///     }
+/// }
+///
+/// assert_eq!(buffer, [0x4090_4800, 0xC000_0000, 0x4080_3C00, 0x0000_0000]);
+/// ```
+#[derive(Debug)]
+pub struct UmpStreamIterMut<'a> {
+    buffer: &'a mut [u32],
+    offset: usize,
+}
+
+impl<'a> UmpStreamIterMut<'a> {
+    pub fn new(buffer: &'a mut [u32]) -> Self {
+        UmpStreamIterMut { buffer, offset: 0 }
+    }
+
+    /// The word offset in the buffer of the next message to be read.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> core::iter::Iterator for UmpStreamIterMut<'a> {
+    type Item = core::result::Result<UmpMessage<&'a mut [u32]>, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let offset = self.offset;
+        let (len, size) = match message_size(self.buffer) {
+            Ok(len) => (len, Ok(())),
+            Err((len, error)) => (len, Err(error)),
+        };
+        let (message, rest) = core::mem::take(&mut self.buffer).split_at_mut(len);
+        self.buffer = rest;
+        self.offset += len;
+
+        Some(
+            size.and_then(|()| UmpMessage::try_from(message))
+                .map_err(|error| StreamError { offset, len, error }),
+        )
+    }
+}
+
+impl<'a> core::iter::FusedIterator for UmpStreamIterMut<'a> {}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PacketFormat {
+    Complete,
+    Start,
+    Continue,
+    End,
+}
+
+// the format of packets belonging to messages which
+// may be spread over multiple packets
+fn packet_format(p: u32) -> Option<PacketFormat> {
+    use PacketFormat::*;
+    let format = match u8::from(p.nibble(0)) {
+        // sysex7 and sysex8
+        0x3 | 0x5 => u8::from(p.nibble(2)),
+        // flex data
+        0xD => u8::from(p.crumb(4)),
+        // ump stream
+        0xF => u8::from(p.crumb(2)),
+        _ => return None,
+    };
+    Some(match format {
+        0x1 => Start,
+        0x2 => Continue,
+        0x3 => End,
+        // other statuses are single packet messages
+        _ => Complete,
+    })
+}
+
+fn packet_has_group(p: u32) -> bool {
+    matches!(u8::from(p.nibble(0)), 0x3 | 0x5 | 0xD)
+}
+
+// the number of words taken up by the message at the front of the buffer.
+// in the error case we also return the number of words to skip.
+pub(crate) fn message_size(buffer: &[u32]) -> core::result::Result<usize, (usize, Error)> {
+    let first = buffer[0];
+    let packet_size = ump_packet_size(first.nibble(0));
+
+    if buffer.len() < packet_size {
+        return Err((buffer.len(), Error::InvalidData(ERR_TRUNCATED_PACKET)));
+    }
+
+    match packet_format(first) {
+        None | Some(PacketFormat::Complete) => Ok(packet_size),
+        Some(PacketFormat::Continue) | Some(PacketFormat::End) => Err((
+            packet_size,
+            Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_BEGIN),
+        )),
+        Some(PacketFormat::Start) => {
+            let mut size = packet_size;
+            let expected_end = Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_END);
+            loop {
+                let Some(packet) = buffer.get(size..size + packet_size) else {
+                    return Err((size, expected_end));
+                };
+                if packet[0].nibble(0) != first.nibble(0)
+                    || (packet_has_group(first) && packet[0].nibble(1) != first.nibble(1))
+                {
+                    return Err((size, expected_end));
+                }
+                match packet_format(packet[0]) {
+                    Some(PacketFormat::Continue) => size += packet_size,
+                    Some(PacketFormat::End) => return Ok(size + packet_size),
+                    _ => return Err((size, expected_end)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn packet_sizes() {
+        let sizes: [usize; 16] = core::array::from_fn(|t| ump_packet_size(u4::new(t as u8)));
+        assert_eq!(sizes, [1, 1, 1, 2, 2, 4, 1, 1, 2, 2, 2, 3, 3, 4, 4, 4]);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(UmpStreamIter::new(&[]).next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice2")]
+    fn single_packet_messages() {
+        let buffer = [0x4090_3C00, 0xC000_0000, 0x4181_3C00, 0x8000_0000];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[0..2]).unwrap()))
+        );
+        assert_eq!(iter.offset(), 2);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[2..4]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice2", feature = "sysex7"))]
+    fn multi_packet_sysex7() {
+        let buffer = [
+            0x3016_0001,
+            0x0203_0405,
+            0x3026_0607,
+            0x0809_0A0B,
+            0x3032_0C0D,
+            0x0000_0000,
+            0x4090_3C00,
+            0xC000_0000,
+        ];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[0..6]).unwrap()))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[6..8]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice2", not(feature = "unknown")))]
+    fn reserved_message_types_are_skipped() {
+        let buffer = [
+            0x6000_0000,
+            0xB000_0000,
+            0x0000_0000,
+            0x0000_0000,
+            0x4090_3C00,
+            0xC000_0000,
+        ];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 0,
+                len: 1,
+                error: Error::InvalidData("Couldn't interpret ump message type"),
+            }))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 1,
+                len: 3,
+                error: Error::InvalidData("Couldn't interpret ump message type"),
+            }))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[4..6]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "unknown")]
+    fn reserved_message_types_are_preserved() {
+        let buffer = [0x6000_0000, 0xB000_0000, 0x0000_0000, 0x0000_0000];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::Unknown(
+                crate::UnknownUmp::try_from(&buffer[0..1]).unwrap()
+            )))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::Unknown(
+                crate::UnknownUmp::try_from(&buffer[1..4]).unwrap()
+            )))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice2", feature = "sysex7"))]
+    fn mutable_messages() {
+        use crate::traits::Grouped;
+
+        let mut buffer = [
+            0x4090_3C00,
+            0xC000_0000,
+            0x3016_0001,
+            0x0203_0405,
+            0x3035_0607,
+            0x0809_0A00,
+        ];
+        let mut iter = UmpStreamIterMut::new(&mut buffer[..]);
+        while let Some(Ok(message)) = iter.next() {
+            match message {
+                UmpMessage::ChannelVoice2(mut m) => m.set_group(u4::new(0x7)),
+                UmpMessage::Sysex7(mut m) => m.set_group(u4::new(0x7)),
+                // only reachable with further message features enabled
+                #[allow(unreachable_patterns)]
+                _ => panic!(),
+            }
+        }
+        assert_eq!(iter.offset(), 6);
+        assert_eq!(
+            buffer,
+            [
+                0x4790_3C00,
+                0xC000_0000,
+                0x3716_0001,
+                0x0203_0405,
+                0x3735_0607,
+                0x0809_0A00,
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice2")]
+    fn mutable_messages_skip_malformed_data() {
+        let mut buffer = [0x3020_0000, 0x0000_0000, 0x4090_3C00, 0xC000_0000];
+        let mut iter = UmpStreamIterMut::new(&mut buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 0,
+                len: 2,
+                error: Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_BEGIN),
+            }))
+        );
+        assert!(matches!(
+            iter.next(),
+            Some(Ok(UmpMessage::ChannelVoice2(_)))
+        ));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice2")]
+    fn truncated_packet() {
+        let buffer = [0x4090_3C00, 0xC000_0000, 0x4090_3C00];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 2,
+                len: 1,
+                error: Error::InvalidData(ERR_TRUNCATED_PACKET),
+            }))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice2", feature = "sysex7"))]
+    fn sysex7_continue_without_start() {
+        let buffer = [0x3026_0607, 0x0809_0A0B, 0x4090_3C00, 0xC000_0000];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 0,
+                len: 2,
+                error: Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_BEGIN),
+            }))
+        );
+        assert!(iter.next().unwrap().is_ok());
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice2", feature = "sysex7"))]
+    fn sysex7_interrupted() {
+        let buffer = [
+            0x3016_0001,
+            0x0203_0405,
+            0x3026_0607,
+            0x0809_0A0B,
+            0x4090_3C00,
+            0xC000_0000,
+        ];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 0,
+                len: 4,
+                error: Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_END),
+            }))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[4..6]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "sysex7")]
+    fn sysex7_group_change() {
+        let buffer = [0x3016_0001, 0x0203_0405, 0x3132_0C0D, 0x0000_0000];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 0,
+                len: 2,
+                error: Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_END),
+            }))
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Err(StreamError {
+                offset: 2,
+                len: 2,
+                error: Error::InvalidData(message_helpers::ERR_SYSEX_EXPECTED_BEGIN),
+            }))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "sysex8")]
+    fn multi_packet_sysex8() {
+        let buffer = [
+            0x501E_0000,
+            0x0102_0304,
+            0x0506_0708,
+            0x090A_0B0C,
+            0x503E_000D,
+            0x0E0F_1011,
+            0x1213_1415,
+            0x1617_1819,
+        ];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[..]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "flex-data")]
+    fn multi_packet_flex_data() {
+        let buffer = [
+            0xD050_0106,
+            0x4769_6D6D,
+            0x6520_736F,
+            0x6D65_2073,
+            0xD0D0_0106,
+            0x6967_6E61,
+            0x6C20_3A29,
+            0x0000_0000,
+        ];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[..]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "ump-stream")]
+    fn multi_packet_ump_stream() {
+        let buffer = [
+            0xF403_4769,
+            0x6D6D_6520,
+            0x736F_6D65,
+            0x2073_7967,
+            0xFC03_6E61,
+            0x6C20_3A29,
+            0x0000_0000,
+            0x0000_0000,
+        ];
+        let mut iter = UmpStreamIter::new(&buffer[..]);
+        assert_eq!(
+            iter.next(),
+            Some(Ok(UmpMessage::try_from(&buffer[..]).unwrap()))
+        );
+        assert_eq!(iter.next(), None);
+    }
+}
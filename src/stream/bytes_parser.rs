@@ -0,0 +1,435 @@
+use crate::{
+    buffer::{BufferDefault, BufferMut, BufferTryResize, Bytes},
+    error::Error,
+    message::BytesMessage,
+    sysex7::Sysex7,
+    traits::TryRebufferInto,
+};
+
+const ERR_DATA_WITHOUT_STATUS: &str = "Data byte without a preceding status byte";
+const ERR_INCOMPLETE_MESSAGE: &str = "Message interrupted by a status byte";
+const ERR_UNEXPECTED_END_OF_SYSEX: &str = "End of sysex byte without a start of sysex";
+
+const START_OF_SYSEX: u8 = 0xF0;
+const END_OF_SYSEX: u8 = 0xF7;
+
+/// A message read from a MIDI 1.0 byte stream by a [BytesParser].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BytesParserItem<B: Bytes> {
+    Message(BytesMessage<[u8; 3]>),
+    Sysex7(Sysex7<B>),
+}
+
+/// Reads messages from a continuous MIDI 1.0 byte stream.
+///
+/// The stream can be fed to the parser in chunks of any size and
+/// messages which span the boundary between chunks are handled.
+/// Running status is supported and system real time bytes are read
+/// even when they interrupt other messages.
+///
+/// Sysex data is collected in a buffer of type `B`.
+/// When `B` is a fixed size array the parser doesn't allocate and
+/// sysex messages which are too large to fit are reported with
+/// [Error::BufferOverflow].
+///
+/// ```rust
+/// use midi2::{prelude::*, BytesParser, BytesParserItem};
+///
+/// let mut parser = BytesParser::<[u8; 32]>::new();
+///
+/// let mut messages = parser.parse(&[0xF0, 0x01, 0x02]).collect::<Vec<_>>();
+/// assert!(messages.is_empty());
+///
+/// messages.extend(parser.parse(&[0x03, 0xF7]));
+/// let [Ok(BytesParserItem::Sysex7(sysex))] = &messages[..] else {
+///     panic!();
+/// };
+/// assert_eq!(sysex.data(), &[0xF0, 0x01, 0x02, 0x03, 0xF7]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BytesParser<B: Bytes + BufferMut + BufferDefault + BufferTryResize> {
+    running_status: Option<u8>,
+    message: [u8; 3],
+    message_len: usize,
+    sysex: B,
+    sysex_state: SysexState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SysexState {
+    Inactive,
+    Active(usize),
+    Overflowed,
+}
+
+impl<B: Bytes + BufferMut + BufferDefault + BufferTryResize> Default for BytesParser<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Bytes + BufferMut + BufferDefault + BufferTryResize> BytesParser<B> {
+    pub fn new() -> Self {
+        BytesParser {
+            running_status: None,
+            message: [0x0; 3],
+            message_len: 0,
+            sysex: B::default(),
+            sysex_state: SysexState::Inactive,
+        }
+    }
+
+    /// Read the messages from the next chunk of the byte stream.
+    ///
+    /// Any trailing incomplete message is kept by the parser
+    /// and completed by the following chunks.
+    pub fn parse<'a>(&'a mut self, bytes: &'a [u8]) -> ParseIterator<'a, B> {
+        ParseIterator {
+            parser: self,
+            bytes,
+            index: 0,
+        }
+    }
+
+    /// Discard any partially read message and the running status.
+    pub fn reset(&mut self) {
+        self.running_status = None;
+        self.message_len = 0;
+        self.sysex_state = SysexState::Inactive;
+    }
+
+    // returns whether the byte was consumed.
+    // status bytes which end an incomplete message are
+    // left in place to be read on the following step.
+    fn step(&mut self, byte: u8) -> (bool, Option<Result<BytesParserItem<B>, Error>>) {
+        match byte {
+            0xF8..=0xFF => (true, Some(message(&[byte]))),
+            0x80..=0xF7 if self.sysex_state != SysexState::Inactive => {
+                let consumed = byte == END_OF_SYSEX;
+                (consumed, self.end_sysex())
+            }
+            0x80..=0xF7 if self.message_len != 0 => {
+                self.message_len = 0;
+                (false, Some(Err(Error::InvalidData(ERR_INCOMPLETE_MESSAGE))))
+            }
+            START_OF_SYSEX => {
+                self.running_status = None;
+                self.sysex_state = match self.sysex.try_resize(1) {
+                    Ok(()) => {
+                        self.sysex.buffer_mut()[0] = START_OF_SYSEX;
+                        SysexState::Active(1)
+                    }
+                    Err(_) => SysexState::Overflowed,
+                };
+                (
+                    true,
+                    (self.sysex_state == SysexState::Overflowed)
+                        .then_some(Err(Error::BufferOverflow)),
+                )
+            }
+            END_OF_SYSEX => {
+                self.running_status = None;
+                (
+                    true,
+                    Some(Err(Error::InvalidData(ERR_UNEXPECTED_END_OF_SYSEX))),
+                )
+            }
+            0x80..=0xF6 => {
+                self.running_status = (byte < 0xF0).then_some(byte);
+                self.message[0] = byte;
+                self.message_len = 1;
+                (true, self.complete_message())
+            }
+            _ => (true, self.data(byte)),
+        }
+    }
+
+    fn data(&mut self, byte: u8) -> Option<Result<BytesParserItem<B>, Error>> {
+        match self.sysex_state {
+            SysexState::Active(len) => {
+                if self.sysex.try_resize(len + 1).is_err() {
+                    self.sysex_state = SysexState::Overflowed;
+                    return Some(Err(Error::BufferOverflow));
+                }
+                self.sysex.buffer_mut()[len] = byte;
+                self.sysex_state = SysexState::Active(len + 1);
+                return None;
+            }
+            SysexState::Overflowed => return None,
+            SysexState::Inactive => {}
+        }
+
+        if self.message_len == 0 {
+            let Some(status) = self.running_status else {
+                return Some(Err(Error::InvalidData(ERR_DATA_WITHOUT_STATUS)));
+            };
+            self.message[0] = status;
+            self.message_len = 1;
+        }
+
+        self.message[self.message_len] = byte;
+        self.message_len += 1;
+        self.complete_message()
+    }
+
+    fn complete_message(&mut self) -> Option<Result<BytesParserItem<B>, Error>> {
+        if self.message_len < message_size(self.message[0]) {
+            return None;
+        }
+        let len = self.message_len;
+        self.message_len = 0;
+        Some(message(&self.message[..len]))
+    }
+
+    fn end_sysex(&mut self) -> Option<Result<BytesParserItem<B>, Error>> {
+        let state = self.sysex_state;
+        self.sysex_state = SysexState::Inactive;
+        self.running_status = None;
+
+        let SysexState::Active(len) = state else {
+            return None;
+        };
+        if self.sysex.try_resize(len + 1).is_err() {
+            return Some(Err(Error::BufferOverflow));
+        }
+        self.sysex.buffer_mut()[len] = END_OF_SYSEX;
+
+        Some(
+            Sysex7::try_from(&self.sysex.buffer()[..len + 1])
+                .and_then(|sysex| sysex.try_rebuffer_into().map_err(Error::from))
+                .map(BytesParserItem::Sysex7),
+        )
+    }
+}
+
+fn message<B: Bytes>(bytes: &[u8]) -> Result<BytesParserItem<B>, Error> {
+    let message = BytesMessage::try_from(bytes)?;
+    Ok(BytesParserItem::Message(message.try_rebuffer_into()?))
+}
+
+// number of bytes in a message with the given status
+fn message_size(status: u8) -> usize {
+    match status {
+        0xC0..=0xDF => 2,
+        0x80..=0xEF => 3,
+        0xF1 | 0xF3 => 2,
+        0xF2 => 3,
+        _ => 1,
+    }
+}
+
+/// Yields the messages read from a single chunk of a byte stream.
+/// See [BytesParser::parse].
+pub struct ParseIterator<'a, B: Bytes + BufferMut + BufferDefault + BufferTryResize> {
+    parser: &'a mut BytesParser<B>,
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl<'a, B: Bytes + BufferMut + BufferDefault + BufferTryResize> core::iter::Iterator
+    for ParseIterator<'a, B>
+{
+    type Item = Result<BytesParserItem<B>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&byte) = self.bytes.get(self.index) {
+            let (consumed, item) = self.parser.step(byte);
+            if consumed {
+                self.index += 1;
+            }
+            if item.is_some() {
+                return item;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Data;
+    use pretty_assertions::assert_eq;
+
+    fn sysex_data<B: Bytes + core::fmt::Debug>(item: &Result<BytesParserItem<B>, Error>) -> &[u8] {
+        let Ok(BytesParserItem::Sysex7(sysex)) = item else {
+            panic!("Expected sysex, found {:?}", item);
+        };
+        sysex.data()
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    fn message_data(item: &Result<BytesParserItem<[u8; 32]>, Error>) -> &[u8] {
+        let Ok(BytesParserItem::Message(message)) = item else {
+            panic!("Expected message, found {:?}", item);
+        };
+        message.data()
+    }
+
+    #[test]
+    fn sysex() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0xF0, 0x01, 0x02, 0x03, 0xF7])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert_eq!(sysex_data(&items[0]), &[0xF0, 0x01, 0x02, 0x03, 0xF7]);
+    }
+
+    #[test]
+    fn sysex_split_across_chunks() {
+        let mut parser = BytesParser::<std::vec::Vec<u8>>::new();
+        assert_eq!(parser.parse(&[0xF0, 0x01]).next(), None);
+        assert_eq!(parser.parse(&[0x02]).next(), None);
+        let items = parser.parse(&[0x03, 0xF7]).collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert_eq!(sysex_data(&items[0]), &[0xF0, 0x01, 0x02, 0x03, 0xF7]);
+    }
+
+    #[test]
+    fn sysex_overflow() {
+        let mut parser = BytesParser::<[u8; 4]>::new();
+        let items = parser
+            .parse(&[0xF0, 0x01, 0x02, 0x03, 0x04, 0xF7, 0xF0, 0x01, 0xF7])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0], Err(Error::BufferOverflow));
+        assert_eq!(sysex_data(&items[1]), &[0xF0, 0x01, 0xF7]);
+    }
+
+    #[test]
+    fn stray_data_after_sysex() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0xF0, 0x01, 0xF7, 0x02])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert_eq!(sysex_data(&items[0]), &[0xF0, 0x01, 0xF7]);
+        assert_eq!(items[1], Err(Error::InvalidData(ERR_DATA_WITHOUT_STATUS)));
+    }
+
+    #[test]
+    fn unexpected_end_of_sysex() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        assert_eq!(
+            parser.parse(&[0xF7]).next(),
+            Some(Err(Error::InvalidData(ERR_UNEXPECTED_END_OF_SYSEX)))
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        assert_eq!(parser.parse(&[0xF0, 0x01]).next(), None);
+        parser.reset();
+        assert_eq!(
+            parser.parse(&[0x02]).next(),
+            Some(Err(Error::InvalidData(ERR_DATA_WITHOUT_STATUS)))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn running_status() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0x92, 0x3C, 0x40, 0x3E, 0x40, 0x40, 0x00])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 3);
+        assert_eq!(message_data(&items[0]), &[0x92, 0x3C, 0x40]);
+        assert_eq!(message_data(&items[1]), &[0x92, 0x3E, 0x40]);
+        assert_eq!(message_data(&items[2]), &[0x92, 0x40, 0x00]);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn running_status_single_data_byte() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0xC5, 0x01, 0x02])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert_eq!(message_data(&items[0]), &[0xC5, 0x01]);
+        assert_eq!(message_data(&items[1]), &[0xC5, 0x02]);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn message_split_across_chunks() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        assert_eq!(parser.parse(&[0x92]).next(), None);
+        assert_eq!(parser.parse(&[0x3C]).next(), None);
+        let items = parser.parse(&[0x40, 0x3E]).collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert_eq!(message_data(&items[0]), &[0x92, 0x3C, 0x40]);
+        let items = parser.parse(&[0x40]).collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 1);
+        assert_eq!(message_data(&items[0]), &[0x92, 0x3E, 0x40]);
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn interrupted_message() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0x92, 0x3C, 0xB0, 0x07, 0x7F])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0], Err(Error::InvalidData(ERR_INCOMPLETE_MESSAGE)));
+        assert_eq!(message_data(&items[1]), &[0xB0, 0x07, 0x7F]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice1", feature = "system-common"))]
+    fn real_time_inside_message() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0x92, 0x3C, 0xF8, 0x40, 0x3E, 0xFE, 0x40])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 4);
+        assert_eq!(message_data(&items[0]), &[0xF8]);
+        assert_eq!(message_data(&items[1]), &[0x92, 0x3C, 0x40]);
+        assert_eq!(message_data(&items[2]), &[0xFE]);
+        assert_eq!(message_data(&items[3]), &[0x92, 0x3E, 0x40]);
+    }
+
+    #[test]
+    #[cfg(feature = "system-common")]
+    fn real_time_inside_sysex() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0xF0, 0x01, 0xF8, 0x02, 0xF7])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 2);
+        let Ok(BytesParserItem::Message(BytesMessage::SystemCommon(_))) = items[0] else {
+            panic!();
+        };
+        assert_eq!(sysex_data(&items[1]), &[0xF0, 0x01, 0x02, 0xF7]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "channel-voice1", feature = "system-common"))]
+    fn system_common_clears_running_status() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0x92, 0x3C, 0x40, 0xF3, 0x05, 0x3E, 0x40])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 4);
+        assert_eq!(message_data(&items[0]), &[0x92, 0x3C, 0x40]);
+        assert_eq!(message_data(&items[1]), &[0xF3, 0x05]);
+        assert_eq!(items[2], Err(Error::InvalidData(ERR_DATA_WITHOUT_STATUS)));
+        assert_eq!(items[3], Err(Error::InvalidData(ERR_DATA_WITHOUT_STATUS)));
+    }
+
+    #[test]
+    #[cfg(feature = "channel-voice1")]
+    fn sysex_ended_by_status_byte() {
+        let mut parser = BytesParser::<[u8; 32]>::new();
+        let items = parser
+            .parse(&[0xF0, 0x01, 0x02, 0x92, 0x3C, 0x40])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(items.len(), 2);
+        assert_eq!(sysex_data(&items[0]), &[0xF0, 0x01, 0x02, 0xF7]);
+        assert_eq!(message_data(&items[1]), &[0x92, 0x3C, 0x40]);
+    }
+}
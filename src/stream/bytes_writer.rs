@@ -0,0 +1,339 @@
+use crate::{
+    buffer::{BufferMut, BufferTryResize, Bytes},
+    error::BufferOverflow,
+    traits::Data,
+};
+
+/// A destination for the bytes produced by a [BytesWriter].
+///
+/// Much like [core::fmt::Write], implementors only need to say how a
+/// slice of data is written out. This makes it easy to hook a writer
+/// straight up to a serial port or similar.
+pub trait BytesSink {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), BufferOverflow>;
+}
+
+/// A [BytesSink] which appends to a backing buffer.
+///
+/// Writes which don't fit in the buffer fail
+/// with [BufferOverflow] and leave the buffer unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BufferSink<B: Bytes + BufferMut + BufferTryResize> {
+    buffer: B,
+    len: usize,
+}
+
+impl<B: Bytes + BufferMut + BufferTryResize> BufferSink<B> {
+    pub fn new(buffer: B) -> Self {
+        BufferSink { buffer, len: 0 }
+    }
+
+    /// The bytes written so far.
+    pub fn data(&self) -> &[u8] {
+        &self.buffer.buffer()[..self.len]
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+}
+
+impl<B: Bytes + BufferMut + BufferTryResize> BytesSink for BufferSink<B> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), BufferOverflow> {
+        let len = self.len + bytes.len();
+        if self.buffer.buffer().len() < len {
+            self.buffer.try_resize(len)?;
+        }
+        self.buffer.buffer_mut()[self.len..len].copy_from_slice(bytes);
+        self.len = len;
+        Ok(())
+    }
+}
+
+/// Writes messages out as a MIDI 1.0 byte stream.
+///
+/// Any message with a byte representation can be written, including the
+/// [BytesMessage](crate::BytesMessage) aggregate.
+///
+/// Running status compression is optional. When it's enabled, the status
+/// byte of a channel voice message is left out if it matches the status
+/// of the previous channel voice message. System common and sysex messages
+/// always reset the running status, while system real time messages
+/// leave it alone.
+///
+/// ```rust
+/// # #[cfg(feature = "channel-voice1")]
+/// # {
+/// use midi2::{prelude::*, BufferSink, BytesWriter};
+///
+/// let mut writer = BytesWriter::new();
+/// writer.set_running_status(true);
+///
+/// let mut sink = BufferSink::new([0x0_u8; 8]);
+/// let mut message = channel_voice1::NoteOn::<[u8; 3]>::new();
+/// message.set_note(u7::new(0x3C));
+/// writer.write(&message, &mut sink).unwrap();
+/// message.set_note(u7::new(0x3E));
+/// writer.write(&message, &mut sink).unwrap();
+///
+/// assert_eq!(sink.data(), &[0x90, 0x3C, 0x00, 0x3E, 0x00]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BytesWriter {
+    use_running_status: bool,
+    running_status: Option<u8>,
+}
+
+impl BytesWriter {
+    /// Create a writer with running status compression disabled.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn running_status(&self) -> bool {
+        self.use_running_status
+    }
+
+    pub fn set_running_status(&mut self, v: bool) {
+        self.use_running_status = v;
+        self.running_status = None;
+    }
+
+    /// Forget the current running status so that
+    /// the next message is written in full.
+    pub fn reset(&mut self) {
+        self.running_status = None;
+    }
+
+    pub fn write<B: Bytes, M: Data<B>, S: BytesSink>(
+        &mut self,
+        message: &M,
+        sink: &mut S,
+    ) -> Result<(), BufferOverflow> {
+        let data = message.data();
+        let Some(&status) = data.first() else {
+            return Ok(());
+        };
+
+        match status {
+            0x80..=0xEF => {
+                if self.use_running_status && self.running_status == Some(status) {
+                    sink.write_bytes(&data[1..])?;
+                } else {
+                    sink.write_bytes(data)?;
+                }
+                self.running_status = Some(status);
+            }
+            0xF8..=0xFF => sink.write_bytes(data)?,
+            _ => {
+                sink.write_bytes(data)?;
+                self.running_status = None;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn buffer_sink_array() {
+        let mut sink = BufferSink::new([0x0_u8; 4]);
+        assert_eq!(sink.write_bytes(&[0x1, 0x2, 0x3]), Ok(()));
+        assert_eq!(sink.write_bytes(&[0x4, 0x5]), Err(BufferOverflow));
+        assert_eq!(sink.write_bytes(&[0x4]), Ok(()));
+        assert_eq!(sink.data(), &[0x1, 0x2, 0x3, 0x4]);
+    }
+
+    #[test]
+    fn buffer_sink_vec() {
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        assert_eq!(sink.write_bytes(&[0x1, 0x2, 0x3]), Ok(()));
+        assert_eq!(sink.write_bytes(&[0x4, 0x5]), Ok(()));
+        assert_eq!(sink.data(), &[0x1, 0x2, 0x3, 0x4, 0x5]);
+    }
+
+    #[test]
+    #[cfg(feature = "sysex7")]
+    fn sysex() {
+        use crate::{sysex7::Sysex7, traits::Sysex, ux::u7};
+
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload((0..4).map(u7::new));
+
+        let mut sink = BufferSink::new(std::vec::Vec::new());
+        BytesWriter::new().write(&message, &mut sink).unwrap();
+        assert_eq!(sink.data(), &[0xF0, 0x0, 0x1, 0x2, 0x3, 0xF7]);
+    }
+
+    #[cfg(all(feature = "channel-voice1", feature = "system-common"))]
+    mod with_messages {
+        use super::*;
+        use crate::{
+            channel_voice1::{ControlChange, NoteOn},
+            message::BytesMessage,
+            system_common::{SongSelect, TimingClock},
+            ux::{u4, u7},
+        };
+        use pretty_assertions::assert_eq;
+
+        fn note_on(channel: u4, note: u8) -> BytesMessage<[u8; 3]> {
+            use crate::traits::Channeled;
+            let mut message = NoteOn::<[u8; 3]>::new();
+            message.set_channel(channel);
+            message.set_note(u7::new(note));
+            message.set_velocity(u7::new(0x40));
+            message.into()
+        }
+
+        fn control_change(control: u8, value: u8) -> BytesMessage<[u8; 3]> {
+            let mut message = ControlChange::<[u8; 3]>::new();
+            message.set_control(u7::new(control));
+            message.set_control_data(u7::new(value));
+            message.into()
+        }
+
+        fn song_select(song: u8) -> BytesMessage<[u8; 3]> {
+            let mut message = SongSelect::<[u8; 3]>::new();
+            message.set_song(u7::new(song));
+            message.into()
+        }
+
+        fn timing_clock() -> BytesMessage<[u8; 3]> {
+            TimingClock::<[u8; 3]>::new().into()
+        }
+
+        #[test]
+        fn without_running_status() {
+            let mut sink = BufferSink::new([0x0; 16]);
+            let mut writer = BytesWriter::new();
+            for message in [note_on(u4::new(0x2), 0x3C), note_on(u4::new(0x2), 0x3E)] {
+                writer.write(&message, &mut sink).unwrap();
+            }
+            assert_eq!(sink.data(), &[0x92, 0x3C, 0x40, 0x92, 0x3E, 0x40]);
+        }
+
+        #[test]
+        fn with_running_status() {
+            let mut sink = BufferSink::new([0x0; 16]);
+            let mut writer = BytesWriter::new();
+            writer.set_running_status(true);
+            for message in [
+                note_on(u4::new(0x2), 0x3C),
+                note_on(u4::new(0x2), 0x3E),
+                note_on(u4::new(0x3), 0x3E),
+            ] {
+                writer.write(&message, &mut sink).unwrap();
+            }
+            assert_eq!(
+                sink.data(),
+                &[0x92, 0x3C, 0x40, 0x3E, 0x40, 0x93, 0x3E, 0x40]
+            );
+        }
+
+        #[test]
+        fn real_time_keeps_running_status() {
+            let mut sink = BufferSink::new([0x0; 16]);
+            let mut writer = BytesWriter::new();
+            writer.set_running_status(true);
+            for message in [
+                note_on(u4::new(0x2), 0x3C),
+                timing_clock(),
+                note_on(u4::new(0x2), 0x3E),
+            ] {
+                writer.write(&message, &mut sink).unwrap();
+            }
+            assert_eq!(sink.data(), &[0x92, 0x3C, 0x40, 0xF8, 0x3E, 0x40]);
+        }
+
+        #[test]
+        fn system_common_resets_running_status() {
+            let mut sink = BufferSink::new([0x0; 16]);
+            let mut writer = BytesWriter::new();
+            writer.set_running_status(true);
+            for message in [
+                control_change(0x07, 0x7F),
+                song_select(0x05),
+                control_change(0x07, 0x00),
+            ] {
+                writer.write(&message, &mut sink).unwrap();
+            }
+            assert_eq!(
+                sink.data(),
+                &[0xB0, 0x07, 0x7F, 0xF3, 0x05, 0xB0, 0x07, 0x00]
+            );
+        }
+
+        #[test]
+        fn overflow_keeps_running_status() {
+            let mut sink = BufferSink::new([0x0; 4]);
+            let mut writer = BytesWriter::new();
+            writer.set_running_status(true);
+            assert_eq!(
+                writer.write(&note_on(u4::new(0x2), 0x3C), &mut sink),
+                Ok(())
+            );
+            assert_eq!(
+                writer.write(&note_on(u4::new(0x3), 0x3C), &mut sink),
+                Err(BufferOverflow)
+            );
+            assert_eq!(
+                writer.write(&note_on(u4::new(0x2), 0x3E), &mut sink),
+                Err(BufferOverflow)
+            );
+            sink.clear();
+            assert_eq!(
+                writer.write(&note_on(u4::new(0x2), 0x3E), &mut sink),
+                Ok(())
+            );
+            assert_eq!(sink.data(), &[0x3E, 0x40]);
+        }
+
+        #[test]
+        #[cfg(feature = "sysex7")]
+        fn round_trip() {
+            use crate::stream::{BytesParser, BytesParserItem};
+
+            let messages = [
+                note_on(u4::new(0x2), 0x3C),
+                note_on(u4::new(0x2), 0x3E),
+                timing_clock(),
+                note_on(u4::new(0x2), 0x40),
+                control_change(0x07, 0x7F),
+                control_change(0x0A, 0x40),
+                song_select(0x05),
+                control_change(0x0A, 0x20),
+                note_on(u4::new(0x5), 0x3C),
+            ];
+
+            for use_running_status in [false, true] {
+                let mut writer = BytesWriter::new();
+                writer.set_running_status(use_running_status);
+
+                let mut sink = BufferSink::new(std::vec::Vec::new());
+                for message in messages.iter() {
+                    writer.write(message, &mut sink).unwrap();
+                }
+
+                let mut parser = BytesParser::<[u8; 8]>::new();
+                let parsed = parser
+                    .parse(sink.data())
+                    .map(|item| match item {
+                        Ok(BytesParserItem::Message(m)) => m,
+                        _ => panic!("Unexpected item {:?}", item),
+                    })
+                    .collect::<std::vec::Vec<_>>();
+                assert_eq!(parsed, messages);
+            }
+        }
+    }
+}
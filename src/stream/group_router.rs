@@ -0,0 +1,359 @@
+use crate::{
+    buffer::{BufferMut, Ump},
+    error::Error,
+    message::UmpMessage,
+    traits::OptionalGrouped,
+    ump_stream::{Direction, FunctionBlock},
+    ux::u4,
+};
+use std::vec::Vec;
+
+const NUMBER_OF_GROUPS: u8 = 16;
+const ERR_NO_SUCH_FUNCTION_BLOCK: &str = "No such function block";
+const ERR_GROUPS_OUT_OF_RANGE: &str = "Function block groups run past the last group";
+
+/// Why a [GroupRouter] couldn't route a message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupRouterError {
+    /// The message doesn't carry a group, as with utility
+    /// and ump stream messages.
+    Ungrouped,
+    /// No active function block which receives messages
+    /// spans the group of the message.
+    UnownedGroup(u4),
+    /// The message was routed to the function block with the given
+    /// index, which has no sink.
+    MissingSink(usize),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Route {
+    block: FunctionBlock,
+    output_first_group: u8,
+}
+
+/// Dispatches messages to the function blocks of an endpoint by group.
+///
+/// Each block owns the groups it spans. Blocks with the
+/// [Output](crate::ump_stream::Direction::Output) direction only send
+/// messages, so nothing is routed to them. When the spans of active
+/// blocks overlap, the first block in the table takes the message.
+/// A block's groups can be remapped onto a different range of groups,
+/// which is applied to the messages as they are routed.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     ump_stream::FunctionBlock,
+///     GroupRouter, GroupRouterError,
+/// };
+/// use std::vec::Vec;
+///
+/// let mut router = GroupRouter::new(&[
+///     FunctionBlock {
+///         first_group: u4::new(0x0),
+///         number_of_groups_spanned: 2,
+///         ..Default::default()
+///     },
+///     FunctionBlock {
+///         first_group: u4::new(0x2),
+///         ..Default::default()
+///     },
+/// ]);
+/// // the second block's engine expects its messages on group 0
+/// router.remap(1, u4::new(0x0)).unwrap();
+///
+/// let mut piano = Vec::new();
+/// let mut drums = Vec::new();
+/// {
+///     let mut sinks: [&mut dyn FnMut(UmpMessage<Vec<u32>>); 2] =
+///         [&mut |m| piano.push(m), &mut |m| drums.push(m)];
+///
+///     let note_on = UmpMessage::try_from(&[0x4290_3C00, 0xFFFF_0000][..]).unwrap();
+///     router.dispatch(note_on.rebuffer_into(), &mut sinks).unwrap();
+///
+///     let unowned = UmpMessage::try_from(&[0x4590_3C00, 0xFFFF_0000][..]).unwrap();
+///     assert_eq!(
+///         router.dispatch(unowned.rebuffer_into(), &mut sinks),
+///         Err(GroupRouterError::UnownedGroup(u4::new(0x5))),
+///     );
+/// }
+/// assert!(piano.is_empty());
+/// assert_eq!(drums[0].data(), &[0x4090_3C00, 0xFFFF_0000]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GroupRouter {
+    routes: Vec<Route>,
+}
+
+impl GroupRouter {
+    /// A router for the given table of function blocks,
+    /// indexed by function block number.
+    pub fn new(function_blocks: &[FunctionBlock]) -> Self {
+        GroupRouter {
+            routes: function_blocks
+                .iter()
+                .map(|block| Route {
+                    block: block.clone(),
+                    output_first_group: u8::from(block.first_group),
+                })
+                .collect(),
+        }
+    }
+
+    /// The number of function blocks in the table.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Moves the groups of a block onto the range beginning at
+    /// `first_group` for the messages it is routed.
+    pub fn remap(&mut self, block: usize, first_group: u4) -> Result<(), Error> {
+        let Some(route) = self.routes.get_mut(block) else {
+            return Err(Error::InvalidData(ERR_NO_SUCH_FUNCTION_BLOCK));
+        };
+        if u8::from(first_group) + route.block.number_of_groups_spanned.min(NUMBER_OF_GROUPS)
+            > NUMBER_OF_GROUPS
+        {
+            return Err(Error::InvalidData(ERR_GROUPS_OUT_OF_RANGE));
+        }
+        route.output_first_group = u8::from(first_group);
+        Ok(())
+    }
+
+    /// Activates or deactivates a block, for example when the endpoint
+    /// sends an updated [FunctionBlockInfo](crate::ump_stream::FunctionBlockInfo).
+    pub fn set_active(&mut self, block: usize, active: bool) -> Result<(), Error> {
+        let Some(route) = self.routes.get_mut(block) else {
+            return Err(Error::InvalidData(ERR_NO_SUCH_FUNCTION_BLOCK));
+        };
+        route.block.active = active;
+        Ok(())
+    }
+
+    /// Finds the block which owns the group of the message
+    /// and applies the block's remapping to it.
+    ///
+    /// Returns the index of the block.
+    pub fn route<B: Ump + BufferMut>(
+        &self,
+        message: &mut UmpMessage<B>,
+    ) -> Result<usize, GroupRouterError> {
+        let group = message.group().ok_or(GroupRouterError::Ungrouped)?;
+        let (index, route) = self
+            .routes
+            .iter()
+            .enumerate()
+            .find(|(_, route)| {
+                route.block.active
+                    && route.block.direction != Direction::Output
+                    && route.block.contains_group(group)
+            })
+            .ok_or(GroupRouterError::UnownedGroup(group))?;
+        let first_group = u8::from(route.block.first_group);
+        if route.output_first_group != first_group {
+            let offset = u8::from(group) - first_group;
+            message.set_group(u4::new(route.output_first_group + offset));
+        }
+        Ok(index)
+    }
+
+    /// Routes the message and passes it on to the sink of its block.
+    ///
+    /// The sinks are indexed by function block number.
+    /// A message routed to a block without a sink is dropped
+    /// and reported with [GroupRouterError::MissingSink].
+    pub fn dispatch<B, S>(
+        &self,
+        mut message: UmpMessage<B>,
+        sinks: &mut [S],
+    ) -> Result<(), GroupRouterError>
+    where
+        B: Ump + BufferMut,
+        S: FnMut(UmpMessage<B>),
+    {
+        let index = self.route(&mut message)?;
+        let sink = sinks
+            .get_mut(index)
+            .ok_or(GroupRouterError::MissingSink(index))?;
+        sink(message);
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "channel-voice2"))]
+mod tests {
+    use super::*;
+    use crate::traits::{Data, RebufferInto};
+    use pretty_assertions::assert_eq;
+
+    fn router() -> GroupRouter {
+        GroupRouter::new(&[
+            FunctionBlock {
+                first_group: u4::new(0x0),
+                number_of_groups_spanned: 2,
+                ..Default::default()
+            },
+            FunctionBlock {
+                first_group: u4::new(0x4),
+                number_of_groups_spanned: 4,
+                ..Default::default()
+            },
+            FunctionBlock {
+                active: false,
+                first_group: u4::new(0x8),
+                ..Default::default()
+            },
+        ])
+    }
+
+    fn note_on(group: u8) -> UmpMessage<Vec<u32>> {
+        let word = 0x4090_3C00 | (u32::from(group) << 24);
+        UmpMessage::try_from(&[word, 0xFFFF_0000][..])
+            .unwrap()
+            .rebuffer_into()
+    }
+
+    #[test]
+    fn route_to_block() {
+        let router = router();
+        assert_eq!(router.route(&mut note_on(0x1)), Ok(0));
+        assert_eq!(router.route(&mut note_on(0x4)), Ok(1));
+        assert_eq!(router.route(&mut note_on(0x7)), Ok(1));
+    }
+
+    #[test]
+    fn unowned_group() {
+        assert_eq!(
+            router().route(&mut note_on(0x2)),
+            Err(GroupRouterError::UnownedGroup(u4::new(0x2))),
+        );
+    }
+
+    #[test]
+    fn inactive_block() {
+        assert_eq!(
+            router().route(&mut note_on(0x8)),
+            Err(GroupRouterError::UnownedGroup(u4::new(0x8))),
+        );
+    }
+
+    #[test]
+    fn output_block() {
+        let router = GroupRouter::new(&[
+            FunctionBlock {
+                first_group: u4::new(0x0),
+                number_of_groups_spanned: 4,
+                direction: Direction::Output,
+                ..Default::default()
+            },
+            FunctionBlock {
+                first_group: u4::new(0x2),
+                direction: Direction::Input,
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(
+            router.route(&mut note_on(0x0)),
+            Err(GroupRouterError::UnownedGroup(u4::new(0x0))),
+        );
+        assert_eq!(router.route(&mut note_on(0x2)), Ok(1));
+    }
+
+    #[test]
+    fn activate_block() {
+        let mut router = router();
+        router.set_active(2, true).unwrap();
+        assert_eq!(router.route(&mut note_on(0x8)), Ok(2));
+    }
+
+    #[test]
+    fn overlapping_blocks() {
+        let router = GroupRouter::new(&[
+            FunctionBlock {
+                first_group: u4::new(0x0),
+                number_of_groups_spanned: 4,
+                ..Default::default()
+            },
+            FunctionBlock {
+                first_group: u4::new(0x2),
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(router.route(&mut note_on(0x2)), Ok(0));
+    }
+
+    #[cfg(feature = "utility")]
+    #[test]
+    fn ungrouped() {
+        let mut message: UmpMessage<Vec<u32>> = UmpMessage::try_from(&[0x0020_1234][..])
+            .unwrap()
+            .rebuffer_into();
+        assert_eq!(
+            router().route(&mut message),
+            Err(GroupRouterError::Ungrouped)
+        );
+    }
+
+    #[test]
+    fn remap() {
+        let mut router = router();
+        router.remap(1, u4::new(0xA)).unwrap();
+        let mut message = note_on(0x5);
+        assert_eq!(router.route(&mut message), Ok(1));
+        assert_eq!(message.data(), &[0x4B90_3C00, 0xFFFF_0000]);
+    }
+
+    #[test]
+    fn remap_out_of_range() {
+        assert_eq!(
+            router().remap(1, u4::new(0xD)),
+            Err(Error::InvalidData(ERR_GROUPS_OUT_OF_RANGE)),
+        );
+    }
+
+    #[test]
+    fn remap_missing_block() {
+        assert_eq!(
+            router().remap(3, u4::new(0x0)),
+            Err(Error::InvalidData(ERR_NO_SUCH_FUNCTION_BLOCK)),
+        );
+    }
+
+    #[test]
+    fn dispatch() {
+        let mut router = router();
+        router.remap(0, u4::new(0xE)).unwrap();
+        let mut received = [std::vec::Vec::new(), std::vec::Vec::new()];
+        {
+            let [first, second] = &mut received;
+            let mut sinks: [&mut dyn FnMut(UmpMessage<Vec<u32>>); 2] =
+                [&mut |m| first.push(m), &mut |m| second.push(m)];
+            router.dispatch(note_on(0x1), &mut sinks).unwrap();
+            router.dispatch(note_on(0x6), &mut sinks).unwrap();
+            assert_eq!(
+                router.dispatch(note_on(0x3), &mut sinks),
+                Err(GroupRouterError::UnownedGroup(u4::new(0x3))),
+            );
+        }
+        assert_eq!(received[0].len(), 1);
+        assert_eq!(received[0][0].data(), &[0x4F90_3C00, 0xFFFF_0000]);
+        assert_eq!(received[1].len(), 1);
+        assert_eq!(received[1][0].data(), &[0x4690_3C00, 0xFFFF_0000]);
+    }
+
+    #[test]
+    fn dispatch_missing_sink() {
+        let mut router = router();
+        router.set_active(2, true).unwrap();
+        let mut sinks: [&mut dyn FnMut(UmpMessage<Vec<u32>>); 2] = [&mut |_| {}, &mut |_| {}];
+        assert_eq!(
+            router.dispatch(note_on(0x8), &mut sinks),
+            Err(GroupRouterError::MissingSink(2)),
+        );
+    }
+}
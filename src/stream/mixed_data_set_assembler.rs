@@ -0,0 +1,499 @@
+use crate::{
+    stream::PacketEvents,
+    sysex8::{MixedDataSet, MixedDataSetHeader, MixedDataSetPayload},
+    traits::Grouped,
+    ux::u4,
+};
+
+const PACKET_SIZE: usize = 4;
+
+/// A Mixed Data Set combined from all of its chunks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MixedDataSetBlob {
+    pub group: u4,
+    pub mds_id: u4,
+    pub manufacturer_id: u16,
+    pub device_id: u16,
+    pub sub_id_1: u16,
+    pub sub_id_2: u16,
+    pub data: std::vec::Vec<u8>,
+}
+
+/// The outcome of pushing a packet into a [MixedDataSetAssembler].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MixedDataSetAssemblerEvent {
+    /// A complete data set.
+    Complete(MixedDataSetBlob),
+    /// The header of a chunk arrived without the preceding chunks
+    /// of its data set. The chunk is discarded.
+    MissingChunk { group: u4, mds_id: u4 },
+    /// A payload packet arrived without a chunk in progress for its
+    /// group and mds id. The packet is discarded.
+    Orphaned { group: u4, mds_id: u4 },
+    /// A header arrived which doesn't continue the data set in progress
+    /// for the same group and mds id. The partial data set is discarded.
+    Interrupted { group: u4, mds_id: u4 },
+    /// The data set in progress outgrew the maximum size of the assembler.
+    /// The rest of its packets are discarded.
+    BufferOverflow { group: u4, mds_id: u4 },
+    /// The packet isn't a valid mixed data set packet.
+    InvalidPacket,
+}
+
+#[derive(Clone, Debug)]
+struct Progress {
+    number_of_chunks: u16,
+    chunk_number: u16,
+    ids: [u16; 4],
+    remaining: usize,
+    data: std::vec::Vec<u8>,
+    overflowed: bool,
+}
+
+/// Combines the chunks of Mixed Data Sets into single blobs of data.
+///
+/// Data sets on different groups, or with different mds ids on the same
+/// group, may be interleaved. The assembler keeps a data set in progress
+/// for each. Chunks must arrive in order, each header followed by
+/// the payload packets for its valid bytes.
+///
+/// ```rust
+/// use midi2::{MixedDataSetAssembler, MixedDataSetAssemblerEvent};
+///
+/// let mut assembler = MixedDataSetAssembler::new(1024);
+///
+/// // chunk 1 of 2 carrying 3 bytes
+/// assert_eq!(assembler.push(&[0x5080_0003, 0x0002_0001, 0x0, 0x0]).next(), None);
+/// assert_eq!(assembler.push(&[0x5090_0102, 0x0300_0000, 0x0, 0x0]).next(), None);
+/// // chunk 2 of 2 carrying 1 byte
+/// assert_eq!(assembler.push(&[0x5080_0001, 0x0002_0002, 0x0, 0x0]).next(), None);
+/// let Some(MixedDataSetAssemblerEvent::Complete(blob)) =
+///     assembler.push(&[0x5090_0400, 0x0, 0x0, 0x0]).next()
+/// else {
+///     panic!();
+/// };
+/// assert_eq!(blob.data, std::vec![0x1, 0x2, 0x3, 0x4]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MixedDataSetAssembler {
+    sets: std::collections::BTreeMap<(u8, u8), Progress>,
+    max_size: usize,
+}
+
+impl MixedDataSetAssembler {
+    /// Data sets larger than `max_size` bytes are reported with
+    /// [MixedDataSetAssemblerEvent::BufferOverflow].
+    pub fn new(max_size: usize) -> Self {
+        MixedDataSetAssembler {
+            sets: Default::default(),
+            max_size,
+        }
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// The number of data sets in progress.
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Feed the next mixed data set packet into the assembler.
+    ///
+    /// A packet yields at most two events: one for a discarded
+    /// data set and one for the chunk begun by the packet.
+    pub fn push(&mut self, packet: &[u32]) -> PacketEvents<MixedDataSetAssemblerEvent> {
+        let message = packet
+            .get(..PACKET_SIZE)
+            .and_then(|packet| MixedDataSet::try_from(packet).ok());
+        match message {
+            Some(MixedDataSet::Header(header)) => self.push_header(header),
+            Some(MixedDataSet::Payload(payload)) => {
+                PacketEvents::new(None, self.push_payload(payload))
+            }
+            _ => PacketEvents::new(None, Some(MixedDataSetAssemblerEvent::InvalidPacket)),
+        }
+    }
+
+    /// Discard every data set in progress.
+    pub fn reset(&mut self) {
+        self.sets.clear();
+    }
+
+    fn push_header(
+        &mut self,
+        header: MixedDataSetHeader<&[u32]>,
+    ) -> PacketEvents<MixedDataSetAssemblerEvent> {
+        let group = header.group();
+        let mds_id = header.mds_id();
+        let number_of_chunks = header.number_of_chunks();
+        let chunk_number = header.chunk_number();
+        let ids = [
+            header.manufacturer_id(),
+            header.device_id(),
+            header.sub_id_1(),
+            header.sub_id_2(),
+        ];
+        if chunk_number == 0 || chunk_number > number_of_chunks {
+            return PacketEvents::new(None, Some(MixedDataSetAssemblerEvent::InvalidPacket));
+        }
+
+        let key = (u8::from(group), u8::from(mds_id));
+        let interrupted = match self.sets.get(&key) {
+            Some(progress)
+                if progress.remaining == 0
+                    && progress.number_of_chunks == number_of_chunks
+                    && progress.chunk_number + 1 == chunk_number
+                    && progress.ids == ids =>
+            {
+                None
+            }
+            Some(_) => {
+                let progress = self.sets.remove(&key).unwrap();
+                (!progress.overflowed)
+                    .then_some(MixedDataSetAssemblerEvent::Interrupted { group, mds_id })
+            }
+            None => None,
+        };
+
+        let progress = match self.sets.get_mut(&key) {
+            Some(progress) => progress,
+            None if chunk_number == 1 => self.sets.entry(key).or_insert(Progress {
+                number_of_chunks,
+                chunk_number: 0,
+                ids,
+                remaining: 0,
+                data: Default::default(),
+                overflowed: false,
+            }),
+            None => {
+                return PacketEvents::new(
+                    interrupted,
+                    Some(MixedDataSetAssemblerEvent::MissingChunk { group, mds_id }),
+                )
+            }
+        };
+        progress.chunk_number = chunk_number;
+        progress.remaining = usize::from(header.number_of_valid_bytes());
+
+        PacketEvents::new(interrupted, self.finish(key, group, mds_id))
+    }
+
+    fn push_payload(
+        &mut self,
+        payload: MixedDataSetPayload<&[u32]>,
+    ) -> Option<MixedDataSetAssemblerEvent> {
+        let group = payload.group();
+        let mds_id = payload.mds_id();
+        let key = (u8::from(group), u8::from(mds_id));
+        let Some(progress) = self.sets.get_mut(&key).filter(|p| p.remaining != 0) else {
+            return Some(MixedDataSetAssemblerEvent::Orphaned { group, mds_id });
+        };
+
+        let bytes = payload.payload();
+        let len = progress.remaining.min(bytes.len());
+        progress.remaining -= len;
+        if progress.overflowed {
+            return self.finish(key, group, mds_id);
+        }
+        if progress.data.len() + len > self.max_size {
+            progress.overflowed = true;
+            progress.data = Default::default();
+            self.finish(key, group, mds_id);
+            return Some(MixedDataSetAssemblerEvent::BufferOverflow { group, mds_id });
+        }
+        progress.data.extend_from_slice(&bytes[..len]);
+        self.finish(key, group, mds_id)
+    }
+
+    // remove the data set if its last chunk has been received
+    fn finish(
+        &mut self,
+        key: (u8, u8),
+        group: u4,
+        mds_id: u4,
+    ) -> Option<MixedDataSetAssemblerEvent> {
+        let progress = self.sets.get(&key)?;
+        if progress.remaining != 0 || progress.chunk_number != progress.number_of_chunks {
+            return None;
+        }
+        let progress = self.sets.remove(&key)?;
+        if progress.overflowed {
+            return None;
+        }
+        let [manufacturer_id, device_id, sub_id_1, sub_id_2] = progress.ids;
+        Some(MixedDataSetAssemblerEvent::Complete(MixedDataSetBlob {
+            group,
+            mds_id,
+            manufacturer_id,
+            device_id,
+            sub_id_1,
+            sub_id_2,
+            data: progress.data,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    type Event = MixedDataSetAssemblerEvent;
+
+    fn push(assembler: &mut MixedDataSetAssembler, packet: [u32; 4]) -> std::vec::Vec<Event> {
+        assembler.push(&packet[..]).collect()
+    }
+
+    fn complete(group: u8, mds_id: u8, data: &[u8]) -> Event {
+        Event::Complete(MixedDataSetBlob {
+            group: u4::new(group),
+            mds_id: u4::new(mds_id),
+            manufacturer_id: 0x1234,
+            device_id: 0x5678,
+            sub_id_1: 0x9ABC,
+            sub_id_2: 0xDEF0,
+            data: data.to_vec(),
+        })
+    }
+
+    #[test]
+    fn single_chunk() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0010, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5090_0001, 0x0203_0405, 0x0607_0809, 0x0A0B_0C0D]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0E0F, 0x0, 0x0, 0x0]),
+            std::vec![complete(
+                0x0,
+                0x0,
+                &[0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD, 0xE, 0xF]
+            )],
+        );
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn multiple_chunks() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0002, 0x0003_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0102, 0x0, 0x0, 0x0]),
+            std::vec![]
+        );
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0001, 0x0003_0002, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0300, 0x0, 0x0, 0x0]),
+            std::vec![]
+        );
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0001, 0x0003_0003, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0400, 0x0, 0x0, 0x0]),
+            std::vec![complete(0x0, 0x0, &[0x1, 0x2, 0x3, 0x4])],
+        );
+    }
+
+    #[test]
+    fn empty_chunk() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0000, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![complete(0x0, 0x0, &[])],
+        );
+    }
+
+    #[test]
+    fn interleaved_sets() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0001, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5081_0001, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5380_0001, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(assembler.len(), 3);
+        assert_eq!(
+            push(&mut assembler, [0x5091_0B00, 0x0, 0x0, 0x0]),
+            std::vec![complete(0x0, 0x1, &[0xB])],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5390_0C00, 0x0, 0x0, 0x0]),
+            std::vec![complete(0x3, 0x0, &[0xC])],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0A00, 0x0, 0x0, 0x0]),
+            std::vec![complete(0x0, 0x0, &[0xA])],
+        );
+    }
+
+    #[test]
+    fn orphaned_payload() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(&mut assembler, [0x5392_0102, 0x0, 0x0, 0x0]),
+            std::vec![Event::Orphaned {
+                group: u4::new(0x3),
+                mds_id: u4::new(0x2)
+            }],
+        );
+    }
+
+    #[test]
+    fn missing_chunk() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0001, 0x0002_0002, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![Event::MissingChunk {
+                group: u4::new(0x0),
+                mds_id: u4::new(0x0)
+            }],
+        );
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn interrupted() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0002, 0x0002_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0001, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![Event::Interrupted {
+                group: u4::new(0x0),
+                mds_id: u4::new(0x0)
+            }],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0500, 0x0, 0x0, 0x0]),
+            std::vec![complete(0x0, 0x0, &[0x5])],
+        );
+    }
+
+    #[test]
+    fn buffer_overflow() {
+        let mut assembler = MixedDataSetAssembler::new(4);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0010, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0000, 0x0, 0x0, 0x0]),
+            std::vec![Event::BufferOverflow {
+                group: u4::new(0x0),
+                mds_id: u4::new(0x0)
+            }],
+        );
+        assert_eq!(
+            push(&mut assembler, [0x5090_0000, 0x0, 0x0, 0x0]),
+            std::vec![]
+        );
+        assert!(assembler.is_empty());
+    }
+
+    #[test]
+    fn invalid_chunk_number() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            push(
+                &mut assembler,
+                [0x5080_0001, 0x0001_0002, 0x1234_5678, 0x9ABC_DEF0]
+            ),
+            std::vec![Event::InvalidPacket],
+        );
+    }
+
+    #[test]
+    fn sysex8_packet() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        assert_eq!(
+            assembler
+                .push(&[0x5002_0001, 0x0, 0x0, 0x0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![Event::InvalidPacket],
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut assembler = MixedDataSetAssembler::new(64);
+        push(
+            &mut assembler,
+            [0x5080_0001, 0x0001_0001, 0x1234_5678, 0x9ABC_DEF0],
+        );
+        assembler.reset();
+        assert_eq!(
+            push(&mut assembler, [0x5090_0100, 0x0, 0x0, 0x0]),
+            std::vec![Event::Orphaned {
+                group: u4::new(0x0),
+                mds_id: u4::new(0x0)
+            }],
+        );
+    }
+}
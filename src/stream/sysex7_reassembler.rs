@@ -0,0 +1,431 @@
+use crate::{
+    buffer::{BufferDefault, BufferMut, BufferTryResize, Ump},
+    detail::BitOps,
+    stream::PacketEvents,
+    sysex7::{self, Sysex7},
+    traits::TryRebufferInto,
+    ux::u4,
+};
+
+const NUMBER_OF_GROUPS: usize = 16;
+const PACKET_SIZE: usize = 2;
+
+const COMPLETE: u8 = 0x0;
+const START: u8 = 0x1;
+const CONTINUE: u8 = 0x2;
+const END: u8 = 0x3;
+
+/// The outcome of pushing a packet into a [Sysex7Reassembler].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sysex7ReassemblerEvent<B: Ump> {
+    /// A complete message.
+    Message(Sysex7<B>),
+    /// A continue or end packet arrived without a preceding start packet.
+    /// The packet is discarded.
+    MissingStart { group: u4 },
+    /// A start or complete packet arrived before the end of the message
+    /// in progress on the same group. The partial message is discarded.
+    Interrupted { group: u4 },
+    /// The message in progress outgrew the buffer. The packets up to and
+    /// including the end packet of the message are discarded.
+    BufferOverflow { group: u4 },
+    /// The packet isn't a valid sysex7 packet.
+    InvalidPacket,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupState {
+    Idle,
+    Active(usize),
+    Overflowed,
+}
+
+/// Reassembles sysex7 messages from individual ump packets.
+///
+/// The packets of messages on different groups may be interleaved, so
+/// the reassembler keeps a message in progress for each group.
+/// Problems with the packet sequence are reported as events rather
+/// than being dropped silently.
+///
+/// Messages are collected in buffers of type `B`. When `B` is a fixed
+/// size array the reassembler doesn't allocate and messages which
+/// are too large to fit are reported with
+/// [Sysex7ReassemblerEvent::BufferOverflow].
+///
+/// ```rust
+/// use midi2::{prelude::*, Sysex7Reassembler, Sysex7ReassemblerEvent};
+///
+/// let mut reassembler = Sysex7Reassembler::<[u32; 16]>::new();
+///
+/// assert_eq!(reassembler.push(&[0x3016_0001, 0x0203_0405]).next(), None);
+/// // a packet from another group
+/// assert_eq!(reassembler.push(&[0x3101_0000, 0x0000_0000]).count(), 1);
+///
+/// let Some(Sysex7ReassemblerEvent::Message(message)) =
+///     reassembler.push(&[0x3032_0607, 0x0000_0000]).next()
+/// else {
+///     panic!();
+/// };
+/// assert_eq!(
+///     message.payload().map(u8::from).collect::<Vec<_>>(),
+///     std::vec![0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7],
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sysex7Reassembler<B: Ump + BufferMut + BufferDefault + BufferTryResize> {
+    buffers: [B; NUMBER_OF_GROUPS],
+    states: [GroupState; NUMBER_OF_GROUPS],
+}
+
+impl<B: Ump + BufferMut + BufferDefault + BufferTryResize> Default for Sysex7Reassembler<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B: Ump + BufferMut + BufferDefault + BufferTryResize> Sysex7Reassembler<B> {
+    pub fn new() -> Self {
+        Sysex7Reassembler {
+            buffers: core::array::from_fn(|_| B::default()),
+            states: [GroupState::Idle; NUMBER_OF_GROUPS],
+        }
+    }
+
+    /// Feed the next sysex7 packet into the reassembler.
+    ///
+    /// A packet yields at most two events: one for an interrupted
+    /// message and one for the message started by the packet.
+    pub fn push(&mut self, packet: &[u32]) -> PacketEvents<Sysex7ReassemblerEvent<B>> {
+        let invalid = || PacketEvents::new(None, Some(Sysex7ReassemblerEvent::InvalidPacket));
+        let Some(packet) = packet.get(..PACKET_SIZE) else {
+            return invalid();
+        };
+        if u8::from(packet[0].nibble(0)) != sysex7::UMP_MESSAGE_TYPE
+            || u8::from(packet[0].nibble(2)) > END
+            || u8::from(packet[0].nibble(3)) > 6
+        {
+            return invalid();
+        }
+
+        let group = packet[0].nibble(1);
+        let index = usize::from(u8::from(group));
+        let status = u8::from(packet[0].nibble(2));
+
+        let interrupted = match (self.states[index], status) {
+            (GroupState::Idle, COMPLETE | START) => None,
+            (_, COMPLETE | START) => {
+                self.states[index] = GroupState::Idle;
+                Some(Sysex7ReassemblerEvent::Interrupted { group })
+            }
+            _ => None,
+        };
+        let event = match (self.states[index], status) {
+            (_, COMPLETE) => Some(message(packet, group)),
+            (_, START) => self.append(index, 0, packet, group),
+            (GroupState::Idle, _) => Some(Sysex7ReassemblerEvent::MissingStart { group }),
+            (GroupState::Overflowed, CONTINUE) => None,
+            (GroupState::Overflowed, _) => {
+                self.states[index] = GroupState::Idle;
+                None
+            }
+            (GroupState::Active(len), CONTINUE) => self.append(index, len, packet, group),
+            (GroupState::Active(len), _) => {
+                let event = self
+                    .append(index, len, packet, group)
+                    .unwrap_or_else(|| self.finish(index, group));
+                // the end packet closes the message even when it overflows
+                self.states[index] = GroupState::Idle;
+                Some(event)
+            }
+        };
+
+        PacketEvents::new(interrupted, event)
+    }
+
+    /// Discard the messages in progress on every group.
+    pub fn reset(&mut self) {
+        self.states = [GroupState::Idle; NUMBER_OF_GROUPS];
+    }
+
+    // add a packet to the message in progress.
+    // only overflow is reported.
+    fn append(
+        &mut self,
+        index: usize,
+        len: usize,
+        packet: &[u32],
+        group: u4,
+    ) -> Option<Sysex7ReassemblerEvent<B>> {
+        let buffer = &mut self.buffers[index];
+        if buffer.try_resize(len + PACKET_SIZE).is_err() {
+            self.states[index] = GroupState::Overflowed;
+            return Some(Sysex7ReassemblerEvent::BufferOverflow { group });
+        }
+        buffer.buffer_mut()[len..len + PACKET_SIZE].copy_from_slice(packet);
+        self.states[index] = GroupState::Active(len + PACKET_SIZE);
+        None
+    }
+
+    fn finish(&mut self, index: usize, group: u4) -> Sysex7ReassemblerEvent<B> {
+        let GroupState::Active(len) = self.states[index] else {
+            unreachable!();
+        };
+        self.states[index] = GroupState::Idle;
+        message(&self.buffers[index].buffer()[..len], group)
+    }
+}
+
+fn message<B: Ump + BufferMut + BufferDefault + BufferTryResize>(
+    data: &[u32],
+    group: u4,
+) -> Sysex7ReassemblerEvent<B> {
+    let Ok(message) = Sysex7::try_from(data) else {
+        return Sysex7ReassemblerEvent::InvalidPacket;
+    };
+    match message.try_rebuffer_into() {
+        Ok(message) => Sysex7ReassemblerEvent::Message(message),
+        Err(_) => Sysex7ReassemblerEvent::BufferOverflow { group },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Data;
+    use pretty_assertions::assert_eq;
+
+    type Reassembler = Sysex7Reassembler<[u32; 6]>;
+    type Event = Sysex7ReassemblerEvent<[u32; 6]>;
+
+    fn push<B: Ump + BufferMut + BufferDefault + BufferTryResize>(
+        reassembler: &mut Sysex7Reassembler<B>,
+        packet: [u32; 2],
+    ) -> std::vec::Vec<Sysex7ReassemblerEvent<B>> {
+        reassembler.push(&packet[..]).collect()
+    }
+
+    fn complete(data: &[u32]) -> Event {
+        Event::Message(Sysex7::try_from(data).unwrap().try_rebuffer_into().unwrap())
+    }
+
+    #[test]
+    fn complete_packet() {
+        let mut reassembler = Reassembler::new();
+        assert_eq!(
+            push(&mut reassembler, [0x3502_0102, 0x0000_0000]),
+            std::vec![complete(&[0x3502_0102, 0x0000_0000])],
+        );
+    }
+
+    #[test]
+    fn multi_packet_message() {
+        let mut reassembler = Reassembler::new();
+        let data = [
+            0x3016_0001,
+            0x0203_0405,
+            0x3026_0607,
+            0x0809_0A0B,
+            0x3032_0C0D,
+            0x0000_0000,
+        ];
+        assert_eq!(push(&mut reassembler, [data[0], data[1]]), std::vec![]);
+        assert_eq!(push(&mut reassembler, [data[2], data[3]]), std::vec![]);
+        assert_eq!(
+            push(&mut reassembler, [data[4], data[5]]),
+            std::vec![complete(&data)],
+        );
+    }
+
+    #[test]
+    fn interleaved_groups() {
+        let mut reassembler = Reassembler::new();
+        assert_eq!(
+            push(&mut reassembler, [0x3016_0001, 0x0203_0405]),
+            std::vec![]
+        );
+        assert_eq!(
+            push(&mut reassembler, [0x3716_1011, 0x1213_1415]),
+            std::vec![]
+        );
+        assert_eq!(
+            push(&mut reassembler, [0x3731_1600, 0x0000_0000]),
+            std::vec![complete(&[
+                0x3716_1011,
+                0x1213_1415,
+                0x3731_1600,
+                0x0000_0000
+            ])],
+        );
+        assert_eq!(
+            push(&mut reassembler, [0x3031_0600, 0x0000_0000]),
+            std::vec![complete(&[
+                0x3016_0001,
+                0x0203_0405,
+                0x3031_0600,
+                0x0000_0000
+            ])],
+        );
+    }
+
+    #[test]
+    fn continue_without_start() {
+        let mut reassembler = Reassembler::new();
+        assert_eq!(
+            push(&mut reassembler, [0x3326_0607, 0x0809_0A0B]),
+            std::vec![Event::MissingStart {
+                group: u4::new(0x3)
+            }],
+        );
+    }
+
+    #[test]
+    fn end_without_start() {
+        let mut reassembler = Reassembler::new();
+        assert_eq!(
+            push(&mut reassembler, [0x3032_0C0D, 0x0000_0000]),
+            std::vec![Event::MissingStart {
+                group: u4::new(0x0)
+            }],
+        );
+    }
+
+    #[test]
+    fn start_before_end() {
+        let mut reassembler = Reassembler::new();
+        push(&mut reassembler, [0x3016_0001, 0x0203_0405]);
+        assert_eq!(
+            push(&mut reassembler, [0x3012_0A0B, 0x0000_0000]),
+            std::vec![Event::Interrupted {
+                group: u4::new(0x0)
+            }],
+        );
+        assert_eq!(
+            push(&mut reassembler, [0x3031_0C00, 0x0000_0000]),
+            std::vec![complete(&[
+                0x3012_0A0B,
+                0x0000_0000,
+                0x3031_0C00,
+                0x0000_0000
+            ])],
+        );
+    }
+
+    #[test]
+    fn complete_before_end() {
+        let mut reassembler = Reassembler::new();
+        push(&mut reassembler, [0x3016_0001, 0x0203_0405]);
+        assert_eq!(
+            push(&mut reassembler, [0x3001_0100, 0x0000_0000]),
+            std::vec![
+                Event::Interrupted {
+                    group: u4::new(0x0)
+                },
+                complete(&[0x3001_0100, 0x0000_0000]),
+            ],
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let mut reassembler = Reassembler::new();
+        push(&mut reassembler, [0x3016_0001, 0x0203_0405]);
+        push(&mut reassembler, [0x3026_0607, 0x0809_0A0B]);
+        push(&mut reassembler, [0x3026_0C0D, 0x0E0F_1011]);
+        assert_eq!(
+            push(&mut reassembler, [0x3026_1213, 0x1415_1617]),
+            std::vec![Event::BufferOverflow {
+                group: u4::new(0x0)
+            }],
+        );
+        assert_eq!(
+            push(&mut reassembler, [0x3032_1819, 0x0000_0000]),
+            std::vec![]
+        );
+        // the following message is unaffected
+        assert_eq!(
+            push(&mut reassembler, [0x3001_0100, 0x0000_0000]),
+            std::vec![complete(&[0x3001_0100, 0x0000_0000])],
+        );
+    }
+
+    #[test]
+    fn overflow_on_end_then_continue() {
+        let mut reassembler = Sysex7Reassembler::<[u32; 2]>::new();
+        push(&mut reassembler, [0x3016_0001, 0x0203_0405]);
+        assert_eq!(
+            push(&mut reassembler, [0x3032_0607, 0x0000_0000]),
+            std::vec![Sysex7ReassemblerEvent::BufferOverflow {
+                group: u4::new(0x0)
+            }],
+        );
+        assert_eq!(
+            push(&mut reassembler, [0x3026_0809, 0x0A0B_0C0D]),
+            std::vec![Sysex7ReassemblerEvent::MissingStart {
+                group: u4::new(0x0)
+            }],
+        );
+    }
+
+    #[test]
+    fn overflow_on_end_then_start() {
+        let mut reassembler = Sysex7Reassembler::<[u32; 2]>::new();
+        push(&mut reassembler, [0x3016_0001, 0x0203_0405]);
+        push(&mut reassembler, [0x3032_0607, 0x0000_0000]);
+        assert_eq!(
+            push(&mut reassembler, [0x3016_0809, 0x0A0B_0C0D]),
+            std::vec![],
+        );
+    }
+
+    #[test]
+    fn invalid_packets() {
+        let mut reassembler = Reassembler::new();
+        for packet in [
+            [0x4090_3C00, 0xC000_0000],
+            [0x3047_0000, 0x0000_0000],
+            [0x3007_0000, 0x0000_0000],
+        ] {
+            assert_eq!(
+                push(&mut reassembler, packet),
+                std::vec![Event::InvalidPacket],
+            );
+        }
+        assert_eq!(
+            reassembler
+                .push(&[0x3001_0000])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![Event::InvalidPacket],
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut reassembler = Reassembler::new();
+        push(&mut reassembler, [0x3016_0001, 0x0203_0405]);
+        reassembler.reset();
+        assert_eq!(
+            push(&mut reassembler, [0x3032_0C0D, 0x0000_0000]),
+            std::vec![Event::MissingStart {
+                group: u4::new(0x0)
+            }],
+        );
+    }
+
+    #[test]
+    fn vec_backed() {
+        let mut reassembler = Sysex7Reassembler::<std::vec::Vec<u32>>::new();
+        for _ in 0..20 {
+            assert_eq!(reassembler.push(&[0x3026_0001, 0x0203_0405]).count(), 1);
+        }
+        reassembler.push(&[0x3016_0001, 0x0203_0405]).for_each(drop);
+        for _ in 0..20 {
+            assert_eq!(reassembler.push(&[0x3026_0001, 0x0203_0405]).count(), 0);
+        }
+        let Some(Sysex7ReassemblerEvent::Message(message)) =
+            reassembler.push(&[0x3030_0000, 0x0000_0000]).next()
+        else {
+            panic!();
+        };
+        assert_eq!(message.data().len(), 44);
+    }
+}
@@ -0,0 +1,602 @@
+use crate::{
+    buffer::{BufferDefault, BufferMut, BufferTryResize, Ump},
+    detail::BitOps,
+    stream::PacketEvents,
+    sysex8::{self, Sysex8},
+    traits::TryRebufferInto,
+    ux::u4,
+};
+
+const PACKET_SIZE: usize = 4;
+
+const COMPLETE: u8 = 0x0;
+const START: u8 = 0x1;
+const END: u8 = 0x3;
+
+/// The outcome of pushing a packet into a [Sysex8Reassembler]
+/// or a [Sysex8MapReassembler].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Sysex8ReassemblerEvent<B: Ump> {
+    /// A complete message.
+    Message(Sysex8<B>),
+    /// A continue or end packet arrived without a message in progress
+    /// for its group and stream id. The packet is discarded.
+    Orphaned { group: u4, stream_id: u8 },
+    /// A start or complete packet arrived before the end of the message
+    /// in progress for the same group and stream id.
+    /// The partial message is discarded.
+    Interrupted { group: u4, stream_id: u8 },
+    /// A message in progress was discarded to make room for a new stream.
+    Evicted { group: u4, stream_id: u8 },
+    /// Every slot was in use, so the start packet of a new stream
+    /// was discarded. The rest of its packets will be orphaned.
+    Rejected { group: u4, stream_id: u8 },
+    /// The message in progress outgrew the buffer. The packets up to and
+    /// including the end packet of the message are discarded.
+    BufferOverflow { group: u4, stream_id: u8 },
+    /// The packet isn't a valid sysex8 packet.
+    InvalidPacket,
+}
+
+/// What happens to the start of a new stream when every slot is in use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// Discard the message in progress which was least recently added to.
+    #[default]
+    EvictLeastRecent,
+    /// Discard the new stream.
+    RejectNew,
+}
+
+/// Reassembles sysex8 messages from individual ump packets
+/// into a fixed number of slots.
+///
+/// Each group can carry up to 256 concurrent sysex8 streams, told apart
+/// by their stream id. Every message in progress occupies one of the
+/// `SLOTS` slots of the reassembler until its end packet arrives.
+/// When the slots run out the [EvictionPolicy] decides what to drop.
+///
+/// The reassembler doesn't allocate when `B` is a fixed size array.
+/// See [Sysex8MapReassembler] for a version which allocates slots
+/// on demand.
+///
+/// ```rust
+/// use midi2::{prelude::*, EvictionPolicy, Sysex8Reassembler, Sysex8ReassemblerEvent};
+///
+/// let mut reassembler = Sysex8Reassembler::<[u32; 8], 4>::new(EvictionPolicy::RejectNew);
+///
+/// // interleaved packets from streams 0x1 and 0x2
+/// assert_eq!(reassembler.push(&[0x5013_0101, 0x0200_0000, 0x0, 0x0]).next(), None);
+/// assert_eq!(reassembler.push(&[0x5013_0203, 0x0400_0000, 0x0, 0x0]).next(), None);
+/// let Some(Sysex8ReassemblerEvent::Message(message)) =
+///     reassembler.push(&[0x5032_0105, 0x0, 0x0, 0x0]).next()
+/// else {
+///     panic!();
+/// };
+/// assert_eq!(message.stream_id(), 0x1);
+/// assert_eq!(message.payload().collect::<Vec<u8>>(), std::vec![0x1, 0x2, 0x5]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sysex8Reassembler<
+    B: Ump + BufferMut + BufferDefault + BufferTryResize,
+    const SLOTS: usize,
+> {
+    slots: [Slot<B>; SLOTS],
+    policy: EvictionPolicy,
+    clock: u64,
+}
+
+impl<B: Ump + BufferMut + BufferDefault + BufferTryResize, const SLOTS: usize> Default
+    for Sysex8Reassembler<B, SLOTS>
+{
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<B: Ump + BufferMut + BufferDefault + BufferTryResize, const SLOTS: usize>
+    Sysex8Reassembler<B, SLOTS>
+{
+    pub fn new(policy: EvictionPolicy) -> Self {
+        Sysex8Reassembler {
+            slots: core::array::from_fn(|_| Slot::new(None)),
+            policy,
+            clock: 0,
+        }
+    }
+
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// Feed the next sysex8 packet into the reassembler.
+    ///
+    /// A packet yields at most two events: one for a discarded
+    /// message and one for the message started by the packet.
+    pub fn push(&mut self, packet: &[u32]) -> PacketEvents<Sysex8ReassemblerEvent<B>> {
+        self.clock += 1;
+        push(&mut self.slots, self.policy, self.clock, packet)
+    }
+
+    /// Discard every message in progress.
+    pub fn reset(&mut self) {
+        self.slots.clear();
+    }
+}
+
+/// Reassembles sysex8 messages from individual ump packets,
+/// allocating slots as new streams begin.
+///
+/// This behaves like [Sysex8Reassembler], except that the limit on the
+/// number of messages in progress is set at run time.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct Sysex8MapReassembler<B: Ump + BufferMut + BufferDefault + BufferTryResize> {
+    slots: MapSlots<B>,
+    policy: EvictionPolicy,
+    clock: u64,
+}
+
+#[cfg(feature = "std")]
+impl<B: Ump + BufferMut + BufferDefault + BufferTryResize> Sysex8MapReassembler<B> {
+    pub fn new(max_streams: usize, policy: EvictionPolicy) -> Self {
+        Sysex8MapReassembler {
+            slots: MapSlots {
+                map: Default::default(),
+                max_streams,
+            },
+            policy,
+            clock: 0,
+        }
+    }
+
+    pub fn max_streams(&self) -> usize {
+        self.slots.max_streams
+    }
+
+    pub fn policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// The number of messages in progress.
+    pub fn len(&self) -> usize {
+        self.slots.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.map.is_empty()
+    }
+
+    /// See [Sysex8Reassembler::push].
+    pub fn push(&mut self, packet: &[u32]) -> PacketEvents<Sysex8ReassemblerEvent<B>> {
+        self.clock += 1;
+        push(&mut self.slots, self.policy, self.clock, packet)
+    }
+
+    /// Discard every message in progress.
+    pub fn reset(&mut self) {
+        self.slots.clear();
+    }
+}
+
+// group and stream id
+type Key = (u8, u8);
+
+#[derive(Clone, Debug)]
+struct Slot<B> {
+    key: Option<Key>,
+    buffer: B,
+    len: usize,
+    overflowed: bool,
+    last_used: u64,
+}
+
+impl<B: BufferDefault> Slot<B> {
+    fn new(key: Option<Key>) -> Self {
+        Slot {
+            key,
+            buffer: B::default(),
+            len: 0,
+            overflowed: false,
+            last_used: 0,
+        }
+    }
+}
+
+// storage for the messages in progress
+trait Slots<B> {
+    fn get(&mut self, key: Key) -> Option<&mut Slot<B>>;
+    fn remove(&mut self, key: Key);
+    fn is_full(&self) -> bool;
+    fn least_recent(&self) -> Option<Key>;
+    // there must be room for the new slot
+    fn insert(&mut self, key: Key) -> &mut Slot<B>;
+    fn clear(&mut self);
+}
+
+impl<B, const SLOTS: usize> Slots<B> for [Slot<B>; SLOTS] {
+    fn get(&mut self, key: Key) -> Option<&mut Slot<B>> {
+        self.iter_mut().find(|s| s.key == Some(key))
+    }
+    fn remove(&mut self, key: Key) {
+        if let Some(slot) = self.get(key) {
+            slot.key = None;
+        }
+    }
+    fn is_full(&self) -> bool {
+        self.iter().all(|s| s.key.is_some())
+    }
+    fn least_recent(&self) -> Option<Key> {
+        self.iter()
+            .filter(|s| s.key.is_some())
+            .min_by_key(|s| s.last_used)
+            .and_then(|s| s.key)
+    }
+    fn insert(&mut self, key: Key) -> &mut Slot<B> {
+        let slot = self
+            .iter_mut()
+            .find(|s| s.key.is_none())
+            .expect("A slot should be free");
+        slot.key = Some(key);
+        slot.len = 0;
+        slot.overflowed = false;
+        slot
+    }
+    fn clear(&mut self) {
+        for slot in self.iter_mut() {
+            slot.key = None;
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+struct MapSlots<B> {
+    map: std::collections::BTreeMap<Key, Slot<B>>,
+    max_streams: usize,
+}
+
+#[cfg(feature = "std")]
+impl<B: BufferDefault> Slots<B> for MapSlots<B> {
+    fn get(&mut self, key: Key) -> Option<&mut Slot<B>> {
+        self.map.get_mut(&key)
+    }
+    fn remove(&mut self, key: Key) {
+        self.map.remove(&key);
+    }
+    fn is_full(&self) -> bool {
+        self.map.len() >= self.max_streams
+    }
+    fn least_recent(&self) -> Option<Key> {
+        self.map
+            .iter()
+            .min_by_key(|(_, s)| s.last_used)
+            .map(|(key, _)| *key)
+    }
+    fn insert(&mut self, key: Key) -> &mut Slot<B> {
+        self.map.entry(key).or_insert(Slot::new(Some(key)))
+    }
+    fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+fn push<B: Ump + BufferMut + BufferDefault + BufferTryResize, S: Slots<B>>(
+    slots: &mut S,
+    policy: EvictionPolicy,
+    clock: u64,
+    packet: &[u32],
+) -> PacketEvents<Sysex8ReassemblerEvent<B>> {
+    use Sysex8ReassemblerEvent::*;
+
+    let Some(packet) = packet.get(..PACKET_SIZE) else {
+        return PacketEvents::new(None, Some(InvalidPacket));
+    };
+    if u8::from(packet[0].nibble(0)) != sysex8::UMP_MESSAGE_TYPE
+        || u8::from(packet[0].nibble(2)) > END
+        || !(1..=14).contains(&u8::from(packet[0].nibble(3)))
+    {
+        return PacketEvents::new(None, Some(InvalidPacket));
+    }
+
+    let group = packet[0].nibble(1);
+    let stream_id = packet[0].octet(2);
+    let key = (u8::from(group), stream_id);
+    let status = u8::from(packet[0].nibble(2));
+
+    if status == COMPLETE || status == START {
+        let mut discarded = None;
+        if slots.get(key).is_some() {
+            slots.remove(key);
+            discarded = Some(Interrupted { group, stream_id });
+        }
+        if status == COMPLETE {
+            return PacketEvents::new(discarded, Some(message(packet, group, stream_id)));
+        }
+        if slots.is_full() {
+            match (policy, slots.least_recent()) {
+                (EvictionPolicy::EvictLeastRecent, Some(evicted)) => {
+                    slots.remove(evicted);
+                    discarded = Some(Evicted {
+                        group: u4::new(evicted.0),
+                        stream_id: evicted.1,
+                    });
+                }
+                _ => return PacketEvents::new(None, Some(Rejected { group, stream_id })),
+            }
+        }
+        let slot = slots.insert(key);
+        slot.last_used = clock;
+        return PacketEvents::new(discarded, append(slot, packet, group, stream_id));
+    }
+
+    let Some(slot) = slots.get(key) else {
+        return PacketEvents::new(None, Some(Orphaned { group, stream_id }));
+    };
+    slot.last_used = clock;
+    let event = if slot.overflowed {
+        None
+    } else {
+        append(slot, packet, group, stream_id)
+    };
+    if status != END {
+        return PacketEvents::new(None, event);
+    }
+    let event = match (event, slot.overflowed) {
+        (Some(event), _) => Some(event),
+        (None, true) => None,
+        (None, false) => Some(message(&slot.buffer.buffer()[..slot.len], group, stream_id)),
+    };
+    slots.remove(key);
+    PacketEvents::new(None, event)
+}
+
+// add a packet to the message in progress.
+// only overflow is reported.
+fn append<B: Ump + BufferMut + BufferTryResize>(
+    slot: &mut Slot<B>,
+    packet: &[u32],
+    group: u4,
+    stream_id: u8,
+) -> Option<Sysex8ReassemblerEvent<B>> {
+    if slot.buffer.try_resize(slot.len + PACKET_SIZE).is_err() {
+        slot.overflowed = true;
+        return Some(Sysex8ReassemblerEvent::BufferOverflow { group, stream_id });
+    }
+    slot.buffer.buffer_mut()[slot.len..slot.len + PACKET_SIZE].copy_from_slice(packet);
+    slot.len += PACKET_SIZE;
+    None
+}
+
+fn message<B: Ump + BufferMut + BufferDefault + BufferTryResize>(
+    data: &[u32],
+    group: u4,
+    stream_id: u8,
+) -> Sysex8ReassemblerEvent<B> {
+    let Ok(message) = Sysex8::try_from(data) else {
+        return Sysex8ReassemblerEvent::InvalidPacket;
+    };
+    match message.try_rebuffer_into() {
+        Ok(message) => Sysex8ReassemblerEvent::Message(message),
+        Err(_) => Sysex8ReassemblerEvent::BufferOverflow { group, stream_id },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Sysex;
+    use pretty_assertions::assert_eq;
+
+    type Event = Sysex8ReassemblerEvent<[u32; 8]>;
+
+    const START_1: [u32; 4] = [0x501E_0100, 0x0102_0304, 0x0506_0708, 0x090A_0B0C];
+    const END_1: [u32; 4] = [0x5032_010D, 0x0, 0x0, 0x0];
+    const START_2: [u32; 4] = [0x5013_0220, 0x2100_0000, 0x0, 0x0];
+    const END_2: [u32; 4] = [0x5032_0222, 0x0, 0x0, 0x0];
+    const START_3: [u32; 4] = [0x5013_0330, 0x3100_0000, 0x0, 0x0];
+
+    fn collect<I: Iterator<Item = Event>>(events: I) -> std::vec::Vec<Event> {
+        events.collect()
+    }
+
+    fn complete(packets: &[[u32; 4]]) -> Event {
+        let data: std::vec::Vec<u32> = packets.iter().flatten().cloned().collect();
+        Event::Message(
+            Sysex8::try_from(&data[..])
+                .unwrap()
+                .try_rebuffer_into()
+                .unwrap(),
+        )
+    }
+
+    fn stream(group: u8, stream_id: u8) -> (u4, u8) {
+        (u4::new(group), stream_id)
+    }
+
+    #[test]
+    fn complete_packet() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        let packet = [0x5002_0901, 0x0, 0x0, 0x0];
+        assert_eq!(
+            collect(reassembler.push(&packet)),
+            std::vec![complete(&[packet])],
+        );
+    }
+
+    #[test]
+    fn interleaved_streams() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        assert_eq!(collect(reassembler.push(&START_1)), std::vec![]);
+        assert_eq!(collect(reassembler.push(&START_2)), std::vec![]);
+        assert_eq!(
+            collect(reassembler.push(&END_2)),
+            std::vec![complete(&[START_2, END_2])],
+        );
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![complete(&[START_1, END_1])],
+        );
+    }
+
+    #[test]
+    fn streams_keyed_by_group() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        let mut start_other_group = START_1;
+        start_other_group[0] |= 0x0400_0000;
+        reassembler.push(&START_1).for_each(drop);
+        reassembler.push(&start_other_group).for_each(drop);
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![complete(&[START_1, END_1])],
+        );
+    }
+
+    #[test]
+    fn orphaned() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        let (group, stream_id) = stream(0, 1);
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![Event::Orphaned { group, stream_id }],
+        );
+    }
+
+    #[test]
+    fn interrupted() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        let (group, stream_id) = stream(0, 1);
+        reassembler.push(&START_1).for_each(drop);
+        assert_eq!(
+            collect(reassembler.push(&START_1)),
+            std::vec![Event::Interrupted { group, stream_id }],
+        );
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![complete(&[START_1, END_1])],
+        );
+    }
+
+    #[test]
+    fn evict_least_recent() {
+        let mut reassembler =
+            Sysex8Reassembler::<[u32; 8], 2>::new(EvictionPolicy::EvictLeastRecent);
+        let (group, stream_id) = stream(0, 1);
+        reassembler.push(&START_1).for_each(drop);
+        reassembler.push(&START_2).for_each(drop);
+        assert_eq!(
+            collect(reassembler.push(&START_3)),
+            std::vec![Event::Evicted { group, stream_id }],
+        );
+        assert_eq!(
+            collect(reassembler.push(&END_2)),
+            std::vec![complete(&[START_2, END_2])],
+        );
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![Event::Orphaned { group, stream_id }],
+        );
+    }
+
+    #[test]
+    fn reject_new() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::new(EvictionPolicy::RejectNew);
+        let (group, stream_id) = stream(0, 3);
+        reassembler.push(&START_1).for_each(drop);
+        reassembler.push(&START_2).for_each(drop);
+        assert_eq!(
+            collect(reassembler.push(&START_3)),
+            std::vec![Event::Rejected { group, stream_id }],
+        );
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![complete(&[START_1, END_1])],
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        let (group, stream_id) = stream(0, 1);
+        let mut continue_1 = START_1;
+        continue_1[0] = 0x502E_0100;
+        reassembler.push(&START_1).for_each(drop);
+        reassembler.push(&continue_1).for_each(drop);
+        assert_eq!(
+            collect(reassembler.push(&continue_1)),
+            std::vec![Event::BufferOverflow { group, stream_id }],
+        );
+        assert_eq!(collect(reassembler.push(&END_1)), std::vec![]);
+        // the slot is free again
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![Event::Orphaned { group, stream_id }],
+        );
+    }
+
+    #[test]
+    fn invalid_packets() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        for packet in [
+            &[0x4090_3C00, 0xC000_0000, 0x0, 0x0][..],
+            &[0x5040_0100, 0x0, 0x0, 0x0][..],
+            &[0x5000_0100, 0x0, 0x0, 0x0][..],
+            &[0x501E_0100, 0x0][..],
+        ] {
+            assert_eq!(
+                collect(reassembler.push(packet)),
+                std::vec![Event::InvalidPacket],
+            );
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let mut reassembler = Sysex8Reassembler::<[u32; 8], 2>::default();
+        let (group, stream_id) = stream(0, 1);
+        reassembler.push(&START_1).for_each(drop);
+        reassembler.reset();
+        assert_eq!(
+            collect(reassembler.push(&END_1)),
+            std::vec![Event::Orphaned { group, stream_id }],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn map_reassembler() {
+        let mut reassembler =
+            Sysex8MapReassembler::<std::vec::Vec<u32>>::new(2, EvictionPolicy::RejectNew);
+        let (group, stream_id) = stream(0, 3);
+        reassembler.push(&START_1).for_each(drop);
+        reassembler.push(&START_2).for_each(drop);
+        assert_eq!(reassembler.len(), 2);
+        assert_eq!(
+            reassembler.push(&START_3).collect::<std::vec::Vec<_>>(),
+            std::vec![Sysex8ReassemblerEvent::Rejected { group, stream_id }],
+        );
+        let Some(Sysex8ReassemblerEvent::Message(message)) = reassembler.push(&END_1).next() else {
+            panic!();
+        };
+        assert_eq!(
+            message.payload().collect::<std::vec::Vec<u8>>(),
+            (0x0..=0xD).collect::<std::vec::Vec<u8>>(),
+        );
+        assert_eq!(reassembler.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn map_reassembler_evicts() {
+        let mut reassembler =
+            Sysex8MapReassembler::<std::vec::Vec<u32>>::new(1, EvictionPolicy::EvictLeastRecent);
+        let (group, stream_id) = stream(0, 1);
+        reassembler.push(&START_1).for_each(drop);
+        assert_eq!(
+            reassembler.push(&START_2).collect::<std::vec::Vec<_>>(),
+            std::vec![Sysex8ReassemblerEvent::Evicted { group, stream_id }],
+        );
+        reassembler.reset();
+        assert!(reassembler.is_empty());
+    }
+}
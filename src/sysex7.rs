@@ -40,7 +40,7 @@ const END_BYTE: u8 = 0xF7;
 // ***********************************************************************
 // properties
 
-struct Sysex7BytesBeginByte;
+pub(crate) struct Sysex7BytesBeginByte;
 
 impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for Sysex7BytesBeginByte {
     type Type = ();
@@ -62,9 +62,7 @@ impl<'a, B: crate::buffer::Buffer> crate::detail::property::ReadProperty<'a, B>
             _ => unreachable!(),
         }
     }
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
 }
 
 impl<B: crate::buffer::Buffer + crate::buffer::BufferMut> crate::detail::property::WriteProperty<B>
@@ -78,12 +76,10 @@ impl<B: crate::buffer::Buffer + crate::buffer::BufferMut> crate::detail::propert
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
-struct Sysex7BytesEndByte;
+pub(crate) struct Sysex7BytesEndByte;
 
 impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for Sysex7BytesEndByte {
     type Type = ();
@@ -92,9 +88,7 @@ impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for Sysex7By
 impl<'a, B: crate::buffer::Buffer> crate::detail::property::ReadProperty<'a, B>
     for Sysex7BytesEndByte
 {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
             crate::buffer::UNIT_ID_U8 => buffer
@@ -121,11 +115,9 @@ impl<B: crate::buffer::Buffer + crate::buffer::BufferMut> crate::detail::propert
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
-struct ConsistentStatuses;
+pub(crate) struct ConsistentStatuses;
 
 impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for ConsistentStatuses {
     type Type = ();
@@ -134,9 +126,7 @@ impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for Consiste
 impl<'a, B: crate::buffer::Buffer> crate::detail::property::ReadProperty<'a, B>
     for ConsistentStatuses
 {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID == crate::buffer::UNIT_ID_U32 {
             message_helpers::validate_sysex_group_statuses(
@@ -160,12 +150,10 @@ impl<B: crate::buffer::Buffer + crate::buffer::BufferMut> crate::detail::propert
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
-struct ValidPacketSizes;
+pub(crate) struct ValidPacketSizes;
 
 impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for ValidPacketSizes {
     type Type = ();
@@ -174,9 +162,7 @@ impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for ValidPac
 impl<'a, B: crate::buffer::Buffer> crate::detail::property::ReadProperty<'a, B>
     for ValidPacketSizes
 {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID == crate::buffer::UNIT_ID_U32 {
             if buffer
@@ -201,12 +187,10 @@ impl<B: crate::buffer::Buffer + crate::buffer::BufferMut> crate::detail::propert
     fn validate(_v: &Self::Type) -> crate::result::Result<()> {
         Ok(())
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
-struct GroupProperty;
+pub(crate) struct GroupProperty;
 
 impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for GroupProperty {
     type Type = ux::u4;
@@ -228,7 +212,7 @@ impl<'a, B: crate::buffer::Buffer> crate::detail::property::ReadProperty<'a, B>
                 crate::ux::u4::new(UMP_MESSAGE_TYPE),
             )
         } else {
-            Ok(Default::default())
+            Ok(())
         }
     }
 }
@@ -256,7 +240,8 @@ impl<B: crate::buffer::Buffer + crate::buffer::BufferMut> crate::detail::propert
     }
 }
 
-struct SysexPayloadPlaceholder;
+#[allow(dead_code)]
+pub(crate) struct SysexPayloadPlaceholder;
 
 impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for SysexPayloadPlaceholder {
     type Type = ();
@@ -267,29 +252,34 @@ impl<B: crate::buffer::Buffer> crate::detail::property::Property<B> for SysexPay
 
 impl<B: crate::buffer::Buffer> crate::traits::Size<B> for Sysex7<B> {
     fn size(&self) -> usize {
-        match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
-            crate::buffer::UNIT_ID_U8 => {
-                self.0
-                    .specialise_u8()
-                    .iter()
-                    .position(|b| *b == 0xF7)
-                    .expect("Message is in an invalid state. No end byte.")
-                    + 1
-            }
-            crate::buffer::UNIT_ID_U32 => {
-                self.0
-                    .specialise_u32()
-                    .chunks_exact(2)
-                    .position(|p| {
-                        let status: u8 = p[0].nibble(2).into();
-                        status == 0x0 || status == 0x3
-                    })
-                    .expect("Message is in an invalid state. Couldn't find end packet.")
-                    * 2
-                    + 2
-            }
-            _ => unreachable!(),
+        size(&self.0)
+    }
+}
+
+// the size of the sysex message held in the buffer
+pub(crate) fn size<B: crate::buffer::Buffer>(buffer: &B) -> usize {
+    match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
+        crate::buffer::UNIT_ID_U8 => {
+            buffer
+                .specialise_u8()
+                .iter()
+                .position(|b| *b == 0xF7)
+                .expect("Message is in an invalid state. No end byte.")
+                + 1
+        }
+        crate::buffer::UNIT_ID_U32 => {
+            buffer
+                .specialise_u32()
+                .chunks_exact(2)
+                .position(|p| {
+                    let status: u8 = p[0].nibble(2).into();
+                    status == 0x0 || status == 0x3
+                })
+                .expect("Message is in an invalid state. Couldn't find end packet.")
+                * 2
+                + 2
         }
+        _ => unreachable!(),
     }
 }
 
@@ -421,7 +411,8 @@ fn convert_generated_properties<
 
 impl<B: crate::buffer::Buffer> Sysex<B> for Sysex7<B> {
     type Byte = ux::u7;
-    type PayloadIterator<'a> = PayloadIterator<'a, B::Unit>
+    type PayloadIterator<'a>
+        = PayloadIterator<'a, B::Unit>
     where
         B::Unit: 'a,
         Self: 'a;
@@ -430,29 +421,7 @@ impl<B: crate::buffer::Buffer> Sysex<B> for Sysex7<B> {
     where
         B::Unit: 'a,
     {
-        match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
-            crate::buffer::UNIT_ID_U8 => PayloadIterator {
-                data: &self.0.buffer()[1..self.data().len() - 1],
-                payload_index: 0,
-                packet_index: 0,
-                size_cache: 0,
-            },
-            crate::buffer::UNIT_ID_U32 => {
-                let size_cache = self
-                    .data()
-                    .specialise_u32()
-                    .chunks_exact(2)
-                    .map(PayloadIterator::<B::Unit>::packet_size)
-                    .sum::<usize>();
-                PayloadIterator {
-                    data: self.0.buffer(),
-                    payload_index: 0,
-                    packet_index: 0,
-                    size_cache,
-                }
-            }
-            _ => unreachable!(),
-        }
+        payload(self.data())
     }
 
     fn set_payload<D>(&mut self, data: D)
@@ -475,29 +444,37 @@ impl<B: crate::buffer::Buffer> Sysex<B> for Sysex7<B> {
     }
 }
 
+// an iterator over the payload of the sysex message data
+pub(crate) fn payload<U: crate::buffer::Unit>(data: &[U]) -> PayloadIterator<'_, U> {
+    match <U as crate::buffer::UnitPrivate>::UNIT_ID {
+        crate::buffer::UNIT_ID_U8 => PayloadIterator {
+            data: &data[1..data.len() - 1],
+            payload_index: 0,
+            packet_index: 0,
+            size_cache: 0,
+        },
+        crate::buffer::UNIT_ID_U32 => {
+            let size_cache = <U as crate::buffer::UnitPrivate>::specialise_buffer_u32(data)
+                .chunks_exact(2)
+                .map(PayloadIterator::<U>::packet_size)
+                .sum::<usize>();
+            PayloadIterator {
+                data,
+                payload_index: 0,
+                packet_index: 0,
+                size_cache,
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
 impl<B: crate::buffer::Buffer> SysexInternal<B> for Sysex7<B> {
     fn resize(&mut self, payload_size: usize)
     where
         B: crate::buffer::BufferMut + crate::buffer::BufferResize,
     {
-        match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
-            crate::buffer::UNIT_ID_U8 => {
-                let buffer_sz = payload_size + 2;
-                let old_payload_size = self.payload_size();
-                self.0.resize(buffer_sz);
-                if payload_size > old_payload_size {
-                    // erase old end bit
-                    self.0.specialise_u8_mut()[old_payload_size + 1] = 0;
-                }
-                self.0.specialise_u8_mut()[buffer_sz - 1] = END_BYTE;
-            }
-            crate::buffer::UNIT_ID_U32 => try_resize_ump(self, payload_size, |s, sz| {
-                s.0.resize(sz);
-                Ok(())
-            })
-            .unwrap(),
-            _ => unreachable!(),
-        }
+        resize(&mut self.0, payload_size)
     }
 
     fn try_resize(
@@ -507,76 +484,141 @@ impl<B: crate::buffer::Buffer> SysexInternal<B> for Sysex7<B> {
     where
         B: crate::buffer::BufferMut + crate::buffer::BufferTryResize,
     {
-        match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
-            crate::buffer::UNIT_ID_U8 => {
-                let old_payload_size = self.payload_size();
-                let mut buffer_sz = payload_size + 2;
-                let result = self.0.try_resize(buffer_sz).map_err(|_| {
-                    buffer_sz = self.0.buffer().len();
-                    crate::traits::SysexTryResizeError(buffer_sz.saturating_sub(2))
-                });
-                if buffer_sz > old_payload_size {
-                    // erase old end bit
-                    self.0.specialise_u8_mut()[old_payload_size + 1] = 0;
-                }
-                self.0.specialise_u8_mut()[buffer_sz - 1] = END_BYTE;
-                result
-            }
-            crate::buffer::UNIT_ID_U32 => {
-                try_resize_ump(self, payload_size, |s, sz| s.0.try_resize(sz))
-            }
-            _ => unreachable!(),
-        }
+        try_resize(&mut self.0, payload_size)
     }
 
     fn write_datum(&mut self, datum: Self::Byte, payload_index: usize)
     where
         B: crate::buffer::BufferMut,
     {
-        match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
-            crate::buffer::UNIT_ID_U8 => {
-                self.0.specialise_u8_mut()[payload_index + 1] = datum.into();
+        write_datum(&mut self.0, datum, payload_index)
+    }
+}
+
+// resize the buffer to fit the requested payload size
+pub(crate) fn resize<
+    B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferResize,
+>(
+    buffer: &mut B,
+    payload_size: usize,
+) {
+    match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
+        crate::buffer::UNIT_ID_U8 => {
+            let buffer_sz = payload_size + 2;
+            let old_payload_size = size(buffer) - 2;
+            buffer.resize(buffer_sz);
+            if payload_size > old_payload_size {
+                // erase old end bit
+                buffer.specialise_u8_mut()[old_payload_size + 1] = 0;
             }
-            crate::buffer::UNIT_ID_U32 => {
-                // data is written into the buffer contiguously
-                // meaning only the last packet may have a size < 6
-                let buffer_index = 2 * (payload_index / 6);
-                let byte_index = payload_index % 6;
-                self.0.specialise_u32_mut()[buffer_index + (byte_index + 2) / 4]
-                    .set_septet((byte_index + 2) % 4, datum);
+            buffer.specialise_u8_mut()[buffer_sz - 1] = END_BYTE;
+        }
+        crate::buffer::UNIT_ID_U32 => try_resize_ump(buffer, payload_size, |b, sz| {
+            b.resize(sz);
+            Ok(())
+        })
+        .unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+// fallible version of the above
+pub(crate) fn try_resize<
+    B: crate::buffer::Buffer + crate::buffer::BufferMut + crate::buffer::BufferTryResize,
+>(
+    buffer: &mut B,
+    payload_size: usize,
+) -> core::result::Result<(), crate::traits::SysexTryResizeError> {
+    match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
+        crate::buffer::UNIT_ID_U8 => {
+            let old_payload_size = size(buffer) - 2;
+            let mut buffer_sz = payload_size + 2;
+            let result = buffer.try_resize(buffer_sz).map_err(|_| {
+                buffer_sz = buffer.buffer().len();
+                crate::traits::SysexTryResizeError(buffer_sz.saturating_sub(2))
+            });
+            if buffer_sz > old_payload_size {
+                // erase old end bit
+                buffer.specialise_u8_mut()[old_payload_size + 1] = 0;
             }
-            _ => unreachable!(),
+            buffer.specialise_u8_mut()[buffer_sz - 1] = END_BYTE;
+            result
         }
+        crate::buffer::UNIT_ID_U32 => {
+            try_resize_ump(buffer, payload_size, |b, sz| b.try_resize(sz))
+        }
+        _ => unreachable!(),
     }
+}
 
-    fn payload_size(&self) -> usize {
-        self.payload().len()
+// write byte into the buffer at the provided payload index.
+// NOTE: the caller must ensure the buffer is large enough
+pub(crate) fn write_datum<B: crate::buffer::Buffer + crate::buffer::BufferMut>(
+    buffer: &mut B,
+    datum: u7,
+    payload_index: usize,
+) {
+    match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
+        crate::buffer::UNIT_ID_U8 => {
+            buffer.specialise_u8_mut()[payload_index + 1] = datum.into();
+        }
+        crate::buffer::UNIT_ID_U32 => {
+            // data is written into the buffer contiguously
+            // meaning only the last packet may have a size < 6
+            let buffer_index = 2 * (payload_index / 6);
+            let byte_index = payload_index % 6;
+            buffer.specialise_u32_mut()[buffer_index + (byte_index + 2) / 4]
+                .set_septet((byte_index + 2) % 4, datum);
+        }
+        _ => unreachable!(),
+    }
+}
+
+// read the byte at the provided payload index.
+// NOTE: like write_datum this assumes the data is laid out contiguously
+#[cfg(feature = "ci")]
+pub(crate) fn read_datum<B: crate::buffer::Buffer>(buffer: &B, payload_index: usize) -> u7 {
+    match <B::Unit as crate::buffer::UnitPrivate>::UNIT_ID {
+        crate::buffer::UNIT_ID_U8 => u7::new(buffer.specialise_u8()[payload_index + 1]),
+        crate::buffer::UNIT_ID_U32 => {
+            let buffer_index = 2 * (payload_index / 6);
+            let byte_index = payload_index % 6;
+            buffer.specialise_u32()[buffer_index + (byte_index + 2) / 4]
+                .septet((byte_index + 2) % 4)
+        }
+        _ => unreachable!(),
     }
 }
 
 fn try_resize_ump<
     B: crate::buffer::Buffer + crate::buffer::BufferMut,
-    ResizeBuffer: Fn(&mut Sysex7<B>, usize) -> Result<(), crate::error::BufferOverflow>,
+    ResizeBuffer: Fn(&mut B, usize) -> Result<(), crate::error::BufferOverflow>,
 >(
-    sysex: &mut Sysex7<B>,
+    buffer: &mut B,
     mut payload_size: usize,
     try_resize_buffer: ResizeBuffer,
 ) -> Result<(), crate::traits::SysexTryResizeError> {
-    use ux::u4;
-
     let mut buffer_size = buffer_size_from_payload_size_ump(payload_size);
-    let resize_result = try_resize_buffer(sysex, buffer_size);
-    if let Err(_) = resize_result {
-        buffer_size = sysex.0.buffer().len();
+    let resize_result = try_resize_buffer(buffer, buffer_size);
+    if resize_result.is_err() {
+        buffer_size = buffer.buffer().len();
         payload_size = (buffer_size / 2) * 6;
     }
 
-    let mut iter = sysex
-        .0
-        .specialise_u32_mut()
-        .chunks_exact_mut(2)
-        .take(buffer_size / 2)
-        .peekable();
+    write_ump_packet_headers(
+        &mut buffer.specialise_u32_mut()[..buffer_size],
+        payload_size,
+    );
+
+    resize_result.map_err(|_| crate::traits::SysexTryResizeError(payload_size))
+}
+
+// lay out the packet headers for a message with the given payload size
+// across the provided packets
+pub(crate) fn write_ump_packet_headers(buffer: &mut [u32], payload_size: usize) {
+    use ux::u4;
+
+    let mut iter = buffer.chunks_exact_mut(2).peekable();
     let mut group = None;
 
     const MESSAGE_TYPE: u4 = u4::new(UMP_MESSAGE_TYPE);
@@ -624,11 +666,9 @@ fn try_resize_ump<
             };
         }
     }
-
-    resize_result.map_err(|_| crate::traits::SysexTryResizeError(payload_size))
 }
 
-fn buffer_size_from_payload_size_ump(payload_size: usize) -> usize {
+pub(crate) fn buffer_size_from_payload_size_ump(payload_size: usize) -> usize {
     if payload_size % 6 == 0 {
         if payload_size == 0 {
             2
@@ -749,7 +789,7 @@ impl<'a, U: crate::buffer::Unit> core::iter::Iterator for PayloadIterator<'a, U>
                 };
 
                 let ret = do_nth();
-                if let None = ret {
+                if ret.is_none() {
                     // if we failed it means we ran out of data
                     // so we set the iterator into finished state
                     self.packet_index = self.data.len() / 2;
@@ -769,7 +809,8 @@ impl<'a, U: crate::buffer::Unit> core::iter::Iterator for PayloadIterator<'a, U>
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        (self.size_cache, Some(self.size_cache))
+        let len = self.len();
+        (len, Some(len))
     }
 }
 
@@ -821,6 +862,7 @@ impl<'a, U: crate::buffer::Unit> core::iter::ExactSizeIterator for PayloadIterat
 }
 
 #[cfg(test)]
+#[allow(clippy::iter_nth_zero)]
 mod tests {
     use super::*;
     use crate::{
@@ -1372,6 +1414,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn payload_bytes_size_hint() {
+        assert_eq!(
+            Sysex7::try_from(&[0xF0_u8, 0x00, 0x01, 0x02, 0xF7][..])
+                .unwrap()
+                .payload()
+                .skip(1)
+                .size_hint(),
+            (2, Some(2)),
+        );
+    }
+
     #[test]
     fn payload_ump() {
         assert_eq!(
@@ -9,6 +9,60 @@ pub(crate) const UMP_MESSAGE_TYPE: u8 = 0x5;
 const ERR_INVALID_NUMBER_OF_PAYLOAD_BYTES: &str = "Invalid number of payload bytes in packet";
 const ERR_INCONSISTENT_STREAM_ID: &str = "Inconsistent stream id fields across packets";
 
+mod mixed_data_set_header;
+mod mixed_data_set_payload;
+
+pub use mixed_data_set_header::*;
+pub use mixed_data_set_payload::*;
+
+/// The Mixed Data Set messages which share the sysex8 message type.
+#[derive(
+    derive_more::From,
+    midi2_proc::Data,
+    midi2_proc::Grouped,
+    midi2_proc::RebufferFrom,
+    midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+)]
+#[non_exhaustive]
+pub enum MixedDataSet<B: crate::buffer::Ump> {
+    Header(mixed_data_set_header::MixedDataSetHeader<B>),
+    Payload(mixed_data_set_payload::MixedDataSetPayload<B>),
+}
+
+impl<'a> core::convert::TryFrom<&'a [u32]> for MixedDataSet<&'a [u32]> {
+    type Error = crate::error::Error;
+    fn try_from(buffer: &'a [u32]) -> Result<Self, Self::Error> {
+        if buffer.is_empty() {
+            return Err(crate::error::Error::InvalidData("Slice is too short"));
+        };
+        Ok(match u8::from(buffer[0].nibble(2)) {
+            mixed_data_set_header::STATUS => {
+                mixed_data_set_header::MixedDataSetHeader::try_from(buffer)?.into()
+            }
+            mixed_data_set_payload::STATUS => {
+                mixed_data_set_payload::MixedDataSetPayload::try_from(buffer)?.into()
+            }
+            _ => Err(crate::error::Error::InvalidData(
+                "Unknown mixed data set message status",
+            ))?,
+        })
+    }
+}
+
+/// Whether the packet at the front of the buffer is a Mixed Data Set
+/// message rather than part of a sysex8 message.
+pub(crate) fn is_mixed_data_set(buffer: &[u32]) -> bool {
+    matches!(
+        u8::from(buffer[0].nibble(2)),
+        mixed_data_set_header::STATUS | mixed_data_set_payload::STATUS
+    )
+}
+
 #[midi2_proc::generate_message(MinSizeUmp(4))]
 /// A semantic wrapper type around MIDI 2.0 System Exclusive 8bit data.
 /// See the [module docs](crate::sysex8) for more detailed info
@@ -39,9 +93,7 @@ impl<B: crate::buffer::Ump> crate::detail::property::Property<B> for ConsistentS
 impl<'a, B: crate::buffer::Ump> crate::detail::property::ReadProperty<'a, B>
     for ConsistentStatuses
 {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         message_helpers::validate_sysex_group_statuses(
             buffer.buffer(),
@@ -66,7 +118,7 @@ impl<'a, B: crate::buffer::Ump> crate::detail::property::ReadProperty<'a, B> for
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if buffer.buffer().chunks_exact(4).any(|p| {
             let number_bytes = u8::from(p[0].nibble(3));
-            number_bytes < 1 || number_bytes > 14
+            !(1..=14).contains(&number_bytes)
         }) {
             Err(crate::error::Error::InvalidData(
                 ERR_INVALID_NUMBER_OF_PAYLOAD_BYTES,
@@ -264,7 +316,7 @@ impl<'a> core::iter::Iterator for PayloadIterator<'a> {
         };
 
         let ret = do_nth();
-        if let None = ret {
+        if ret.is_none() {
             // if we failed it means we ran out of data
             // so we set the iterator into finished state
             self.packet_index = self.data.len() / 2;
@@ -321,7 +373,10 @@ impl<'a> PayloadIterator<'a> {
 
 impl<B: crate::buffer::Ump> Sysex<B> for Sysex8<B> {
     type Byte = u8;
-    type PayloadIterator<'a> = PayloadIterator<'a> where B: 'a;
+    type PayloadIterator<'a>
+        = PayloadIterator<'a>
+    where
+        B: 'a;
     fn payload<'a>(&'a self) -> Self::PayloadIterator<'a>
     where
         <B as crate::buffer::Buffer>::Unit: 'a,
@@ -333,7 +388,7 @@ impl<B: crate::buffer::Ump> Sysex<B> for Sysex8<B> {
             size_cache: self
                 .data()
                 .chunks_exact(4)
-                .map(|packet| PayloadIterator::packet_size(packet))
+                .map(PayloadIterator::packet_size)
                 .sum(),
         }
     }
@@ -389,10 +444,6 @@ impl<B: crate::buffer::Ump> SysexInternal<B> for Sysex8<B> {
         self.0.specialise_u32_mut()[buffer_index + (byte_index + 3) / 4]
             .set_octet((byte_index + 3) % 4, datum);
     }
-
-    fn payload_size(&self) -> usize {
-        self.payload().len()
-    }
 }
 
 fn try_resize<
@@ -407,7 +458,7 @@ fn try_resize<
 
     let mut buffer_size = buffer_size_from_payload_size(payload_size);
     let resize_result = try_resize_buffer(sysex, buffer_size);
-    if let Err(_) = resize_result {
+    if resize_result.is_err() {
         // resize failed. We make do with what we've got
         buffer_size = sysex.0.buffer().len();
         payload_size = buffer_size * 13 / 4;
@@ -483,6 +534,7 @@ fn buffer_size_from_payload_size(payload_size: usize) -> usize {
 }
 
 #[cfg(test)]
+#[allow(clippy::iter_nth_zero)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
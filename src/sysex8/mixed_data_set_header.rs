@@ -0,0 +1,139 @@
+use crate::{
+    detail::{common_properties, schema},
+    sysex8,
+    sysex8::UMP_MESSAGE_TYPE,
+    ux::u4,
+};
+
+pub(crate) const STATUS: u8 = 0x8;
+
+/// The header of a chunk of a Mixed Data Set.
+///
+/// Each chunk of the data set begins with a header packet and is followed
+/// by the [payload](crate::sysex8::MixedDataSetPayload) packets carrying
+/// the chunk's data.
+#[midi2_proc::generate_message(Via(sysex8::MixedDataSet), FixedSize, MinSizeUmp(4))]
+struct MixedDataSetHeader {
+    #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
+    ump_type: (),
+    #[property(common_properties::ChannelVoiceStatusProperty<STATUS>)]
+    status: (),
+    #[property(common_properties::GroupProperty)]
+    group: u4,
+    #[property(common_properties::UmpSchemaProperty<u4, schema::Ump<0x000F_0000, 0x0, 0x0, 0x0>>)]
+    mds_id: u4,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0000_FFFF, 0x0, 0x0, 0x0>>)]
+    number_of_valid_bytes: u16,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0, 0xFFFF_0000, 0x0, 0x0>>)]
+    number_of_chunks: u16,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0, 0x0000_FFFF, 0x0, 0x0>>)]
+    chunk_number: u16,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0, 0x0, 0xFFFF_0000, 0x0>>)]
+    manufacturer_id: u16,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0, 0x0, 0x0000_FFFF, 0x0>>)]
+    device_id: u16,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0, 0x0, 0x0, 0xFFFF_0000>>)]
+    sub_id_1: u16,
+    #[property(common_properties::UmpSchemaProperty<u16, schema::Ump<0x0, 0x0, 0x0, 0x0000_FFFF>>)]
+    sub_id_2: u16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{Data, Grouped};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn builder() {
+        let mut message = MixedDataSetHeader::<[u32; 4]>::new();
+        message.set_group(u4::new(0x3));
+        message.set_mds_id(u4::new(0xA));
+        message.set_number_of_valid_bytes(0x0123);
+        message.set_number_of_chunks(0x0004);
+        message.set_chunk_number(0x0002);
+        message.set_manufacturer_id(0x4567);
+        message.set_device_id(0x89AB);
+        message.set_sub_id_1(0xCDEF);
+        message.set_sub_id_2(0x1234);
+        assert_eq!(
+            message,
+            MixedDataSetHeader([0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234]),
+        );
+    }
+
+    #[test]
+    fn group() {
+        assert_eq!(
+            MixedDataSetHeader::try_from(&[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..])
+                .unwrap()
+                .group(),
+            u4::new(0x3),
+        );
+    }
+
+    #[test]
+    fn mds_id() {
+        assert_eq!(
+            MixedDataSetHeader::try_from(&[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..])
+                .unwrap()
+                .mds_id(),
+            u4::new(0xA),
+        );
+    }
+
+    #[test]
+    fn number_of_valid_bytes() {
+        assert_eq!(
+            MixedDataSetHeader::try_from(&[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..])
+                .unwrap()
+                .number_of_valid_bytes(),
+            0x0123,
+        );
+    }
+
+    #[test]
+    fn chunks() {
+        let message =
+            MixedDataSetHeader::try_from(&[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..])
+                .unwrap();
+        assert_eq!(
+            (message.number_of_chunks(), message.chunk_number()),
+            (0x0004, 0x0002),
+        );
+    }
+
+    #[test]
+    fn ids() {
+        let message =
+            MixedDataSetHeader::try_from(&[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..])
+                .unwrap();
+        assert_eq!(
+            (
+                message.manufacturer_id(),
+                message.device_id(),
+                message.sub_id_1(),
+                message.sub_id_2(),
+            ),
+            (0x4567, 0x89AB, 0xCDEF, 0x1234),
+        );
+    }
+
+    #[test]
+    fn data() {
+        assert_eq!(
+            MixedDataSetHeader::try_from(&[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..])
+                .unwrap()
+                .data(),
+            &[0x538A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234],
+        );
+    }
+
+    #[test]
+    fn try_from_payload_status() {
+        assert_eq!(
+            MixedDataSetHeader::try_from(&[0x539A_0123, 0x0004_0002, 0x4567_89AB, 0xCDEF_1234][..]),
+            Err(crate::error::Error::InvalidData("Incorrect message status")),
+        );
+    }
+}
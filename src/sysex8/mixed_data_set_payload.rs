@@ -0,0 +1,100 @@
+use crate::{
+    detail::{common_properties, schema, BitOps},
+    sysex8,
+    sysex8::UMP_MESSAGE_TYPE,
+    ux::u4,
+};
+
+pub(crate) const STATUS: u8 = 0x9;
+
+/// The number of data bytes carried by a single payload packet.
+pub const MIXED_DATA_SET_PAYLOAD_SIZE: usize = 14;
+
+/// A packet of data belonging to a chunk of a Mixed Data Set.
+///
+/// Every payload packet carries 14 bytes. The number of those which are
+/// meaningful is given by the preceding
+/// [header](crate::sysex8::MixedDataSetHeader) of the chunk.
+#[midi2_proc::generate_message(Via(sysex8::MixedDataSet), FixedSize, MinSizeUmp(4))]
+struct MixedDataSetPayload {
+    #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
+    ump_type: (),
+    #[property(common_properties::ChannelVoiceStatusProperty<STATUS>)]
+    status: (),
+    #[property(common_properties::GroupProperty)]
+    group: u4,
+    #[property(common_properties::UmpSchemaProperty<u4, schema::Ump<0x000F_0000, 0x0, 0x0, 0x0>>)]
+    mds_id: u4,
+    #[property(common_properties::UmpSchemaProperty<[u8; MIXED_DATA_SET_PAYLOAD_SIZE], schema::Ump<0x0000_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF>>)]
+    payload: [u8; MIXED_DATA_SET_PAYLOAD_SIZE],
+}
+
+impl schema::UmpSchemaRepr<schema::Ump<0x0000_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF>>
+    for [u8; MIXED_DATA_SET_PAYLOAD_SIZE]
+{
+    fn write(buffer: &mut [u32], value: Self) {
+        for (i, v) in value.into_iter().enumerate() {
+            buffer[(i + 2) / 4].set_octet((i + 2) % 4, v);
+        }
+    }
+    fn read(buffer: &[u32]) -> Self {
+        core::array::from_fn(|i| buffer[(i + 2) / 4].octet((i + 2) % 4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Grouped;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn builder() {
+        let mut message = MixedDataSetPayload::<[u32; 4]>::new();
+        message.set_group(u4::new(0x3));
+        message.set_mds_id(u4::new(0xA));
+        message.set_payload([
+            0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD,
+        ]);
+        assert_eq!(
+            message,
+            MixedDataSetPayload([0x539A_0001, 0x0203_0405, 0x0607_0809, 0x0A0B_0C0D]),
+        );
+    }
+
+    #[test]
+    fn group() {
+        assert_eq!(
+            MixedDataSetPayload::try_from(
+                &[0x539A_0001, 0x0203_0405, 0x0607_0809, 0x0A0B_0C0D][..]
+            )
+            .unwrap()
+            .group(),
+            u4::new(0x3),
+        );
+    }
+
+    #[test]
+    fn mds_id() {
+        assert_eq!(
+            MixedDataSetPayload::try_from(
+                &[0x539A_0001, 0x0203_0405, 0x0607_0809, 0x0A0B_0C0D][..]
+            )
+            .unwrap()
+            .mds_id(),
+            u4::new(0xA),
+        );
+    }
+
+    #[test]
+    fn payload() {
+        assert_eq!(
+            MixedDataSetPayload::try_from(
+                &[0x539A_0001, 0x0203_0405, 0x0607_0809, 0x0A0B_0C0D][..]
+            )
+            .unwrap()
+            .payload(),
+            [0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD],
+        );
+    }
+}
@@ -16,7 +16,7 @@ mod tune_request {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct TuneRequest {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -37,7 +37,7 @@ mod timing_clock {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct TimingClock {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -58,7 +58,7 @@ mod start {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct Start {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -79,7 +79,7 @@ mod cont {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct Continue {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -100,7 +100,7 @@ mod stop {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct Stop {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -121,7 +121,7 @@ mod active_sensing {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct ActiveSensing {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -142,7 +142,7 @@ mod reset {
         Via(system_common::SystemCommon),
         FixedSize,
         MinSizeUmp(1),
-        MinSizeBytes(2)
+        MinSizeBytes(1)
     )]
     struct Reset {
         #[property(common_properties::UmpMessageTypeProperty<UMP_MESSAGE_TYPE>)]
@@ -175,6 +175,7 @@ pub use tune_request::*;
     midi2_proc::TryFromUmp,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -197,11 +198,11 @@ pub enum SystemCommon<B: crate::buffer::Buffer> {
 impl<'a, U: crate::buffer::Unit> core::convert::TryFrom<&'a [U]> for SystemCommon<&'a [U]> {
     type Error = crate::error::Error;
     fn try_from(buffer: &'a [U]) -> Result<Self, Self::Error> {
-        if buffer.len() < 1 {
+        if buffer.is_empty() {
             return Err(crate::error::Error::InvalidData("Slice is too short"));
         };
 
-        Ok(match status(&buffer) {
+        Ok(match status(buffer) {
             active_sensing::STATUS => active_sensing::ActiveSensing::try_from(buffer)?.into(),
             cont::STATUS => cont::Continue::try_from(buffer)?.into(),
             reset::STATUS => reset::Reset::try_from(buffer)?.into(),
@@ -232,9 +233,7 @@ impl<const STATUS: u8, B: crate::buffer::Buffer> crate::detail::property::Proper
 impl<'a, const STATUS: u8, B: crate::buffer::Buffer> crate::detail::property::ReadProperty<'a, B>
     for SystemCommonStatus<STATUS>
 {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if status(buffer.buffer()) != STATUS {
             Err(crate::error::Error::InvalidData("Incorrect status field"))
@@ -302,4 +301,22 @@ mod tests {
             time_code::TimeCode::try_from(&[0x15F1_5F00_u32][..]).map(|m| m.into())
         );
     }
+    #[test]
+    fn byte_data_sizes() {
+        use crate::traits::Data;
+        for data in [
+            &[0xF1_u8, 0x5F][..],
+            &[0xF2, 0x7D, 0x6C][..],
+            &[0xF3, 0x4D][..],
+            &[0xF6][..],
+            &[0xF8][..],
+            &[0xFA][..],
+            &[0xFB][..],
+            &[0xFC][..],
+            &[0xFE][..],
+            &[0xFF][..],
+        ] {
+            assert_eq!(SystemCommon::try_from(data).unwrap().data(), data);
+        }
+    }
 }
@@ -18,6 +18,28 @@ pub trait Channeled<B: Buffer> {
         B: BufferMut;
 }
 
+/// Group access for messages and aggregates for which the group
+/// is optional.
+///
+/// Setting the group of a message without one leaves it unchanged.
+pub trait OptionalGrouped<B: Ump> {
+    fn group(&self) -> Option<crate::ux::u4>;
+    fn set_group(&mut self, group: crate::ux::u4)
+    where
+        B: BufferMut;
+}
+
+/// Channel access for messages and aggregates for which the channel
+/// is optional.
+///
+/// Setting the channel of a message which can't carry one leaves it unchanged.
+pub trait OptionalChanneled<B: Buffer> {
+    fn channel(&self) -> Option<crate::ux::u4>;
+    fn set_channel(&mut self, channel: crate::ux::u4)
+    where
+        B: BufferMut;
+}
+
 pub trait RebufferFrom<T>: Sized {
     fn rebuffer_from(value: T) -> Self;
 }
@@ -147,8 +169,6 @@ pub trait Sysex<B: crate::buffer::Buffer> {
 pub(crate) struct SysexTryResizeError(pub usize);
 
 pub(crate) trait SysexInternal<B: crate::buffer::Buffer>: Sysex<B> {
-    fn payload_size(&self) -> usize;
-
     // resize the underlying buffer to accommodate the requested amount
     // of bytes. The newly allocated data should be assumed to be
     // written to immediately after this call - so it doesn't matter
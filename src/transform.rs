@@ -0,0 +1,514 @@
+//! Transformations applied in place to the messages of a stream.
+//!
+//! A [Transform] modifies a message or decides that it should be dropped.
+//! Transforms chain over any iterator of messages with
+//! [TransformExt::transform], in the same way as the iterator adapters
+//! of the standard library.
+//!
+//! ```rust
+//! use midi2::{
+//!     prelude::*,
+//!     transform::{CategoryFilter, GroupRemap, MessageCategory, TransformExt},
+//! };
+//!
+//! let mut buffer = [
+//!     0x4090_3C00, 0xFFFF_0000, // note on
+//!     0x40B0_0700, 0x8000_0000, // control change
+//! ];
+//! let notes = UmpStreamIterMut::new(&mut buffer)
+//!     .transform(CategoryFilter::deny_all().allow(MessageCategory::Note))
+//!     .transform(GroupRemap::new().map(u4::new(0x0), u4::new(0x3)))
+//!     .count();
+//! assert_eq!(notes, 1);
+//! assert_eq!(buffer[0], 0x4390_3C00);
+//! ```
+
+mod filter;
+mod note;
+mod remap;
+
+pub use filter::*;
+pub use note::*;
+pub use remap::*;
+
+use crate::ux::u4;
+
+/// Modifies messages in place.
+pub trait Transform<M> {
+    /// Returns `false` when the message should be dropped.
+    fn transform(&mut self, message: &mut M) -> bool;
+}
+
+/// Errors read from a stream pass through untouched.
+impl<M, E, T: Transform<M>> Transform<core::result::Result<M, E>> for T {
+    fn transform(&mut self, message: &mut core::result::Result<M, E>) -> bool {
+        match message {
+            Ok(message) => Transform::transform(self, message),
+            Err(_) => true,
+        }
+    }
+}
+
+pub trait TransformExt: Iterator + Sized {
+    /// Applies the transform to each message, skipping over those it drops.
+    fn transform<T: Transform<Self::Item>>(self, transform: T) -> Transformed<Self, T> {
+        Transformed {
+            iter: self,
+            transform,
+        }
+    }
+}
+
+impl<I: Iterator> TransformExt for I {}
+
+/// See [TransformExt::transform].
+#[derive(Clone, Debug)]
+pub struct Transformed<I, T> {
+    iter: I,
+    transform: T,
+}
+
+impl<I: Iterator, T: Transform<I::Item>> core::iter::Iterator for Transformed<I, T> {
+    type Item = I::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut message = self.iter.next()?;
+            if self.transform.transform(&mut message) {
+                return Some(message);
+            }
+        }
+    }
+}
+
+impl<I: core::iter::FusedIterator, T: Transform<I::Item>> core::iter::FusedIterator
+    for Transformed<I, T>
+{
+}
+
+/// The broad kinds of message which a [CategoryFilter] selects between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageCategory {
+    /// Note on and note off messages.
+    Note,
+    /// Channel voice messages other than note on and note off.
+    ChannelVoice,
+    /// System common messages other than the real time ones.
+    SystemCommon,
+    /// Timing clock, start, continue, stop, active sensing and reset.
+    SystemRealTime,
+    /// System exclusive messages and Mixed Data Sets.
+    Sysex,
+    FlexData,
+    UmpStream,
+    Utility,
+    /// Messages which the crate doesn't recognise.
+    Unknown,
+}
+
+mod message {
+    use super::MessageCategory;
+    use crate::ux::{u4, u7};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum NoteEvent {
+        On,
+        Off,
+        // key pressure and the per note messages
+        PerNote,
+    }
+
+    // the access to messages shared by the transforms
+    pub trait Message {
+        fn group(&self) -> Option<u4>;
+        fn set_group(&mut self, group: u4);
+        fn channel(&self) -> Option<u4>;
+        fn set_channel(&mut self, channel: u4);
+        fn category(&self) -> MessageCategory;
+        fn note(&self) -> Option<(NoteEvent, u7)>;
+        fn set_note(&mut self, note: u7);
+        // note on velocity at MIDI 2.0 resolution
+        fn velocity(&self) -> Option<u16>;
+        fn set_velocity(&mut self, velocity: u16);
+    }
+}
+
+use message::Message;
+
+impl<B: crate::buffer::Ump + crate::buffer::BufferMut> Message for crate::UmpMessage<B> {
+    fn group(&self) -> Option<u4> {
+        crate::traits::OptionalGrouped::group(self)
+    }
+    fn set_group(&mut self, group: u4) {
+        crate::traits::OptionalGrouped::set_group(self, group)
+    }
+    fn channel(&self) -> Option<u4> {
+        crate::traits::OptionalChanneled::channel(self)
+    }
+    fn set_channel(&mut self, channel: u4) {
+        crate::traits::OptionalChanneled::set_channel(self, channel)
+    }
+    fn category(&self) -> MessageCategory {
+        use crate::UmpMessage::*;
+        match self {
+            #[cfg(feature = "flex-data")]
+            FlexData(_) => MessageCategory::FlexData,
+            #[cfg(feature = "channel-voice1")]
+            ChannelVoice1(m) => channel_voice1_category(m),
+            #[cfg(feature = "channel-voice2")]
+            ChannelVoice2(m) => {
+                use crate::channel_voice2::ChannelVoice2;
+                match m {
+                    ChannelVoice2::NoteOn(_) | ChannelVoice2::NoteOff(_) => MessageCategory::Note,
+                    _ => MessageCategory::ChannelVoice,
+                }
+            }
+            #[cfg(feature = "sysex7")]
+            Sysex7(_) => MessageCategory::Sysex,
+            #[cfg(feature = "sysex8")]
+            Sysex8(_) | MixedDataSet(_) => MessageCategory::Sysex,
+            #[cfg(feature = "system-common")]
+            SystemCommon(m) => system_common_category(m),
+            #[cfg(feature = "ump-stream")]
+            UmpStream(_) => MessageCategory::UmpStream,
+            #[cfg(feature = "utility")]
+            Utility(_) => MessageCategory::Utility,
+            #[cfg(feature = "unknown")]
+            Unknown(_) => MessageCategory::Unknown,
+        }
+    }
+    fn note(&self) -> Option<(message::NoteEvent, crate::ux::u7)> {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::UmpMessage::ChannelVoice1(m) => channel_voice1_note(m),
+            #[cfg(feature = "channel-voice2")]
+            crate::UmpMessage::ChannelVoice2(m) => channel_voice2_note(m),
+            #[cfg(any(
+                feature = "flex-data",
+                feature = "sysex7",
+                feature = "sysex8",
+                feature = "system-common",
+                feature = "ump-stream",
+                feature = "utility",
+                feature = "unknown"
+            ))]
+            _ => None,
+        }
+    }
+    #[cfg_attr(
+        not(any(feature = "channel-voice1", feature = "channel-voice2")),
+        allow(unused_variables)
+    )]
+    fn set_note(&mut self, note: crate::ux::u7) {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::UmpMessage::ChannelVoice1(m) => set_channel_voice1_note(m, note),
+            #[cfg(feature = "channel-voice2")]
+            crate::UmpMessage::ChannelVoice2(m) => set_channel_voice2_note(m, note),
+            #[cfg(any(
+                feature = "flex-data",
+                feature = "sysex7",
+                feature = "sysex8",
+                feature = "system-common",
+                feature = "ump-stream",
+                feature = "utility",
+                feature = "unknown"
+            ))]
+            _ => {}
+        }
+    }
+    fn velocity(&self) -> Option<u16> {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::UmpMessage::ChannelVoice1(m) => channel_voice1_velocity(m),
+            #[cfg(feature = "channel-voice2")]
+            crate::UmpMessage::ChannelVoice2(m) => channel_voice2_velocity(m),
+            #[cfg(any(
+                feature = "flex-data",
+                feature = "sysex7",
+                feature = "sysex8",
+                feature = "system-common",
+                feature = "ump-stream",
+                feature = "utility",
+                feature = "unknown"
+            ))]
+            _ => None,
+        }
+    }
+    #[cfg_attr(
+        not(any(feature = "channel-voice1", feature = "channel-voice2")),
+        allow(unused_variables)
+    )]
+    fn set_velocity(&mut self, velocity: u16) {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::UmpMessage::ChannelVoice1(m) => set_channel_voice1_velocity(m, velocity),
+            #[cfg(feature = "channel-voice2")]
+            crate::UmpMessage::ChannelVoice2(m) => set_channel_voice2_velocity(m, velocity),
+            #[cfg(any(
+                feature = "flex-data",
+                feature = "sysex7",
+                feature = "sysex8",
+                feature = "system-common",
+                feature = "ump-stream",
+                feature = "utility",
+                feature = "unknown"
+            ))]
+            _ => {}
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "channel-voice1",
+    feature = "sysex7",
+    feature = "system-common"
+))]
+impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> Message for crate::BytesMessage<B> {
+    fn group(&self) -> Option<u4> {
+        None
+    }
+    fn set_group(&mut self, _group: u4) {}
+    fn channel(&self) -> Option<u4> {
+        crate::traits::OptionalChanneled::channel(self)
+    }
+    fn set_channel(&mut self, channel: u4) {
+        crate::traits::OptionalChanneled::set_channel(self, channel)
+    }
+    fn category(&self) -> MessageCategory {
+        use crate::BytesMessage::*;
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            ChannelVoice1(m) => channel_voice1_category(m),
+            #[cfg(feature = "sysex7")]
+            Sysex7(_) => MessageCategory::Sysex,
+            #[cfg(feature = "system-common")]
+            SystemCommon(m) => system_common_category(m),
+        }
+    }
+    fn note(&self) -> Option<(message::NoteEvent, crate::ux::u7)> {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::BytesMessage::ChannelVoice1(m) => channel_voice1_note(m),
+            #[cfg(any(feature = "sysex7", feature = "system-common"))]
+            _ => None,
+        }
+    }
+    #[cfg_attr(not(feature = "channel-voice1"), allow(unused_variables))]
+    fn set_note(&mut self, note: crate::ux::u7) {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::BytesMessage::ChannelVoice1(m) => set_channel_voice1_note(m, note),
+            #[cfg(any(feature = "sysex7", feature = "system-common"))]
+            _ => {}
+        }
+    }
+    fn velocity(&self) -> Option<u16> {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::BytesMessage::ChannelVoice1(m) => channel_voice1_velocity(m),
+            #[cfg(any(feature = "sysex7", feature = "system-common"))]
+            _ => None,
+        }
+    }
+    #[cfg_attr(not(feature = "channel-voice1"), allow(unused_variables))]
+    fn set_velocity(&mut self, velocity: u16) {
+        match self {
+            #[cfg(feature = "channel-voice1")]
+            crate::BytesMessage::ChannelVoice1(m) => set_channel_voice1_velocity(m, velocity),
+            #[cfg(any(feature = "sysex7", feature = "system-common"))]
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "channel-voice1")]
+fn channel_voice1_category<B: crate::buffer::Buffer>(
+    message: &crate::channel_voice1::ChannelVoice1<B>,
+) -> MessageCategory {
+    use crate::channel_voice1::ChannelVoice1;
+    match message {
+        ChannelVoice1::NoteOn(_) | ChannelVoice1::NoteOff(_) => MessageCategory::Note,
+        _ => MessageCategory::ChannelVoice,
+    }
+}
+
+#[cfg(feature = "channel-voice1")]
+fn channel_voice1_note<B: crate::buffer::Buffer>(
+    message: &crate::channel_voice1::ChannelVoice1<B>,
+) -> Option<(message::NoteEvent, crate::ux::u7)> {
+    use crate::channel_voice1::ChannelVoice1;
+    use message::NoteEvent;
+    match message {
+        // a note on with zero velocity is a note off
+        ChannelVoice1::NoteOn(m) if m.velocity() == crate::ux::u7::new(0) => {
+            Some((NoteEvent::Off, m.note()))
+        }
+        ChannelVoice1::NoteOn(m) => Some((NoteEvent::On, m.note())),
+        ChannelVoice1::NoteOff(m) => Some((NoteEvent::Off, m.note())),
+        ChannelVoice1::KeyPressure(m) => Some((NoteEvent::PerNote, m.note())),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "channel-voice1")]
+fn set_channel_voice1_note<B: crate::buffer::Buffer + crate::buffer::BufferMut>(
+    message: &mut crate::channel_voice1::ChannelVoice1<B>,
+    note: crate::ux::u7,
+) {
+    use crate::channel_voice1::ChannelVoice1;
+    match message {
+        ChannelVoice1::NoteOn(m) => m.set_note(note),
+        ChannelVoice1::NoteOff(m) => m.set_note(note),
+        ChannelVoice1::KeyPressure(m) => m.set_note(note),
+        _ => {}
+    }
+}
+
+#[cfg(feature = "channel-voice1")]
+fn channel_voice1_velocity<B: crate::buffer::Buffer>(
+    message: &crate::channel_voice1::ChannelVoice1<B>,
+) -> Option<u16> {
+    match message {
+        crate::channel_voice1::ChannelVoice1::NoteOn(m)
+            if m.velocity() != crate::ux::u7::new(0) =>
+        {
+            Some(crate::scale::u7_to_u16(m.velocity()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "channel-voice1")]
+fn set_channel_voice1_velocity<B: crate::buffer::Buffer + crate::buffer::BufferMut>(
+    message: &mut crate::channel_voice1::ChannelVoice1<B>,
+    velocity: u16,
+) {
+    if let crate::channel_voice1::ChannelVoice1::NoteOn(m) = message {
+        // keep the note on from turning into a note off
+        let velocity = u8::from(crate::scale::u16_to_u7(velocity)).max(1);
+        m.set_velocity(crate::ux::u7::new(velocity));
+    }
+}
+
+#[cfg(feature = "channel-voice2")]
+fn channel_voice2_note<B: crate::buffer::Ump>(
+    message: &crate::channel_voice2::ChannelVoice2<B>,
+) -> Option<(message::NoteEvent, crate::ux::u7)> {
+    use crate::channel_voice2::ChannelVoice2::*;
+    use message::NoteEvent;
+    match message {
+        NoteOn(m) => Some((NoteEvent::On, m.note())),
+        NoteOff(m) => Some((NoteEvent::Off, m.note())),
+        KeyPressure(m) => Some((NoteEvent::PerNote, m.note())),
+        AssignablePerNoteController(m) => Some((NoteEvent::PerNote, m.note())),
+        RegisteredPerNoteController(m) => Some((NoteEvent::PerNote, m.note())),
+        PerNoteManagement(m) => Some((NoteEvent::PerNote, m.note())),
+        PerNotePitchBend(m) => Some((NoteEvent::PerNote, m.note())),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "channel-voice2")]
+fn set_channel_voice2_note<B: crate::buffer::Ump + crate::buffer::BufferMut>(
+    message: &mut crate::channel_voice2::ChannelVoice2<B>,
+    note: crate::ux::u7,
+) {
+    use crate::channel_voice2::ChannelVoice2::*;
+    match message {
+        NoteOn(m) => m.set_note(note),
+        NoteOff(m) => m.set_note(note),
+        KeyPressure(m) => m.set_note(note),
+        AssignablePerNoteController(m) => m.set_note(note),
+        RegisteredPerNoteController(m) => m.set_note(note),
+        PerNoteManagement(m) => m.set_note(note),
+        PerNotePitchBend(m) => m.set_note(note),
+        _ => {}
+    }
+}
+
+#[cfg(feature = "channel-voice2")]
+fn channel_voice2_velocity<B: crate::buffer::Ump>(
+    message: &crate::channel_voice2::ChannelVoice2<B>,
+) -> Option<u16> {
+    match message {
+        crate::channel_voice2::ChannelVoice2::NoteOn(m) => Some(m.velocity()),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "channel-voice2")]
+fn set_channel_voice2_velocity<B: crate::buffer::Ump + crate::buffer::BufferMut>(
+    message: &mut crate::channel_voice2::ChannelVoice2<B>,
+    velocity: u16,
+) {
+    if let crate::channel_voice2::ChannelVoice2::NoteOn(m) = message {
+        m.set_velocity(velocity);
+    }
+}
+
+#[cfg(feature = "system-common")]
+fn system_common_category<B: crate::buffer::Buffer>(
+    message: &crate::system_common::SystemCommon<B>,
+) -> MessageCategory {
+    use crate::system_common::SystemCommon::*;
+    match message {
+        TimingClock(_) | Start(_) | Continue(_) | Stop(_) | ActiveSensing(_) | Reset(_) => {
+            MessageCategory::SystemRealTime
+        }
+        _ => MessageCategory::SystemCommon,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct DropAll;
+
+    impl Transform<u32> for DropAll {
+        fn transform(&mut self, _message: &mut u32) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn errors_pass_through() {
+        let items: [Result<u32, ()>; 2] = [Ok(0x0), Err(())];
+        assert_eq!(
+            items
+                .into_iter()
+                .transform(DropAll)
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![Err(())],
+        );
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn chained_transforms() {
+        let mut buffer = [0x4090_3C00, 0xFFFF_0000, 0x4190_3C00, 0xFFFF_0000];
+        let groups = crate::UmpStreamIterMut::new(&mut buffer)
+            .transform(GroupRemap::new().map(u4::new(0x0), u4::new(0x1)))
+            .transform(GroupChannelFilter::new().allow_group(u4::new(0x1)))
+            .count();
+        assert_eq!(groups, 2);
+        assert_eq!(buffer, [0x4190_3C00, 0xFFFF_0000, 0x4190_3C00, 0xFFFF_0000]);
+    }
+
+    #[cfg(feature = "system-common")]
+    #[test]
+    fn real_time_category() {
+        let mut buffer = [0xF8_u8];
+        let message = crate::BytesMessage::try_from(&mut buffer[..]).unwrap();
+        assert_eq!(message.category(), MessageCategory::SystemRealTime);
+    }
+
+    #[cfg(feature = "system-common")]
+    #[test]
+    fn system_common_category() {
+        let mut buffer = [0xF6_u8];
+        let message = crate::BytesMessage::try_from(&mut buffer[..]).unwrap();
+        assert_eq!(message.category(), MessageCategory::SystemCommon);
+    }
+}
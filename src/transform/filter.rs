@@ -0,0 +1,177 @@
+use crate::{
+    transform::{message::Message, MessageCategory, Transform},
+    ux::u4,
+};
+
+/// Drops messages which aren't on an allowed group and channel.
+///
+/// Until a group is allowed, messages on every group pass,
+/// and likewise for channels. Messages without a group or channel
+/// aren't affected by the respective allowlist.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GroupChannelFilter {
+    groups: Option<u16>,
+    channels: Option<u16>,
+}
+
+impl GroupChannelFilter {
+    /// A filter which lets every message pass.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow_group(mut self, group: u4) -> Self {
+        self.groups = Some(self.groups.unwrap_or(0) | mask(group));
+        self
+    }
+
+    pub fn allow_channel(mut self, channel: u4) -> Self {
+        self.channels = Some(self.channels.unwrap_or(0) | mask(channel));
+        self
+    }
+
+    pub fn is_group_allowed(&self, group: u4) -> bool {
+        self.groups.map_or(true, |groups| groups & mask(group) != 0)
+    }
+
+    pub fn is_channel_allowed(&self, channel: u4) -> bool {
+        self.channels
+            .map_or(true, |channels| channels & mask(channel) != 0)
+    }
+}
+
+fn mask(v: u4) -> u16 {
+    1 << u8::from(v)
+}
+
+impl<M: Message> Transform<M> for GroupChannelFilter {
+    fn transform(&mut self, message: &mut M) -> bool {
+        message.group().map_or(true, |g| self.is_group_allowed(g))
+            && message
+                .channel()
+                .map_or(true, |c| self.is_channel_allowed(c))
+    }
+}
+
+/// Drops messages by their [MessageCategory].
+///
+/// ```rust
+/// use midi2::transform::{CategoryFilter, MessageCategory};
+///
+/// let no_real_time = CategoryFilter::allow_all().deny(MessageCategory::SystemRealTime);
+/// let only_notes = CategoryFilter::deny_all().allow(MessageCategory::Note);
+///
+/// assert!(!no_real_time.is_allowed(MessageCategory::SystemRealTime));
+/// assert!(!only_notes.is_allowed(MessageCategory::Sysex));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CategoryFilter {
+    allowed: u16,
+}
+
+impl CategoryFilter {
+    pub fn allow_all() -> Self {
+        CategoryFilter { allowed: u16::MAX }
+    }
+
+    pub fn deny_all() -> Self {
+        CategoryFilter { allowed: 0 }
+    }
+
+    pub fn allow(mut self, category: MessageCategory) -> Self {
+        self.allowed |= 1 << category as u16;
+        self
+    }
+
+    pub fn deny(mut self, category: MessageCategory) -> Self {
+        self.allowed &= !(1 << category as u16);
+        self
+    }
+
+    pub fn is_allowed(&self, category: MessageCategory) -> bool {
+        self.allowed & (1 << category as u16) != 0
+    }
+}
+
+impl<M: Message> Transform<M> for CategoryFilter {
+    fn transform(&mut self, message: &mut M) -> bool {
+        self.is_allowed(message.category())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn empty_allowlists() {
+        let filter = GroupChannelFilter::new();
+        assert!(filter.is_group_allowed(u4::new(0x3)));
+        assert!(filter.is_channel_allowed(u4::new(0xF)));
+    }
+
+    #[test]
+    fn allowlists() {
+        let filter = GroupChannelFilter::new()
+            .allow_group(u4::new(0x1))
+            .allow_channel(u4::new(0x9));
+        assert_eq!(
+            (
+                filter.is_group_allowed(u4::new(0x1)),
+                filter.is_group_allowed(u4::new(0x2)),
+                filter.is_channel_allowed(u4::new(0x9)),
+                filter.is_channel_allowed(u4::new(0x0)),
+            ),
+            (true, false, true, false),
+        );
+    }
+
+    #[test]
+    fn categories() {
+        let filter = CategoryFilter::allow_all()
+            .deny(MessageCategory::Sysex)
+            .deny(MessageCategory::Utility);
+        assert!(filter.is_allowed(MessageCategory::Note));
+        assert!(!filter.is_allowed(MessageCategory::Sysex));
+        assert!(!filter.is_allowed(MessageCategory::Utility));
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn drop_channel() {
+        let mut buffer = [0x4293_3C00, 0xFFFF_0000];
+        let mut message = crate::UmpMessage::try_from(&mut buffer[..]).unwrap();
+        let mut filter = GroupChannelFilter::new().allow_channel(u4::new(0x0));
+        assert!(!filter.transform(&mut message));
+    }
+
+    #[cfg(feature = "utility")]
+    #[test]
+    fn ungrouped_message_passes_group_filter() {
+        let mut buffer = [0x0020_1234];
+        let mut message = crate::UmpMessage::try_from(&mut buffer[..]).unwrap();
+        let mut filter = GroupChannelFilter::new().allow_group(u4::new(0x5));
+        assert!(filter.transform(&mut message));
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn only_notes() {
+        let mut filter = CategoryFilter::deny_all().allow(MessageCategory::Note);
+        let mut note_on = [0x93, 0x3C, 0x7F];
+        let mut control_change = [0xB3, 0x07, 0x7F];
+        assert!(filter.transform(&mut crate::BytesMessage::try_from(&mut note_on[..]).unwrap()));
+        assert!(
+            !filter.transform(&mut crate::BytesMessage::try_from(&mut control_change[..]).unwrap())
+        );
+    }
+
+    #[cfg(feature = "sysex7")]
+    #[test]
+    fn drop_sysex() {
+        let mut filter = CategoryFilter::allow_all().deny(MessageCategory::Sysex);
+        let mut buffer = [0xF0, 0x01, 0xF7];
+        assert!(!filter.transform(&mut crate::BytesMessage::try_from(&mut buffer[..]).unwrap()));
+    }
+}
@@ -0,0 +1,528 @@
+use crate::{
+    transform::{message::Message, Transform},
+    ux::{u4, u7},
+};
+
+#[cfg(feature = "std")]
+use crate::transform::message::NoteEvent;
+
+/// What [Transpose] does with notes moved outside of the note range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutOfRange {
+    /// The messages of the note are dropped.
+    Drop,
+    /// The note is moved onto the lowest or highest note.
+    Clamp,
+    /// The note is shifted by octaves back into range.
+    Wrap,
+}
+
+/// Transposes notes by a number of semitones.
+///
+/// Applies to note on, note off, key pressure and the per note messages.
+/// Held notes are remembered with the note they were transposed onto,
+/// so that their note offs and per note messages follow them even if
+/// the transposition changes while they sound. A key which is struck
+/// again while it is held releases its notes in the order they started.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     transform::{OutOfRange, Transform, Transpose},
+/// };
+///
+/// let mut transpose = Transpose::new(12, OutOfRange::Drop);
+///
+/// let mut note_on = [0x4090_3C00, 0xFFFF_0000];
+/// assert!(transpose.transform(&mut UmpMessage::try_from(&mut note_on[..]).unwrap()));
+/// assert_eq!(note_on[0], 0x4090_4800);
+///
+/// transpose.set_semitones(-12);
+///
+/// let mut note_off = [0x4080_3C00, 0x0000_0000];
+/// assert!(transpose.transform(&mut UmpMessage::try_from(&mut note_off[..]).unwrap()));
+/// assert_eq!(note_off[0], 0x4080_4800);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transpose {
+    semitones: i8,
+    out_of_range: OutOfRange,
+    // held notes keyed by group, channel and original note,
+    // oldest first when a key is struck again while it is held
+    held: std::collections::BTreeMap<(u8, u8, u8), std::collections::VecDeque<Option<u7>>>,
+}
+
+#[cfg(feature = "std")]
+impl Transpose {
+    pub fn new(semitones: i8, out_of_range: OutOfRange) -> Self {
+        Transpose {
+            semitones,
+            out_of_range,
+            held: Default::default(),
+        }
+    }
+
+    pub fn semitones(&self) -> i8 {
+        self.semitones
+    }
+
+    /// Applies to notes starting from now.
+    /// Notes which are already held keep their transposition.
+    pub fn set_semitones(&mut self, semitones: i8) {
+        self.semitones = semitones;
+    }
+
+    /// Forgets the held notes, for example after an all notes off.
+    pub fn reset(&mut self) {
+        self.held.clear();
+    }
+
+    /// The note which `note` is transposed onto, or `None` when it is dropped.
+    pub fn transpose(&self, note: u7) -> Option<u7> {
+        let mut note = i16::from(u8::from(note)) + i16::from(self.semitones);
+        match self.out_of_range {
+            OutOfRange::Drop if !(0..=0x7F).contains(&note) => return None,
+            OutOfRange::Drop => {}
+            OutOfRange::Clamp => note = note.clamp(0, 0x7F),
+            OutOfRange::Wrap => {
+                while note > 0x7F {
+                    note -= 12;
+                }
+                while note < 0 {
+                    note += 12;
+                }
+            }
+        }
+        Some(u7::new(note as u8))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<M: Message> Transform<M> for Transpose {
+    fn transform(&mut self, message: &mut M) -> bool {
+        let Some((event, note)) = message.note() else {
+            return true;
+        };
+        let key = (
+            message.group().map_or(0, u8::from),
+            message.channel().map_or(0, u8::from),
+            u8::from(note),
+        );
+        let transposed = match event {
+            NoteEvent::On => {
+                let transposed = self.transpose(note);
+                self.held.entry(key).or_default().push_back(transposed);
+                transposed
+            }
+            NoteEvent::Off => match self.held.get_mut(&key) {
+                Some(notes) => {
+                    let released = notes.pop_front().flatten();
+                    if notes.is_empty() {
+                        self.held.remove(&key);
+                    }
+                    released
+                }
+                None => self.transpose(note),
+            },
+            NoteEvent::PerNote => match self.held.get(&key).and_then(|notes| notes.back()) {
+                Some(held) => *held,
+                None => self.transpose(note),
+            },
+        };
+        match transposed {
+            Some(transposed) => {
+                if transposed != note {
+                    message.set_note(transposed);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Reshapes note on velocities with a function.
+///
+/// The function maps velocities at MIDI 2.0 resolution.
+/// MIDI 1.0 velocities are scaled up before the function is applied
+/// and back down afterwards. MIDI 1.0 note ons are kept at a velocity of
+/// at least one so that they don't become note offs.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     transform::{Transform, VelocityCurve},
+/// };
+///
+/// let mut halve = VelocityCurve::new(|velocity| velocity / 2);
+///
+/// let mut buffer = [0x4090_3C00, 0xFFFF_0000];
+/// assert!(halve.transform(&mut UmpMessage::try_from(&mut buffer[..]).unwrap()));
+/// assert_eq!(buffer[1], 0x7FFF_0000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VelocityCurve<F> {
+    curve: F,
+}
+
+impl<F: FnMut(u16) -> u16> VelocityCurve<F> {
+    pub fn new(curve: F) -> Self {
+        VelocityCurve { curve }
+    }
+}
+
+impl<M: Message, F: FnMut(u16) -> u16> Transform<M> for VelocityCurve<F> {
+    fn transform(&mut self, message: &mut M) -> bool {
+        if let Some(velocity) = message.velocity() {
+            message.set_velocity((self.curve)(velocity));
+        }
+        true
+    }
+}
+
+/// Reshapes note on velocities with a lookup table.
+///
+/// Velocities are looked up at MIDI 1.0 resolution. MIDI 2.0 velocities
+/// which the table leaves unchanged keep their full resolution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VelocityTable {
+    table: [u7; 128],
+}
+
+impl Default for VelocityTable {
+    fn default() -> Self {
+        Self::new(core::array::from_fn(|i| u7::new(i as u8)))
+    }
+}
+
+impl VelocityTable {
+    /// The table is indexed by the incoming velocity.
+    pub fn new(table: [u7; 128]) -> Self {
+        VelocityTable { table }
+    }
+
+    pub fn get(&self, velocity: u7) -> u7 {
+        self.table[usize::from(u8::from(velocity))]
+    }
+}
+
+impl<M: Message> Transform<M> for VelocityTable {
+    fn transform(&mut self, message: &mut M) -> bool {
+        if let Some(velocity) = message.velocity() {
+            let velocity = crate::scale::u16_to_u7(velocity);
+            let mapped = self.get(velocity);
+            if mapped != velocity {
+                message.set_velocity(crate::scale::u7_to_u16(mapped));
+            }
+        }
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Zone {
+    group: Option<u4>,
+    channel: Option<u4>,
+}
+
+/// Splits the keyboard into ranges of notes played on
+/// different channels or groups.
+///
+/// Notes outside of every range are left where they are.
+/// Later ranges take precedence where they overlap earlier ones.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     transform::{KeySplit, Transform},
+/// };
+///
+/// // the bass below middle C plays on channel 2
+/// let mut split = KeySplit::new().channel(u7::new(0x0), u7::new(0x3B), u4::new(0x2));
+///
+/// let mut bass = [0x4090_3000, 0xFFFF_0000];
+/// assert!(split.transform(&mut UmpMessage::try_from(&mut bass[..]).unwrap()));
+/// assert_eq!(bass[0], 0x4092_3000);
+///
+/// let mut lead = [0x4090_3C00, 0xFFFF_0000];
+/// assert!(split.transform(&mut UmpMessage::try_from(&mut lead[..]).unwrap()));
+/// assert_eq!(lead[0], 0x4090_3C00);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeySplit {
+    zones: [Zone; 128],
+}
+
+impl Default for KeySplit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KeySplit {
+    /// A split which leaves every note where it is.
+    pub fn new() -> Self {
+        KeySplit {
+            zones: [Zone::default(); 128],
+        }
+    }
+
+    /// Notes from `lowest` to `highest` inclusive are moved onto `channel`.
+    pub fn channel(mut self, lowest: u7, highest: u7, channel: u4) -> Self {
+        for zone in self.range(lowest, highest) {
+            zone.channel = Some(channel);
+        }
+        self
+    }
+
+    /// Notes from `lowest` to `highest` inclusive are moved onto `group`.
+    ///
+    /// Has no effect on messages without a group.
+    pub fn group(mut self, lowest: u7, highest: u7, group: u4) -> Self {
+        for zone in self.range(lowest, highest) {
+            zone.group = Some(group);
+        }
+        self
+    }
+
+    fn range(&mut self, lowest: u7, highest: u7) -> impl Iterator<Item = &mut Zone> {
+        let lowest = usize::from(u8::from(lowest));
+        let highest = usize::from(u8::from(highest));
+        self.zones.iter_mut().take(highest + 1).skip(lowest)
+    }
+}
+
+impl<M: Message> Transform<M> for KeySplit {
+    fn transform(&mut self, message: &mut M) -> bool {
+        if let Some((_, note)) = message.note() {
+            let zone = self.zones[usize::from(u8::from(note))];
+            if let Some(group) = zone.group {
+                message.set_group(group);
+            }
+            if let Some(channel) = zone.channel {
+                message.set_channel(channel);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "std",
+        feature = "channel-voice1",
+        feature = "channel-voice2"
+    )
+))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[cfg(feature = "channel-voice2")]
+    fn apply<'a, T: Transform<crate::UmpMessage<&'a mut [u32]>>>(
+        transform: &mut T,
+        buffer: &'a mut [u32],
+    ) -> bool {
+        let mut message = crate::UmpMessage::try_from(buffer).unwrap();
+        transform.transform(&mut message)
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn out_of_range() {
+        let note = u7::new(0x7A);
+        assert_eq!(Transpose::new(12, OutOfRange::Drop).transpose(note), None);
+        assert_eq!(
+            Transpose::new(12, OutOfRange::Clamp).transpose(note),
+            Some(u7::new(0x7F))
+        );
+        assert_eq!(
+            Transpose::new(12, OutOfRange::Wrap).transpose(note),
+            Some(u7::new(0x7A))
+        );
+        assert_eq!(
+            Transpose::new(-12, OutOfRange::Wrap).transpose(u7::new(0x5)),
+            Some(u7::new(0x5))
+        );
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn transpose_note_on() {
+        let mut transpose = Transpose::new(-2, OutOfRange::Drop);
+        let mut buffer = [0x4593_3C00, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut buffer));
+        assert_eq!(buffer, [0x4593_3A00, 0xFFFF_0000]);
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn note_off_follows_note_on() {
+        let mut transpose = Transpose::new(5, OutOfRange::Drop);
+        let mut note_on = [0x4090_3C00, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut note_on));
+        transpose.set_semitones(7);
+        let mut note_off = [0x4080_3C00, 0x0000_0000];
+        assert!(apply(&mut transpose, &mut note_off));
+        assert_eq!(note_off[0], 0x4080_4100);
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn per_note_messages_follow_note_on() {
+        let mut transpose = Transpose::new(1, OutOfRange::Drop);
+        let mut note_on = [0x4090_3C00, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut note_on));
+        transpose.set_semitones(0);
+        let mut pitch_bend = [0x4060_3C00, 0x8000_0000];
+        assert!(apply(&mut transpose, &mut pitch_bend));
+        assert_eq!(pitch_bend[0], 0x4060_3D00);
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn retriggered_note() {
+        let mut transpose = Transpose::new(12, OutOfRange::Drop);
+        let mut first = [0x4090_3C00, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut first));
+        transpose.set_semitones(0);
+        let mut second = [0x4090_3C00, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut second));
+        assert_eq!((first[0], second[0]), (0x4090_4800, 0x4090_3C00));
+        let mut first_off = [0x4080_3C00, 0x0000_0000];
+        let mut second_off = [0x4080_3C00, 0x0000_0000];
+        assert!(apply(&mut transpose, &mut first_off));
+        assert!(apply(&mut transpose, &mut second_off));
+        assert_eq!((first_off[0], second_off[0]), (0x4080_4800, 0x4080_3C00));
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn retriggered_dropped_note() {
+        let mut transpose = Transpose::new(-12, OutOfRange::Drop);
+        let mut first = [0x4090_0500, 0xFFFF_0000];
+        assert!(!apply(&mut transpose, &mut first));
+        transpose.set_semitones(0);
+        let mut second = [0x4090_0500, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut second));
+        let mut first_off = [0x4080_0500, 0x0000_0000];
+        let mut second_off = [0x4080_0500, 0x0000_0000];
+        assert!(!apply(&mut transpose, &mut first_off));
+        assert!(apply(&mut transpose, &mut second_off));
+        assert_eq!(second_off[0], 0x4080_0500);
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn notes_are_held_per_channel() {
+        let mut transpose = Transpose::new(1, OutOfRange::Drop);
+        let mut note_on = [0x4090_3C00, 0xFFFF_0000];
+        assert!(apply(&mut transpose, &mut note_on));
+        transpose.set_semitones(2);
+        let mut note_off = [0x4081_3C00, 0x0000_0000];
+        assert!(apply(&mut transpose, &mut note_off));
+        assert_eq!(note_off[0], 0x4081_3E00);
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice2"))]
+    #[test]
+    fn dropped_note_drops_note_off() {
+        let mut transpose = Transpose::new(-12, OutOfRange::Drop);
+        let mut note_on = [0x4090_0500, 0xFFFF_0000];
+        assert!(!apply(&mut transpose, &mut note_on));
+        transpose.set_semitones(0);
+        let mut note_off = [0x4080_0500, 0x0000_0000];
+        assert!(!apply(&mut transpose, &mut note_off));
+    }
+
+    #[cfg(all(feature = "std", feature = "channel-voice1"))]
+    #[test]
+    fn zero_velocity_note_on_releases_note() {
+        let mut transpose = Transpose::new(3, OutOfRange::Drop);
+        let mut note_on = [0x90, 0x3C, 0x7F];
+        assert!(transpose.transform(&mut crate::BytesMessage::try_from(&mut note_on[..]).unwrap()));
+        transpose.set_semitones(0);
+        let mut release = [0x90, 0x3C, 0x00];
+        assert!(transpose.transform(&mut crate::BytesMessage::try_from(&mut release[..]).unwrap()));
+        assert_eq!(release, [0x90, 0x3F, 0x00]);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn velocity_curve() {
+        let mut curve = VelocityCurve::new(|v: u16| v.saturating_add(0x1000));
+        let mut buffer = [0x4090_3C00, 0x6000_0000];
+        assert!(apply(&mut curve, &mut buffer));
+        assert_eq!(buffer, [0x4090_3C00, 0x7000_0000]);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn velocity_curve_ignores_note_off() {
+        let mut curve = VelocityCurve::new(|_| 0x0);
+        let mut buffer = [0x4080_3C00, 0x6000_0000];
+        assert!(apply(&mut curve, &mut buffer));
+        assert_eq!(buffer, [0x4080_3C00, 0x6000_0000]);
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn velocity_curve_keeps_note_on() {
+        let mut curve = VelocityCurve::new(|_| 0x0);
+        let mut buffer = [0x90, 0x3C, 0x40];
+        assert!(curve.transform(&mut crate::BytesMessage::try_from(&mut buffer[..]).unwrap()));
+        assert_eq!(buffer, [0x90, 0x3C, 0x01]);
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn velocity_table() {
+        let mut table = VelocityTable::new(core::array::from_fn(|i| u7::new(0x7F - i as u8)));
+        let mut buffer = [0x90, 0x3C, 0x10];
+        assert!(table.transform(&mut crate::BytesMessage::try_from(&mut buffer[..]).unwrap()));
+        assert_eq!(buffer, [0x90, 0x3C, 0x6F]);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn velocity_table_keeps_resolution() {
+        let mut table = VelocityTable::default();
+        let mut buffer = [0x4090_3C00, 0x6123_0000];
+        assert!(apply(&mut table, &mut buffer));
+        assert_eq!(buffer, [0x4090_3C00, 0x6123_0000]);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn split_to_groups() {
+        let mut split = KeySplit::new()
+            .group(u7::new(0x0), u7::new(0x3B), u4::new(0x1))
+            .group(u7::new(0x3C), u7::new(0x7F), u4::new(0x2));
+        let mut low = [0x4090_3B00, 0xFFFF_0000];
+        let mut high = [0x40A0_3C00, 0xFFFF_FFFF];
+        assert!(apply(&mut split, &mut low));
+        assert!(apply(&mut split, &mut high));
+        assert_eq!(low[0], 0x4190_3B00);
+        assert_eq!(high[0], 0x42A0_3C00);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn split_ignores_other_messages() {
+        let mut split = KeySplit::new().channel(u7::new(0x0), u7::new(0x7F), u4::new(0x9));
+        let mut control_change = [0x40B0_0700, 0x8000_0000];
+        assert!(apply(&mut split, &mut control_change));
+        assert_eq!(control_change[0], 0x40B0_0700);
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn split_bytes_to_channels() {
+        let mut split = KeySplit::new().channel(u7::new(0x40), u7::new(0x4F), u4::new(0x4));
+        let mut buffer = [0x80, 0x45, 0x00];
+        assert!(split.transform(&mut crate::BytesMessage::try_from(&mut buffer[..]).unwrap()));
+        assert_eq!(buffer, [0x84, 0x45, 0x00]);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{
+    transform::{message::Message, Transform},
+    ux::u4,
+};
+
+/// Moves messages from one group onto another.
+///
+/// Messages without a group are left unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GroupRemap {
+    table: [u4; 16],
+}
+
+impl Default for GroupRemap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GroupRemap {
+    /// A remap which leaves every group unchanged.
+    pub fn new() -> Self {
+        GroupRemap {
+            table: core::array::from_fn(|i| u4::new(i as u8)),
+        }
+    }
+
+    /// Messages on the `from` group are moved onto the `to` group.
+    pub fn map(mut self, from: u4, to: u4) -> Self {
+        self.table[usize::from(u8::from(from))] = to;
+        self
+    }
+
+    pub fn get(&self, group: u4) -> u4 {
+        self.table[usize::from(u8::from(group))]
+    }
+}
+
+impl<M: Message> Transform<M> for GroupRemap {
+    fn transform(&mut self, message: &mut M) -> bool {
+        if let Some(group) = message.group() {
+            let mapped = self.get(group);
+            if mapped != group {
+                message.set_group(mapped);
+            }
+        }
+        true
+    }
+}
+
+/// Moves messages from one channel onto another.
+///
+/// Messages without a channel are left unchanged.
+/// The same table applies to every group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChannelRemap {
+    table: [u4; 16],
+}
+
+impl Default for ChannelRemap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelRemap {
+    /// A remap which leaves every channel unchanged.
+    pub fn new() -> Self {
+        ChannelRemap {
+            table: core::array::from_fn(|i| u4::new(i as u8)),
+        }
+    }
+
+    /// Messages on the `from` channel are moved onto the `to` channel.
+    pub fn map(mut self, from: u4, to: u4) -> Self {
+        self.table[usize::from(u8::from(from))] = to;
+        self
+    }
+
+    pub fn get(&self, channel: u4) -> u4 {
+        self.table[usize::from(u8::from(channel))]
+    }
+}
+
+impl<M: Message> Transform<M> for ChannelRemap {
+    fn transform(&mut self, message: &mut M) -> bool {
+        if let Some(channel) = message.channel() {
+            let mapped = self.get(channel);
+            if mapped != channel {
+                message.set_channel(mapped);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn identity() {
+        assert_eq!(GroupRemap::new().get(u4::new(0x7)), u4::new(0x7));
+        assert_eq!(ChannelRemap::new().get(u4::new(0x7)), u4::new(0x7));
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn remap_group() {
+        let mut buffer = [0x4290_3C00, 0xFFFF_0000];
+        let mut message = crate::UmpMessage::try_from(&mut buffer[..]).unwrap();
+        let mut remap = GroupRemap::new().map(u4::new(0x2), u4::new(0xA));
+        assert!(remap.transform(&mut message));
+        assert_eq!(buffer, [0x4A90_3C00, 0xFFFF_0000]);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn remap_channel() {
+        let mut buffer = [0x4293_3C00, 0xFFFF_0000];
+        let mut message = crate::UmpMessage::try_from(&mut buffer[..]).unwrap();
+        let mut remap = ChannelRemap::new().map(u4::new(0x3), u4::new(0x9));
+        assert!(remap.transform(&mut message));
+        assert_eq!(buffer, [0x4299_3C00, 0xFFFF_0000]);
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn remap_bytes_channel() {
+        let mut buffer = [0x93, 0x3C, 0x7F];
+        let mut message = crate::BytesMessage::try_from(&mut buffer[..]).unwrap();
+        let mut remap = ChannelRemap::new().map(u4::new(0x3), u4::new(0x0));
+        assert!(remap.transform(&mut message));
+        assert_eq!(buffer, [0x90, 0x3C, 0x7F]);
+    }
+
+    #[cfg(feature = "utility")]
+    #[test]
+    fn ungrouped_message_is_unchanged() {
+        let mut buffer = [0x0020_1234];
+        let mut message = crate::UmpMessage::try_from(&mut buffer[..]).unwrap();
+        let mut remap = GroupRemap::new().map(u4::new(0x0), u4::new(0x5));
+        assert!(remap.transform(&mut message));
+        assert_eq!(buffer, [0x0020_1234]);
+    }
+}
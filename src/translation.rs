@@ -0,0 +1,22 @@
+//! Translation between MIDI 1.0 and MIDI 2.0 channel voice messages,
+//! following the default translation described in the
+//! MIDI 2.0 specification.
+
+mod midi1_to_midi2;
+mod midi2_to_midi1;
+
+pub use midi1_to_midi2::*;
+pub use midi2_to_midi1::*;
+
+const BANK_SELECT_MSB: u8 = 0;
+const BANK_SELECT_LSB: u8 = 32;
+const DATA_ENTRY_MSB: u8 = 6;
+const DATA_ENTRY_LSB: u8 = 38;
+const DATA_INCREMENT: u8 = 96;
+const DATA_DECREMENT: u8 = 97;
+const NRPN_LSB: u8 = 98;
+const NRPN_MSB: u8 = 99;
+const RPN_LSB: u8 = 100;
+const RPN_MSB: u8 = 101;
+
+const NULL_PARAMETER: u8 = 0x7F;
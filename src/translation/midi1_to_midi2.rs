@@ -0,0 +1,444 @@
+use super::{
+    BANK_SELECT_LSB, BANK_SELECT_MSB, DATA_ENTRY_LSB, DATA_ENTRY_MSB, NRPN_LSB, NRPN_MSB,
+    NULL_PARAMETER, RPN_LSB, RPN_MSB,
+};
+use crate::{
+    buffer::{Buffer, UnitPrivate, UNIT_ID_U32},
+    channel_voice1::ChannelVoice1,
+    channel_voice2::{self, ChannelVoice2},
+    detail::{BitOps, Encode7Bit},
+    scale,
+    traits::{Channeled, Data, Grouped},
+    ux::{u14, u4, u7},
+};
+
+const NOTE_OFF_DEFAULT_VELOCITY: u8 = 0x40;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum ParameterKind {
+    #[default]
+    None,
+    Registered,
+    Assignable,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ChannelState {
+    bank_msb: Option<u7>,
+    bank_lsb: Option<u7>,
+    parameter_kind: ParameterKind,
+    parameter_msb: u7,
+    parameter_lsb: u7,
+    data_msb: u7,
+}
+
+/// Translates MIDI 1.0 channel voice messages into
+/// their MIDI 2.0 equivalents.
+///
+/// Values are upscaled with the min-center-max scheme from the
+/// MIDI 2.0 specification, so that the minimum, center and maximum
+/// of the MIDI 1.0 range map onto the same points of the wider range.
+///
+/// Some MIDI 1.0 messages only have meaning as part of a sequence.
+/// The translator keeps track of these for each group and channel.
+///  * Bank select controllers are held back and the bank is applied to
+///    the following program change.
+///  * RPN and NRPN parameter number controllers are held back. Data entry
+///    for the selected parameter is translated into a
+///    [RegisteredController](channel_voice2::RegisteredController) or
+///    an [AssignableController](channel_voice2::AssignableController).
+///    A controller is produced for both the data entry MSB and LSB,
+///    with the LSB taken as zero until it's been received.
+///
+/// Messages in the byte representation carry no group, so they are
+/// assumed to belong to the group given by [Midi1ToMidi2Translator::bytes_group].
+///
+/// ```rust
+/// use midi2::{prelude::*, translation::Midi1ToMidi2Translator};
+///
+/// let mut translator = Midi1ToMidi2Translator::new();
+///
+/// let message = channel_voice1::ChannelVoice1::try_from(&[0x93_u8, 0x3C, 0x7F][..]).unwrap();
+/// let Some(channel_voice2::ChannelVoice2::NoteOn(note_on)) = translator.translate(&message) else {
+///     panic!();
+/// };
+/// assert_eq!(note_on.channel(), u4::new(0x3));
+/// assert_eq!(note_on.note(), u7::new(0x3C));
+/// assert_eq!(note_on.velocity(), 0xFFFF);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Midi1ToMidi2Translator {
+    bytes_group: u4,
+    state: [[ChannelState; 16]; 16],
+}
+
+impl Midi1ToMidi2Translator {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The group assigned to messages in the byte representation.
+    pub fn bytes_group(&self) -> u4 {
+        self.bytes_group
+    }
+
+    pub fn set_bytes_group(&mut self, group: u4) {
+        self.bytes_group = group;
+    }
+
+    /// Forget all bank and parameter selections.
+    pub fn reset(&mut self) {
+        self.state = Default::default();
+    }
+
+    /// Translate the message, or hold it back if it only
+    /// affects how subsequent messages are translated.
+    pub fn translate<B: Buffer>(
+        &mut self,
+        message: &ChannelVoice1<B>,
+    ) -> Option<ChannelVoice2<[u32; 4]>> {
+        use ChannelVoice1 as M1;
+
+        let group = match <B::Unit as UnitPrivate>::UNIT_ID {
+            UNIT_ID_U32 => {
+                <B::Unit as UnitPrivate>::specialise_buffer_u32(message.data())[0].nibble(1)
+            }
+            _ => self.bytes_group,
+        };
+        let channel = message.channel();
+        let state = &mut self.state[usize::from(u8::from(group))][usize::from(u8::from(channel))];
+
+        let mut translated: ChannelVoice2<[u32; 4]> = match message {
+            M1::NoteOn(m) if m.velocity() == u7::new(0) => {
+                let mut note_off = channel_voice2::NoteOff::<[u32; 4]>::new();
+                note_off.set_note(m.note());
+                note_off.set_velocity(scale::u7_to_u16(u7::new(NOTE_OFF_DEFAULT_VELOCITY)));
+                note_off.into()
+            }
+            M1::NoteOn(m) => {
+                let mut note_on = channel_voice2::NoteOn::<[u32; 4]>::new();
+                note_on.set_note(m.note());
+                note_on.set_velocity(scale::u7_to_u16(m.velocity()));
+                note_on.into()
+            }
+            M1::NoteOff(m) => {
+                let mut note_off = channel_voice2::NoteOff::<[u32; 4]>::new();
+                note_off.set_note(m.note());
+                note_off.set_velocity(scale::u7_to_u16(m.velocity()));
+                note_off.into()
+            }
+            M1::KeyPressure(m) => {
+                let mut key_pressure = channel_voice2::KeyPressure::<[u32; 4]>::new();
+                key_pressure.set_note(m.note());
+                key_pressure.set_key_pressure_data(scale::u7_to_u32(m.pressure()));
+                key_pressure.into()
+            }
+            M1::ChannelPressure(m) => {
+                let mut channel_pressure = channel_voice2::ChannelPressure::<[u32; 4]>::new();
+                channel_pressure.set_channel_pressure_data(scale::u7_to_u32(m.pressure()));
+                channel_pressure.into()
+            }
+            M1::PitchBend(m) => {
+                let mut pitch_bend = channel_voice2::ChannelPitchBend::<[u32; 4]>::new();
+                pitch_bend.set_pitch_bend_data(scale::u14_to_u32(m.bend()));
+                pitch_bend.into()
+            }
+            M1::ProgramChange(m) => {
+                let mut program_change = channel_voice2::ProgramChange::<[u32; 4]>::new();
+                program_change.set_program(m.program());
+                if state.bank_msb.is_some() || state.bank_lsb.is_some() {
+                    program_change.set_bank(Some(u14::from_u7s(&[
+                        state.bank_msb.unwrap_or_default(),
+                        state.bank_lsb.unwrap_or_default(),
+                    ])));
+                }
+                program_change.into()
+            }
+            M1::ControlChange(m) => control_change(state, m.control(), m.control_data())?,
+        };
+
+        translated.set_group(group);
+        translated.set_channel(channel);
+        Some(translated)
+    }
+}
+
+fn control_change(
+    state: &mut ChannelState,
+    control: u7,
+    value: u7,
+) -> Option<ChannelVoice2<[u32; 4]>> {
+    match u8::from(control) {
+        BANK_SELECT_MSB => {
+            state.bank_msb = Some(value);
+            return None;
+        }
+        BANK_SELECT_LSB => {
+            state.bank_lsb = Some(value);
+            return None;
+        }
+        RPN_MSB | RPN_LSB | NRPN_MSB | NRPN_LSB => {
+            select_parameter(state, u8::from(control), value);
+            return None;
+        }
+        DATA_ENTRY_MSB if state.parameter_kind != ParameterKind::None => {
+            state.data_msb = value;
+            return Some(parameter_controller(state, u7::new(0)));
+        }
+        DATA_ENTRY_LSB if state.parameter_kind != ParameterKind::None => {
+            return Some(parameter_controller(state, value));
+        }
+        _ => {}
+    }
+
+    let mut control_change = channel_voice2::ControlChange::<[u32; 4]>::new();
+    control_change.set_control(control);
+    control_change.set_control_change_data(scale::u7_to_u32(value));
+    Some(control_change.into())
+}
+
+fn select_parameter(state: &mut ChannelState, control: u8, value: u7) {
+    let kind = match control {
+        RPN_MSB | RPN_LSB => ParameterKind::Registered,
+        _ => ParameterKind::Assignable,
+    };
+    if state.parameter_kind != kind {
+        state.parameter_kind = kind;
+        state.parameter_msb = u7::new(0);
+        state.parameter_lsb = u7::new(0);
+    }
+    match control {
+        RPN_MSB | NRPN_MSB => state.parameter_msb = value,
+        _ => state.parameter_lsb = value,
+    }
+    if state.parameter_msb == u7::new(NULL_PARAMETER)
+        && state.parameter_lsb == u7::new(NULL_PARAMETER)
+    {
+        state.parameter_kind = ParameterKind::None;
+    }
+}
+
+fn parameter_controller(state: &ChannelState, data_lsb: u7) -> ChannelVoice2<[u32; 4]> {
+    let data = scale::u14_to_u32(u14::from_u7s(&[data_lsb, state.data_msb]));
+    match state.parameter_kind {
+        ParameterKind::Registered => {
+            let mut controller = channel_voice2::RegisteredController::<[u32; 4]>::new();
+            controller.set_bank(state.parameter_msb);
+            controller.set_index(state.parameter_lsb);
+            controller.set_controller_data(data);
+            controller.into()
+        }
+        ParameterKind::Assignable => {
+            let mut controller = channel_voice2::AssignableController::<[u32; 4]>::new();
+            controller.set_bank(state.parameter_msb);
+            controller.set_index(state.parameter_lsb);
+            controller.set_controller_data(data);
+            controller.into()
+        }
+        ParameterKind::None => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn translate_bytes(
+        translator: &mut Midi1ToMidi2Translator,
+        data: &[u8],
+    ) -> Option<ChannelVoice2<[u32; 4]>> {
+        translator.translate(&ChannelVoice1::try_from(data).unwrap())
+    }
+
+    fn translated_data(translated: Option<ChannelVoice2<[u32; 4]>>) -> [u32; 4] {
+        let mut ret = [0x0; 4];
+        let translated = translated.unwrap();
+        ret[..translated.data().len()].copy_from_slice(translated.data());
+        ret
+    }
+
+    #[test]
+    fn note_on() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0x93, 0x3C, 0x40])),
+            [0x4093_3C00, 0x8000_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn note_on_ump() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        let message = ChannelVoice1::try_from(&[0x2A93_3C7F_u32][..]).unwrap();
+        assert_eq!(
+            translated_data(translator.translate(&message)),
+            [0x4A93_3C00, 0xFFFF_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn note_on_zero_velocity() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0x93, 0x3C, 0x00])),
+            [0x4083_3C00, 0x8000_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn note_off() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0x83, 0x3C, 0x7F])),
+            [0x4083_3C00, 0xFFFF_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn bytes_group() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        translator.set_bytes_group(u4::new(0x5));
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0x83, 0x3C, 0x7F])),
+            [0x4583_3C00, 0xFFFF_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn key_pressure() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xA1, 0x3C, 0x40])),
+            [0x40A1_3C00, 0x8000_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn channel_pressure() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xD1, 0x7F])),
+            [0x40D1_0000, 0xFFFF_FFFF, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn pitch_bend() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xE1, 0x00, 0x40])),
+            [0x40E1_0000, 0x8000_0000, 0x0, 0x0],
+        );
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xE1, 0x7F, 0x7F])),
+            [0x40E1_0000, 0xFFFF_FFFF, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn control_change() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xB2, 0x07, 0x40])),
+            [0x40B2_0700, 0x8000_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn program_change_without_bank() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xC2, 0x05])),
+            [0x40C2_0000, 0x0500_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn program_change_with_bank() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(translate_bytes(&mut translator, &[0xB2, 0x00, 0x12]), None);
+        assert_eq!(translate_bytes(&mut translator, &[0xB2, 0x20, 0x34]), None);
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xC2, 0x05])),
+            [0x40C2_0001, 0x0500_1234, 0x0, 0x0],
+        );
+        // the bank applies to subsequent program changes too
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xC2, 0x06])),
+            [0x40C2_0001, 0x0600_1234, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn bank_is_per_channel() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(translate_bytes(&mut translator, &[0xB2, 0x00, 0x12]), None);
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xC3, 0x05])),
+            [0x40C3_0000, 0x0500_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn bank_is_per_group() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        let bank_select = ChannelVoice1::try_from(&[0x21B2_0012_u32][..]).unwrap();
+        assert_eq!(translator.translate(&bank_select), None);
+        let program_change = ChannelVoice1::try_from(&[0x22C2_0500_u32][..]).unwrap();
+        assert_eq!(
+            translated_data(translator.translate(&program_change)),
+            [0x42C2_0000, 0x0500_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn registered_controller() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 101, 0x00]), None);
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 100, 0x02]), None);
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xB4, 6, 0x40])),
+            [0x4024_0002, 0x8000_0000, 0x0, 0x0],
+        );
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xB4, 38, 0x7F])),
+            [0x4024_0002, 0x81FC_0FE0, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn assignable_controller() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 99, 0x12]), None);
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 98, 0x34]), None);
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xB4, 6, 0x7F])),
+            [0x4034_1234, 0xFE03_F01F, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn null_parameter() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 101, 0x00]), None);
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 100, 0x00]), None);
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 101, 0x7F]), None);
+        assert_eq!(translate_bytes(&mut translator, &[0xB4, 100, 0x7F]), None);
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xB4, 6, 0x40])),
+            [0x40B4_0600, 0x8000_0000, 0x0, 0x0],
+        );
+    }
+
+    #[test]
+    fn reset() {
+        let mut translator = Midi1ToMidi2Translator::new();
+        assert_eq!(translate_bytes(&mut translator, &[0xB2, 0x00, 0x12]), None);
+        translator.reset();
+        assert_eq!(
+            translated_data(translate_bytes(&mut translator, &[0xC2, 0x05])),
+            [0x40C2_0000, 0x0500_0000, 0x0, 0x0],
+        );
+    }
+}
@@ -0,0 +1,439 @@
+use super::{
+    BANK_SELECT_LSB, BANK_SELECT_MSB, DATA_DECREMENT, DATA_ENTRY_LSB, DATA_ENTRY_MSB,
+    DATA_INCREMENT, NRPN_LSB, NRPN_MSB, RPN_LSB, RPN_MSB,
+};
+use crate::{
+    buffer::Ump,
+    channel_voice1::{self, ChannelVoice1},
+    channel_voice2::{ChannelVoice2, NoteAttribute},
+    detail::Encode7Bit,
+    scale,
+    traits::{Channeled, Grouped},
+    ux::u7,
+};
+
+const MAX_MESSAGES: usize = 4;
+
+/// How to handle MIDI 2.0 messages which have no MIDI 1.0 equivalent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UntranslatablePolicy {
+    /// Messages without an equivalent produce no output.
+    #[default]
+    Drop,
+    /// Messages are mapped onto the closest MIDI 1.0 behaviour.
+    ///  * Per note pitch bend is applied to the whole channel.
+    ///  * Relative controllers select their parameter and send a single
+    ///    data increment or data decrement step.
+    ///  * Notes with a pitch 7.9 attribute use the attribute's note number.
+    ///
+    /// The remaining per note messages are still dropped.
+    Approximate,
+}
+
+/// Translates MIDI 2.0 channel voice messages into
+/// their MIDI 1.0 equivalents.
+///
+/// Values are downscaled by dropping their low bits.
+/// Note on velocities which would be downscaled to zero are
+/// sent with a velocity of one so that they aren't mistaken for a note off.
+///
+/// A single MIDI 2.0 message can result in several MIDI 1.0 messages.
+///  * A program change with a bank is preceded by bank select controllers.
+///  * Registered and assignable controllers are sent as a parameter
+///    number followed by data entry MSB and LSB controllers.
+///
+/// The translated messages are returned through an iterator, so nothing
+/// needs to be allocated. They're in the ump representation, ready
+/// to be converted into bytes if needed.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     translation::Midi2ToMidi1Translator,
+/// };
+///
+/// let translator = Midi2ToMidi1Translator::new();
+///
+/// let mut message = channel_voice2::RegisteredController::<[u32; 4]>::new();
+/// message.set_bank(u7::new(0x0));
+/// message.set_index(u7::new(0x2));
+/// message.set_controller_data(0x8000_0000);
+///
+/// let mut bytes = Vec::new();
+/// for message in translator.translate(&channel_voice2::ChannelVoice2::from(message)) {
+///     let message: channel_voice1::ChannelVoice1<Vec<u8>> = message.into_bytes();
+///     bytes.extend_from_slice(message.data());
+/// }
+/// assert_eq!(
+///     bytes,
+///     &[0xB0, 101, 0x0, 0xB0, 100, 0x2, 0xB0, 6, 0x40, 0xB0, 38, 0x0],
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Midi2ToMidi1Translator {
+    policy: UntranslatablePolicy,
+}
+
+impl Midi2ToMidi1Translator {
+    /// Create a translator which drops messages
+    /// without a MIDI 1.0 equivalent.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn policy(&self) -> UntranslatablePolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: UntranslatablePolicy) {
+        self.policy = policy;
+    }
+
+    pub fn translate<B: Ump>(&self, message: &ChannelVoice2<B>) -> Midi1Messages {
+        use ChannelVoice2 as M2;
+
+        let mut messages = Midi1Messages::default();
+        let group = message.group();
+        let channel = message.channel();
+        let approximate = self.policy == UntranslatablePolicy::Approximate;
+
+        match message {
+            M2::NoteOn(m) => {
+                let mut note_on = channel_voice1::NoteOn::<[u32; 4]>::new();
+                note_on.set_note(self.note(m.note(), m.attribute()));
+                note_on.set_velocity(scale::u16_to_u7(m.velocity()).max(u7::new(1)));
+                messages.push(note_on.into());
+            }
+            M2::NoteOff(m) => {
+                let mut note_off = channel_voice1::NoteOff::<[u32; 4]>::new();
+                note_off.set_note(self.note(m.note(), m.attribute()));
+                note_off.set_velocity(scale::u16_to_u7(m.velocity()));
+                messages.push(note_off.into());
+            }
+            M2::KeyPressure(m) => {
+                let mut key_pressure = channel_voice1::KeyPressure::<[u32; 4]>::new();
+                key_pressure.set_note(m.note());
+                key_pressure.set_pressure(scale::u32_to_u7(m.key_pressure_data()));
+                messages.push(key_pressure.into());
+            }
+            M2::ChannelPressure(m) => {
+                let mut channel_pressure = channel_voice1::ChannelPressure::<[u32; 4]>::new();
+                channel_pressure.set_pressure(scale::u32_to_u7(m.channel_pressure_data()));
+                messages.push(channel_pressure.into());
+            }
+            M2::ChannelPitchBend(m) => messages.push(pitch_bend(m.pitch_bend_data())),
+            M2::PerNotePitchBend(m) if approximate => {
+                messages.push(pitch_bend(m.pitch_bend_data()))
+            }
+            M2::ControlChange(m) => messages.push(control_change(
+                m.control(),
+                scale::u32_to_u7(m.control_change_data()),
+            )),
+            M2::ProgramChange(m) => {
+                if let Some(bank) = m.bank() {
+                    let mut u7s = [u7::default(); 2];
+                    bank.to_u7s(&mut u7s);
+                    messages.push(control_change(u7::new(BANK_SELECT_MSB), u7s[0]));
+                    messages.push(control_change(u7::new(BANK_SELECT_LSB), u7s[1]));
+                }
+                let mut program_change = channel_voice1::ProgramChange::<[u32; 4]>::new();
+                program_change.set_program(m.program());
+                messages.push(program_change.into());
+            }
+            M2::RegisteredController(m) => {
+                messages.push_parameter(RPN_MSB, RPN_LSB, m.bank(), m.index());
+                messages.push_data_entry(m.controller_data());
+            }
+            M2::AssignableController(m) => {
+                messages.push_parameter(NRPN_MSB, NRPN_LSB, m.bank(), m.index());
+                messages.push_data_entry(m.controller_data());
+            }
+            M2::RelativeRegisteredController(m) if approximate && m.controller_data() != 0 => {
+                messages.push_parameter(RPN_MSB, RPN_LSB, m.bank(), m.index());
+                messages.push_data_step(m.controller_data());
+            }
+            M2::RelativeAssignableController(m) if approximate && m.controller_data() != 0 => {
+                messages.push_parameter(NRPN_MSB, NRPN_LSB, m.bank(), m.index());
+                messages.push_data_step(m.controller_data());
+            }
+            _ => {}
+        }
+
+        for message in messages.messages[..messages.len].iter_mut().flatten() {
+            message.set_group(group);
+            message.set_channel(channel);
+        }
+
+        messages
+    }
+
+    fn note(&self, note: u7, attribute: Option<NoteAttribute>) -> u7 {
+        match attribute {
+            Some(NoteAttribute::Pitch7_9 { note: pitch, .. })
+                if self.policy == UntranslatablePolicy::Approximate =>
+            {
+                pitch
+            }
+            _ => note,
+        }
+    }
+}
+
+/// The MIDI 1.0 messages resulting from a single translated MIDI 2.0 message.
+///
+/// See [Midi2ToMidi1Translator::translate].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Midi1Messages {
+    messages: [Option<ChannelVoice1<[u32; 4]>>; MAX_MESSAGES],
+    len: usize,
+    index: usize,
+}
+
+impl Midi1Messages {
+    fn push(&mut self, message: ChannelVoice1<[u32; 4]>) {
+        self.messages[self.len] = Some(message);
+        self.len += 1;
+    }
+
+    fn push_parameter(&mut self, msb_control: u8, lsb_control: u8, msb: u7, lsb: u7) {
+        self.push(control_change(u7::new(msb_control), msb));
+        self.push(control_change(u7::new(lsb_control), lsb));
+    }
+
+    fn push_data_entry(&mut self, data: u32) {
+        let mut u7s = [u7::default(); 2];
+        scale::u32_to_u14(data).to_u7s(&mut u7s);
+        // u7s are ordered least significant first
+        self.push(control_change(u7::new(DATA_ENTRY_MSB), u7s[1]));
+        self.push(control_change(u7::new(DATA_ENTRY_LSB), u7s[0]));
+    }
+
+    fn push_data_step(&mut self, delta: u32) {
+        let control = if (delta as i32).is_negative() {
+            DATA_DECREMENT
+        } else {
+            DATA_INCREMENT
+        };
+        self.push(control_change(u7::new(control), u7::new(0x0)));
+    }
+}
+
+impl core::iter::Iterator for Midi1Messages {
+    type Item = ChannelVoice1<[u32; 4]>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        let ret = self.messages[self.index].take();
+        self.index += 1;
+        ret
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl core::iter::FusedIterator for Midi1Messages {}
+
+impl core::iter::ExactSizeIterator for Midi1Messages {}
+
+fn control_change(control: u7, value: u7) -> ChannelVoice1<[u32; 4]> {
+    let mut control_change = channel_voice1::ControlChange::<[u32; 4]>::new();
+    control_change.set_control(control);
+    control_change.set_control_data(value);
+    control_change.into()
+}
+
+fn pitch_bend(data: u32) -> ChannelVoice1<[u32; 4]> {
+    let mut pitch_bend = channel_voice1::PitchBend::<[u32; 4]>::new();
+    pitch_bend.set_bend(scale::u32_to_u14(data));
+    pitch_bend.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{channel_voice2, traits::Data, ux::u14};
+    use pretty_assertions::assert_eq;
+
+    fn translate(translator: &Midi2ToMidi1Translator, data: &[u32]) -> std::vec::Vec<u32> {
+        translator
+            .translate(&ChannelVoice2::try_from(data).unwrap())
+            .map(|m| m.data()[0])
+            .collect()
+    }
+
+    #[test]
+    fn note_on() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x4B9A_3C00, 0x8000_0000]),
+            [0x2B9A_3C40],
+        );
+    }
+
+    #[test]
+    fn note_on_minimum_velocity() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x4B9A_3C00, 0x0100_0000]),
+            [0x2B9A_3C01],
+        );
+    }
+
+    #[test]
+    fn note_off() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x4B8A_3C00, 0xFFFF_0000]),
+            [0x2B8A_3C7F],
+        );
+    }
+
+    #[test]
+    fn note_on_with_pitch_attribute() {
+        let mut translator = Midi2ToMidi1Translator::new();
+        let data = [0x4B9A_3C03, 0x8000_7E00];
+        assert_eq!(translate(&translator, &data), [0x2B9A_3C40]);
+        translator.set_policy(UntranslatablePolicy::Approximate);
+        assert_eq!(translate(&translator, &data), [0x2B9A_3F40]);
+    }
+
+    #[test]
+    fn key_pressure() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x40A1_3C00, 0xFFFF_FFFF]),
+            [0x20A1_3C7F],
+        );
+    }
+
+    #[test]
+    fn channel_pressure() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x40D1_0000, 0x8000_0000]),
+            [0x20D1_4000],
+        );
+    }
+
+    #[test]
+    fn channel_pitch_bend() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x40E1_0000, 0x8000_0000]),
+            [0x20E1_0040],
+        );
+    }
+
+    #[test]
+    fn per_note_pitch_bend() {
+        let mut translator = Midi2ToMidi1Translator::new();
+        let data = [0x4061_3C00, 0xFFFF_FFFF];
+        assert_eq!(translate(&translator, &data), []);
+        translator.set_policy(UntranslatablePolicy::Approximate);
+        assert_eq!(translate(&translator, &data), [0x20E1_7F7F]);
+    }
+
+    #[test]
+    fn control_change() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x40B2_0700, 0x8000_0000]),
+            [0x20B2_0740],
+        );
+    }
+
+    #[test]
+    fn program_change_without_bank() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x40C2_0000, 0x0500_0000]),
+            [0x20C2_0500],
+        );
+    }
+
+    #[test]
+    fn program_change_with_bank() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x40C2_0001, 0x0500_1234]),
+            [0x20B2_0012, 0x20B2_2034, 0x20C2_0500],
+        );
+    }
+
+    #[test]
+    fn registered_controller() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x4024_0002, 0x81FC_0FE0]),
+            [0x20B4_6500, 0x20B4_6402, 0x20B4_0640, 0x20B4_267F],
+        );
+    }
+
+    #[test]
+    fn assignable_controller() {
+        assert_eq!(
+            translate(&Midi2ToMidi1Translator::new(), &[0x4034_1234, 0xFFFF_FFFF]),
+            [0x20B4_6312, 0x20B4_6234, 0x20B4_067F, 0x20B4_267F],
+        );
+    }
+
+    #[test]
+    fn relative_controller() {
+        let mut translator = Midi2ToMidi1Translator::new();
+        let increment = [0x4044_0002, 0x0000_0010];
+        let decrement = [0x4054_1234, 0xFFFF_FFF0];
+        assert_eq!(translate(&translator, &increment), []);
+        assert_eq!(translate(&translator, &decrement), []);
+        translator.set_policy(UntranslatablePolicy::Approximate);
+        assert_eq!(
+            translate(&translator, &increment),
+            [0x20B4_6500, 0x20B4_6402, 0x20B4_6000],
+        );
+        assert_eq!(
+            translate(&translator, &decrement),
+            [0x20B4_6312, 0x20B4_6234, 0x20B4_6100],
+        );
+    }
+
+    #[test]
+    fn per_note_management() {
+        let mut translator = Midi2ToMidi1Translator::new();
+        translator.set_policy(UntranslatablePolicy::Approximate);
+        assert_eq!(translate(&translator, &[0x40F4_3C03, 0x0]), []);
+    }
+
+    #[test]
+    fn exact_size() {
+        let mut message = channel_voice2::ProgramChange::<[u32; 4]>::new();
+        message.set_bank(Some(u14::new(0x0)));
+        let mut messages = Midi2ToMidi1Translator::new().translate(&message.into());
+        assert_eq!(messages.len(), 3);
+        messages.next();
+        assert_eq!(messages.len(), 2);
+    }
+
+    #[test]
+    fn round_trip() {
+        use crate::translation::Midi1ToMidi2Translator;
+
+        let up = &mut Midi1ToMidi2Translator::new();
+        let down = Midi2ToMidi1Translator::new();
+        let messages: [&[u8]; 8] = [
+            &[0x93, 0x3C, 0x41],
+            &[0x83, 0x3C, 0x12],
+            &[0xA3, 0x3C, 0x7F],
+            &[0xD3, 0x22],
+            &[0xE3, 0x12, 0x34],
+            &[0xB3, 0x07, 0x40],
+            &[0xC3, 0x05],
+            &[0x93, 0x3D, 0x01],
+        ];
+        for data in messages {
+            let message = ChannelVoice1::try_from(data).unwrap();
+            let translated = up.translate(&message).unwrap();
+            let round_tripped = down
+                .translate(&translated)
+                .map(|m| {
+                    let m: ChannelVoice1<std::vec::Vec<u8>> =
+                        crate::traits::IntoBytes::into_bytes(m);
+                    m
+                })
+                .collect::<std::vec::Vec<_>>();
+            assert_eq!(round_tripped.len(), 1);
+            assert_eq!(round_tripped[0].data(), data);
+        }
+    }
+}
@@ -5,9 +5,15 @@ use crate::{
 
 mod device_identity;
 mod end_of_clip;
+#[cfg(feature = "std")]
+mod endpoint_description;
 mod endpoint_discovery;
+#[cfg(feature = "std")]
+mod endpoint_discovery_initiator;
 mod endpoint_info;
 mod endpoint_name;
+#[cfg(feature = "std")]
+mod endpoint_responder;
 mod function_block_discovery;
 mod function_block_info;
 mod function_block_name;
@@ -18,9 +24,15 @@ mod stream_configuration_request;
 
 pub use device_identity::*;
 pub use end_of_clip::*;
+#[cfg(feature = "std")]
+pub use endpoint_description::*;
 pub use endpoint_discovery::*;
+#[cfg(feature = "std")]
+pub use endpoint_discovery_initiator::*;
 pub use endpoint_info::*;
 pub use endpoint_name::*;
+#[cfg(feature = "std")]
+pub use endpoint_responder::*;
 pub use function_block_discovery::*;
 pub use function_block_info::*;
 pub use function_block_name::FunctionBlockName;
@@ -40,6 +52,7 @@ const END_FORMAT: u8 = 0x3;
     midi2_proc::Data,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -61,53 +74,51 @@ pub enum UmpStream<B: crate::buffer::Ump> {
         stream_configuration_notification::StreamConfigurationNotification<B>,
     ),
     StreamConfigurationRequest(stream_configuration_request::StreamConfigurationRequest<B>),
+    #[cfg(feature = "unknown")]
+    Unknown(crate::UnknownUmp<B>),
 }
 
 impl<'a> TryFrom<&'a [u32]> for UmpStream<&'a [u32]> {
     type Error = crate::error::Error;
     fn try_from(value: &'a [u32]) -> Result<Self, Self::Error> {
         use UmpStream::*;
-        if value.len() < 1 {
+        if value.is_empty() {
             return Err(crate::error::Error::InvalidData("Slice is too short"));
         };
         Ok(match status_from_buffer(value) {
             device_identity::STATUS => {
-                DeviceIdentity(device_identity::DeviceIdentity::try_from(value)?.into())
+                DeviceIdentity(device_identity::DeviceIdentity::try_from(value)?)
             }
-            end_of_clip::STATUS => EndOfClip(end_of_clip::EndOfClip::try_from(value)?.into()),
+            end_of_clip::STATUS => EndOfClip(end_of_clip::EndOfClip::try_from(value)?),
             endpoint_discovery::STATUS => {
-                EndpointDiscovery(endpoint_discovery::EndpointDiscovery::try_from(value)?.into())
-            }
-            endpoint_info::STATUS => {
-                EndpointInfo(endpoint_info::EndpointInfo::try_from(value)?.into())
-            }
-            endpoint_name::STATUS => {
-                EndpointName(endpoint_name::EndpointName::try_from(value)?.into())
+                EndpointDiscovery(endpoint_discovery::EndpointDiscovery::try_from(value)?)
             }
+            endpoint_info::STATUS => EndpointInfo(endpoint_info::EndpointInfo::try_from(value)?),
+            endpoint_name::STATUS => EndpointName(endpoint_name::EndpointName::try_from(value)?),
             function_block_discovery::STATUS => FunctionBlockDiscovery(
-                function_block_discovery::FunctionBlockDiscovery::try_from(value)?.into(),
+                function_block_discovery::FunctionBlockDiscovery::try_from(value)?,
             ),
             function_block_info::STATUS => {
-                FunctionBlockInfo(function_block_info::FunctionBlockInfo::try_from(value)?.into())
+                FunctionBlockInfo(function_block_info::FunctionBlockInfo::try_from(value)?)
             }
             function_block_name::STATUS => {
-                FunctionBlockName(function_block_name::FunctionBlockName::try_from(value)?.into())
+                FunctionBlockName(function_block_name::FunctionBlockName::try_from(value)?)
             }
             product_instance_id::STATUS => {
-                ProductInstanceId(product_instance_id::ProductInstanceId::try_from(value)?.into())
-            }
-            start_of_clip::STATUS => {
-                StartOfClip(start_of_clip::StartOfClip::try_from(value)?.into())
+                ProductInstanceId(product_instance_id::ProductInstanceId::try_from(value)?)
             }
+            start_of_clip::STATUS => StartOfClip(start_of_clip::StartOfClip::try_from(value)?),
             stream_configuration_notification::STATUS => StreamConfigurationNotification(
                 stream_configuration_notification::StreamConfigurationNotification::try_from(
                     value,
-                )?
-                .into(),
+                )?,
             ),
             stream_configuration_request::STATUS => StreamConfigurationRequest(
-                stream_configuration_request::StreamConfigurationRequest::try_from(value)?.into(),
+                stream_configuration_request::StreamConfigurationRequest::try_from(value)?,
             ),
+            #[cfg(feature = "unknown")]
+            _ => Unknown(crate::UnknownUmp::try_from(value)?),
+            #[cfg(not(feature = "unknown"))]
             _ => Err(crate::error::Error::InvalidData(
                 "Couldn't interpret flex data status / bank fields",
             ))?,
@@ -122,9 +133,7 @@ impl<const STATUS: u16, B: Ump> property::Property<B> for StatusProperty<STATUS>
 }
 
 impl<'a, const STATUS: u16, B: Ump> property::ReadProperty<'a, B> for StatusProperty<STATUS> {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
     fn validate(buffer: &B) -> crate::result::Result<()> {
         if buffer
             .buffer()
@@ -148,9 +157,7 @@ impl<const STATUS: u16, B: Ump + BufferMut> property::WriteProperty<B> for Statu
             packet[0] |= (STATUS as u32) << 16;
         }
     }
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
 }
 
 struct ConsistentFormatsProperty;
@@ -160,9 +167,7 @@ impl<B: Ump> property::Property<B> for ConsistentFormatsProperty {
 }
 
 impl<'a, B: Ump> property::ReadProperty<'a, B> for ConsistentFormatsProperty {
-    fn read(_buffer: &'a B) -> Self::Type {
-        ()
-    }
+    fn read(_buffer: &'a B) -> Self::Type {}
 
     fn validate(buffer: &B) -> crate::result::Result<()> {
         use crate::detail::helpers::validate_sysex_group_statuses;
@@ -181,9 +186,7 @@ impl<'a, B: Ump> property::ReadProperty<'a, B> for ConsistentFormatsProperty {
 }
 
 impl<B: Ump + BufferMut> property::WriteProperty<B> for ConsistentFormatsProperty {
-    fn default() -> Self::Type {
-        ()
-    }
+    fn default() -> Self::Type {}
     fn write(buffer: &mut B, _v: Self::Type) {
         set_format_fields(buffer.buffer_mut())
     }
@@ -394,7 +397,7 @@ fn clear_payload<const OFFSET: usize>(buffer: &mut [u32]) {
 }
 
 fn required_buffer_size_for_str<const OFFSET: usize>(s: &str) -> usize {
-    let str_size = s.as_bytes().len();
+    let str_size = s.len();
     let packet_capacity = 14 - OFFSET;
     if str_size % packet_capacity == 0 {
         if str_size == 0 {
@@ -448,6 +451,18 @@ mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[cfg(feature = "unknown")]
+    #[test]
+    fn unknown_status() {
+        let buffer = [0xF0FF_0000, 0x0, 0x0, 0x0];
+        assert_eq!(
+            UmpStream::try_from(&buffer[..]),
+            Ok(UmpStream::Unknown(
+                crate::UnknownUmp::try_from(&buffer[..]).unwrap()
+            )),
+        );
+    }
+
     #[test]
     fn builder() {
         assert_eq!(
@@ -0,0 +1,110 @@
+use crate::{
+    ump_stream::{Direction, Midi1Port, UiHint},
+    ux::{u14, u4, u7},
+};
+use std::{string::String, vec::Vec};
+
+/// A function block of a ump endpoint.
+///
+/// Holds the fields reported by the
+/// [FunctionBlockInfo](crate::ump_stream::FunctionBlockInfo) and
+/// [FunctionBlockName](crate::ump_stream::FunctionBlockName) messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionBlock {
+    pub active: bool,
+    pub first_group: u4,
+    pub number_of_groups_spanned: u8,
+    pub midi_ci_version: u8,
+    pub max_number_of_midi_ci_streams: u8,
+    pub ui_hint: UiHint,
+    pub midi1_port: Option<Midi1Port>,
+    pub direction: Direction,
+    pub name: String,
+}
+
+impl Default for FunctionBlock {
+    fn default() -> Self {
+        FunctionBlock {
+            active: true,
+            first_group: u4::new(0x0),
+            number_of_groups_spanned: 1,
+            midi_ci_version: 0x0,
+            max_number_of_midi_ci_streams: 0x0,
+            ui_hint: UiHint::Undeclared,
+            midi1_port: None,
+            direction: Direction::Bidirectional,
+            name: String::new(),
+        }
+    }
+}
+
+impl FunctionBlock {
+    /// Whether the block spans the given group.
+    pub fn contains_group(&self, group: u4) -> bool {
+        let first = u8::from(self.first_group);
+        let group = u8::from(group);
+        group >= first
+            && u16::from(group) < u16::from(first) + u16::from(self.number_of_groups_spanned)
+    }
+}
+
+/// Everything a ump endpoint reports about itself during stream discovery.
+///
+/// An empty name or product instance id is treated as not being supported
+/// and is never sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointDescription {
+    pub ump_version_major: u8,
+    pub ump_version_minor: u8,
+    pub name: String,
+    pub product_instance_id: String,
+    pub device_manufacturer: [u7; 3],
+    pub device_family: u14,
+    pub device_family_model_number: u14,
+    pub software_version: [u7; 4],
+    pub supports_midi1_protocol: bool,
+    pub supports_midi2_protocol: bool,
+    pub supports_receiving_jr_timestamps: bool,
+    pub supports_sending_jr_timestamps: bool,
+    pub static_function_blocks: bool,
+    pub function_blocks: Vec<FunctionBlock>,
+}
+
+impl Default for EndpointDescription {
+    fn default() -> Self {
+        EndpointDescription {
+            ump_version_major: 0x1,
+            ump_version_minor: 0x1,
+            name: String::new(),
+            product_instance_id: String::new(),
+            device_manufacturer: [u7::new(0x0); 3],
+            device_family: u14::new(0x0),
+            device_family_model_number: u14::new(0x0),
+            software_version: [u7::new(0x0); 4],
+            supports_midi1_protocol: false,
+            supports_midi2_protocol: true,
+            supports_receiving_jr_timestamps: false,
+            supports_sending_jr_timestamps: false,
+            static_function_blocks: false,
+            function_blocks: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_group() {
+        let block = FunctionBlock {
+            first_group: u4::new(0x2),
+            number_of_groups_spanned: 3,
+            ..Default::default()
+        };
+        assert!(!block.contains_group(u4::new(0x1)));
+        assert!(block.contains_group(u4::new(0x2)));
+        assert!(block.contains_group(u4::new(0x4)));
+        assert!(!block.contains_group(u4::new(0x5)));
+    }
+}
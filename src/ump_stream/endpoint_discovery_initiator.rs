@@ -0,0 +1,421 @@
+use crate::{
+    buffer::Ump,
+    ump_stream::{
+        DeviceIdentity, EndpointDescription, EndpointDiscovery, EndpointInfo, FunctionBlock,
+        FunctionBlockDiscovery, FunctionBlockInfo, StreamConfigurationNotification, UmpStream,
+    },
+};
+use std::vec::Vec;
+
+const ALL_FUNCTION_BLOCKS: u8 = 0xFF;
+
+/// A remote ump endpoint as learned through stream discovery.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointModel {
+    pub endpoint: EndpointDescription,
+    /// The protocol in use: `0x1` for MIDI 1.0 and `0x2` for MIDI 2.0.
+    pub protocol: u8,
+    pub receive_jr_timestamps: bool,
+    pub send_jr_timestamps: bool,
+}
+
+impl Default for EndpointModel {
+    fn default() -> Self {
+        EndpointModel {
+            endpoint: EndpointDescription {
+                ump_version_major: 0x0,
+                ump_version_minor: 0x0,
+                supports_midi2_protocol: false,
+                ..Default::default()
+            },
+            protocol: 0x0,
+            receive_jr_timestamps: false,
+            send_jr_timestamps: false,
+        }
+    }
+}
+
+/// The progress of an [EndpointDiscoveryInitiator].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiscoveryState {
+    NotStarted,
+    InProgress,
+    Complete,
+    /// The endpoint didn't send all of the expected replies in time.
+    /// The model holds whatever did arrive.
+    TimedOut,
+}
+
+/// Discovers a remote ump endpoint and builds an [EndpointModel] from
+/// its replies.
+///
+/// Discovery is complete once the endpoint info, device identity and stream
+/// configuration, along with the info of every function block, have arrived.
+/// Names and the product instance id are optional for an endpoint, so they
+/// aren't waited for. Replies which arrive late, and any later
+/// notifications, still update the model.
+///
+/// Multi-packet messages must be passed in whole,
+/// for example as read by a [UmpStreamIter](crate::UmpStreamIter).
+///
+/// The timeout is driven by the caller, which passes in the current time
+/// in milliseconds as read from a monotonic clock of its choosing.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     ump_stream::{DiscoveryState, EndpointDiscoveryInitiator, UmpStream},
+/// };
+///
+/// let start = 0;
+/// let mut initiator = EndpointDiscoveryInitiator::new(500);
+/// let _request = initiator.start(start);
+///
+/// let buffer = [
+///     0xF001_0101, 0x0000_0200, 0x0, 0x0, // endpoint info
+///     0xF002_0000, 0x0000_0001, 0x0, 0x0, // device identity
+///     0xF003_5379, 0x6E74_6800, 0x0, 0x0, // endpoint name
+///     0xF006_0200, 0x0, 0x0, 0x0, // stream configuration
+/// ];
+/// for message in UmpStreamIter::new(&buffer) {
+///     let UmpMessage::UmpStream(message) = message.unwrap() else {
+///         panic!();
+///     };
+///     initiator.process(&message, start);
+/// }
+///
+/// assert_eq!(initiator.state(start), DiscoveryState::Complete);
+/// assert_eq!(initiator.model().endpoint.name, "Synth");
+/// ```
+#[derive(Clone, Debug)]
+pub struct EndpointDiscoveryInitiator {
+    timeout: u64,
+    deadline: Option<u64>,
+    model: EndpointModel,
+    endpoint_info_received: bool,
+    device_identity_received: bool,
+    stream_configuration_received: bool,
+    function_block_info_received: Vec<bool>,
+}
+
+impl EndpointDiscoveryInitiator {
+    /// How long to wait for replies, in milliseconds.
+    pub fn new(timeout: u64) -> Self {
+        EndpointDiscoveryInitiator {
+            timeout,
+            deadline: None,
+            model: EndpointModel::default(),
+            endpoint_info_received: false,
+            device_identity_received: false,
+            stream_configuration_received: false,
+            function_block_info_received: Vec::new(),
+        }
+    }
+
+    pub fn model(&self) -> &EndpointModel {
+        &self.model
+    }
+
+    /// Begins discovery afresh, clearing the model.
+    ///
+    /// Returns the request to send to the endpoint.
+    pub fn start(&mut self, now: u64) -> EndpointDiscovery<[u32; 4]> {
+        *self = EndpointDiscoveryInitiator::new(self.timeout);
+        self.deadline = Some(now.saturating_add(self.timeout));
+
+        let mut request = EndpointDiscovery::<[u32; 4]>::new();
+        request.set_ump_version_major(0x1);
+        request.set_ump_version_minor(0x1);
+        request.set_request_endpoint_info(true);
+        request.set_request_device_identity(true);
+        request.set_request_endpoint_name(true);
+        request.set_request_product_instance_id(true);
+        request.set_request_stream_configuration(true);
+        request
+    }
+
+    pub fn state(&self, now: u64) -> DiscoveryState {
+        match self.deadline {
+            None => DiscoveryState::NotStarted,
+            Some(_) if self.is_complete() => DiscoveryState::Complete,
+            Some(deadline) if now >= deadline => DiscoveryState::TimedOut,
+            Some(_) => DiscoveryState::InProgress,
+        }
+    }
+
+    /// Updates the model from a message sent by the endpoint.
+    ///
+    /// When the endpoint info announces function blocks, the request
+    /// for their info and names is returned, to be sent to the endpoint.
+    pub fn process<B: Ump>(
+        &mut self,
+        message: &UmpStream<B>,
+        now: u64,
+    ) -> Option<FunctionBlockDiscovery<[u32; 4]>> {
+        if self.state(now) == DiscoveryState::InProgress {
+            if let Some(deadline) = self.deadline.as_mut() {
+                // the endpoint is still replying
+                *deadline = now.saturating_add(self.timeout);
+            }
+        }
+        match message {
+            UmpStream::EndpointInfo(m) => return self.endpoint_info(m),
+            UmpStream::DeviceIdentity(m) => self.device_identity(m),
+            UmpStream::EndpointName(m) => self.model.endpoint.name = m.name(),
+            UmpStream::ProductInstanceId(m) => self.model.endpoint.product_instance_id = m.id(),
+            UmpStream::StreamConfigurationNotification(m) => self.stream_configuration(m),
+            UmpStream::FunctionBlockInfo(m) => self.function_block_info(m),
+            UmpStream::FunctionBlockName(m) => {
+                self.function_block(usize::from(m.function_block())).name = m.name();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn is_complete(&self) -> bool {
+        self.endpoint_info_received
+            && self.device_identity_received
+            && self.stream_configuration_received
+            && self.function_block_info_received.iter().all(|r| *r)
+    }
+
+    fn endpoint_info<B: Ump>(
+        &mut self,
+        message: &EndpointInfo<B>,
+    ) -> Option<FunctionBlockDiscovery<[u32; 4]>> {
+        let endpoint = &mut self.model.endpoint;
+        endpoint.ump_version_major = message.ump_version_major();
+        endpoint.ump_version_minor = message.ump_version_minor();
+        endpoint.static_function_blocks = message.static_function_blocks();
+        endpoint.supports_midi1_protocol = message.supports_midi1_protocol();
+        endpoint.supports_midi2_protocol = message.supports_midi2_protocol();
+        endpoint.supports_receiving_jr_timestamps = message.supports_receiving_jr_timestamps();
+        endpoint.supports_sending_jr_timestamps = message.supports_sending_jr_timestamps();
+
+        let number_of_function_blocks = usize::from(u8::from(message.number_of_function_blocks()));
+        endpoint
+            .function_blocks
+            .resize(number_of_function_blocks, FunctionBlock::default());
+        self.function_block_info_received
+            .resize(number_of_function_blocks, false);
+        self.endpoint_info_received = true;
+
+        if number_of_function_blocks == 0 {
+            return None;
+        }
+        let mut request = FunctionBlockDiscovery::<[u32; 4]>::new();
+        request.set_function_block_number(ALL_FUNCTION_BLOCKS);
+        request.set_requesting_function_block_info(true);
+        request.set_requesting_function_block_name(true);
+        Some(request)
+    }
+
+    fn device_identity<B: Ump>(&mut self, message: &DeviceIdentity<B>) {
+        let endpoint = &mut self.model.endpoint;
+        endpoint.device_manufacturer = message.device_manufacturer();
+        endpoint.device_family = message.device_family();
+        endpoint.device_family_model_number = message.device_family_model_number();
+        endpoint.software_version = message.software_version();
+        self.device_identity_received = true;
+    }
+
+    fn stream_configuration<B: Ump>(&mut self, message: &StreamConfigurationNotification<B>) {
+        self.model.protocol = message.protocol();
+        self.model.receive_jr_timestamps = message.receive_jr_timestamps();
+        self.model.send_jr_timestamps = message.send_jr_timestamps();
+        self.stream_configuration_received = true;
+    }
+
+    fn function_block_info<B: Ump>(&mut self, message: &FunctionBlockInfo<B>) {
+        let index = usize::from(u8::from(message.function_block_number()));
+        let block = self.function_block(index);
+        block.active = message.active();
+        block.first_group = message.first_group();
+        block.number_of_groups_spanned = message.number_of_groups_spanned();
+        block.midi_ci_version = message.midi_ci_version();
+        block.max_number_of_midi_ci_streams = message.max_number_of_midi_ci_streams();
+        block.ui_hint = message.ui_hint();
+        block.midi1_port = message.midi1_port();
+        block.direction = message.direction();
+        self.function_block_info_received[index] = true;
+    }
+
+    // blocks beyond those announced by the endpoint info are added
+    fn function_block(&mut self, index: usize) -> &mut FunctionBlock {
+        let blocks = &mut self.model.endpoint.function_blocks;
+        if index >= blocks.len() {
+            blocks.resize(index + 1, FunctionBlock::default());
+            self.function_block_info_received.resize(index + 1, false);
+        }
+        &mut blocks[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        traits::Data,
+        ump_stream::{Direction, EndpointResponder, Midi1Port, UiHint},
+        ux::{u14, u4, u7},
+    };
+    use pretty_assertions::assert_eq;
+
+    const TIMEOUT: u64 = 100;
+
+    fn remote() -> EndpointDescription {
+        EndpointDescription {
+            name: "Synth with a rather long name".into(),
+            product_instance_id: "0123456789".into(),
+            device_manufacturer: [u7::new(0x1), u7::new(0x2), u7::new(0x3)],
+            device_family: u14::new(0x0101),
+            device_family_model_number: u14::new(0x0202),
+            software_version: [u7::new(0x4), u7::new(0x5), u7::new(0x6), u7::new(0x7)],
+            supports_midi1_protocol: true,
+            supports_midi2_protocol: true,
+            function_blocks: std::vec![
+                FunctionBlock {
+                    first_group: u4::new(0x0),
+                    number_of_groups_spanned: 2,
+                    ui_hint: UiHint::Receiver,
+                    direction: Direction::Input,
+                    midi1_port: Some(Midi1Port::RestrictBandwidth),
+                    name: "Piano".into(),
+                    ..Default::default()
+                },
+                FunctionBlock {
+                    active: false,
+                    first_group: u4::new(0x2),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn discover(
+        responder: &mut EndpointResponder,
+        initiator: &mut EndpointDiscoveryInitiator,
+        now: u64,
+    ) {
+        let request = initiator.start(now);
+        let mut requests = std::vec![UmpStream::from(request)];
+        while let Some(request) = requests.pop() {
+            for reply in responder.respond(&request) {
+                if let Some(request) = initiator.process(&reply, now) {
+                    requests.push(request.into());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn not_started() {
+        let initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        assert_eq!(initiator.state(0), DiscoveryState::NotStarted);
+    }
+
+    #[test]
+    fn discovery_request() {
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        assert_eq!(initiator.start(0).data(), &[0xF000_0101, 0x0000_001F]);
+    }
+
+    #[test]
+    fn complete_discovery() {
+        let now = 1000;
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        discover(
+            &mut EndpointResponder::new(remote()).unwrap(),
+            &mut initiator,
+            now,
+        );
+        assert_eq!(initiator.state(now), DiscoveryState::Complete);
+        assert_eq!(
+            initiator.model(),
+            &EndpointModel {
+                endpoint: remote(),
+                protocol: 0x2,
+                receive_jr_timestamps: false,
+                send_jr_timestamps: false,
+            }
+        );
+    }
+
+    #[test]
+    fn function_block_request() {
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        let info = EndpointResponder::new(remote())
+            .unwrap()
+            .respond(&initiator.start(0).into())[0]
+            .clone();
+        let request = initiator.process(&info, 0).unwrap();
+        assert_eq!(request.data(), &[0xF010_FF03]);
+    }
+
+    #[test]
+    fn no_function_blocks() {
+        let now = 1000;
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        discover(
+            &mut EndpointResponder::new(EndpointDescription::default()).unwrap(),
+            &mut initiator,
+            now,
+        );
+        assert_eq!(initiator.state(now), DiscoveryState::Complete);
+        assert_eq!(initiator.model().endpoint.function_blocks, std::vec![]);
+    }
+
+    #[test]
+    fn timed_out() {
+        let now = 1000;
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        initiator.start(now);
+        assert_eq!(
+            initiator.state(now + TIMEOUT / 2),
+            DiscoveryState::InProgress
+        );
+        assert_eq!(initiator.state(now + TIMEOUT), DiscoveryState::TimedOut);
+    }
+
+    #[test]
+    fn missing_function_block_info_times_out() {
+        let now = 1000;
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        let mut responder = EndpointResponder::new(remote()).unwrap();
+        for reply in responder.respond(&initiator.start(now).into()) {
+            initiator.process(&reply, now);
+        }
+        assert_eq!(initiator.state(now), DiscoveryState::InProgress);
+        assert_eq!(initiator.state(now + TIMEOUT), DiscoveryState::TimedOut);
+        assert_eq!(
+            initiator.model().endpoint.name,
+            "Synth with a rather long name"
+        );
+    }
+
+    #[test]
+    fn replies_extend_the_deadline() {
+        let now = 1000;
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        let mut responder = EndpointResponder::new(remote()).unwrap();
+        let replies = responder.respond(&initiator.start(now).into());
+        initiator.process(&replies[0], now + TIMEOUT / 2);
+        assert_eq!(initiator.state(now + TIMEOUT), DiscoveryState::InProgress);
+    }
+
+    #[test]
+    fn restart_clears_the_model() {
+        let now = 1000;
+        let mut initiator = EndpointDiscoveryInitiator::new(TIMEOUT);
+        discover(
+            &mut EndpointResponder::new(remote()).unwrap(),
+            &mut initiator,
+            now,
+        );
+        initiator.start(now);
+        assert_eq!(initiator.model(), &EndpointModel::default());
+        assert_eq!(initiator.state(now), DiscoveryState::InProgress);
+    }
+}
@@ -0,0 +1,412 @@
+use crate::{
+    buffer::Ump,
+    error::Error,
+    traits::RebufferInto,
+    ump_stream::{
+        DeviceIdentity, EndpointDescription, EndpointDiscovery, EndpointInfo, EndpointName,
+        FunctionBlock, FunctionBlockDiscovery, FunctionBlockInfo, FunctionBlockName,
+        ProductInstanceId, StreamConfigurationNotification, StreamConfigurationRequest, UmpStream,
+    },
+    ux::u7,
+};
+use std::vec::Vec;
+
+const MIDI1_PROTOCOL: u8 = 0x1;
+const MIDI2_PROTOCOL: u8 = 0x2;
+const ALL_FUNCTION_BLOCKS: u8 = 0xFF;
+const MAX_FUNCTION_BLOCKS: usize = 32;
+
+const ERR_TOO_MANY_FUNCTION_BLOCKS: &str = "An endpoint has at most 32 function blocks";
+
+/// Answers the stream discovery and configuration messages sent to a ump
+/// endpoint.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     ump_stream::{EndpointDescription, EndpointResponder, UmpStream},
+/// };
+///
+/// let mut responder = EndpointResponder::new(EndpointDescription {
+///     name: "Synth".into(),
+///     ..Default::default()
+/// })
+/// .unwrap();
+///
+/// let mut discovery = ump_stream::EndpointDiscovery::<[u32; 4]>::new();
+/// discovery.set_request_endpoint_info(true);
+/// discovery.set_request_endpoint_name(true);
+///
+/// let replies = responder.respond(&discovery.into());
+/// assert_eq!(replies.len(), 2);
+/// assert!(matches!(replies[0], UmpStream::EndpointInfo(_)));
+/// let UmpStream::EndpointName(name) = &replies[1] else {
+///     panic!();
+/// };
+/// assert_eq!(name.name(), "Synth");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EndpointResponder {
+    description: EndpointDescription,
+    protocol: u8,
+    receive_jr_timestamps: bool,
+    send_jr_timestamps: bool,
+}
+
+impl EndpointResponder {
+    /// The endpoint starts out on the MIDI 2.0 protocol if it supports it,
+    /// with jitter reduction timestamps disabled.
+    ///
+    /// Fails when the description has more than the 32 function blocks
+    /// allowed by the specification.
+    pub fn new(description: EndpointDescription) -> Result<Self, Error> {
+        if description.function_blocks.len() > MAX_FUNCTION_BLOCKS {
+            return Err(Error::InvalidData(ERR_TOO_MANY_FUNCTION_BLOCKS));
+        }
+        let protocol =
+            if description.supports_midi2_protocol || !description.supports_midi1_protocol {
+                MIDI2_PROTOCOL
+            } else {
+                MIDI1_PROTOCOL
+            };
+        Ok(EndpointResponder {
+            description,
+            protocol,
+            receive_jr_timestamps: false,
+            send_jr_timestamps: false,
+        })
+    }
+
+    pub fn description(&self) -> &EndpointDescription {
+        &self.description
+    }
+
+    /// The negotiated protocol: `0x1` for MIDI 1.0 and `0x2` for MIDI 2.0.
+    pub fn protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    pub fn receive_jr_timestamps(&self) -> bool {
+        self.receive_jr_timestamps
+    }
+
+    pub fn send_jr_timestamps(&self) -> bool {
+        self.send_jr_timestamps
+    }
+
+    /// The replies to an inbound message, in the order they should be sent.
+    ///
+    /// Messages other than discovery and configuration requests get no reply.
+    pub fn respond<B: Ump>(&mut self, message: &UmpStream<B>) -> Vec<UmpStream<Vec<u32>>> {
+        match message {
+            UmpStream::EndpointDiscovery(m) => self.respond_to_endpoint_discovery(m),
+            UmpStream::FunctionBlockDiscovery(m) => self.respond_to_function_block_discovery(m),
+            UmpStream::StreamConfigurationRequest(m) => {
+                self.configure(m);
+                std::vec![self.stream_configuration_notification().into()]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn respond_to_endpoint_discovery<B: Ump>(
+        &self,
+        discovery: &EndpointDiscovery<B>,
+    ) -> Vec<UmpStream<Vec<u32>>> {
+        let mut replies = Vec::new();
+        if discovery.request_endpoint_info() {
+            replies.push(self.endpoint_info().into());
+        }
+        if discovery.request_device_identity() {
+            replies.push(self.device_identity().into());
+        }
+        if discovery.request_endpoint_name() && !self.description.name.is_empty() {
+            let mut message = EndpointName::<Vec<u32>>::new();
+            message.set_name(&self.description.name);
+            replies.push(message.into());
+        }
+        if discovery.request_product_instance_id()
+            && !self.description.product_instance_id.is_empty()
+        {
+            let mut message = ProductInstanceId::<Vec<u32>>::new();
+            message.set_id(&self.description.product_instance_id);
+            replies.push(message.into());
+        }
+        if discovery.request_stream_configuration() {
+            replies.push(self.stream_configuration_notification().into());
+        }
+        replies
+    }
+
+    fn respond_to_function_block_discovery<B: Ump>(
+        &self,
+        discovery: &FunctionBlockDiscovery<B>,
+    ) -> Vec<UmpStream<Vec<u32>>> {
+        let mut replies = Vec::new();
+        let requested = discovery.function_block_number();
+        for (index, block) in self.description.function_blocks.iter().enumerate() {
+            if requested != ALL_FUNCTION_BLOCKS && usize::from(requested) != index {
+                continue;
+            }
+            if discovery.requesting_function_block_info() {
+                replies.push(function_block_info(index, block).into());
+            }
+            if discovery.requesting_function_block_name() && !block.name.is_empty() {
+                let mut message = FunctionBlockName::<Vec<u32>>::new();
+                message.set_name(&block.name);
+                message.set_function_block(index as u8);
+                replies.push(message.into());
+            }
+        }
+        replies
+    }
+
+    fn configure<B: Ump>(&mut self, request: &StreamConfigurationRequest<B>) {
+        let supported = match request.protocol() {
+            MIDI1_PROTOCOL => self.description.supports_midi1_protocol,
+            MIDI2_PROTOCOL => self.description.supports_midi2_protocol,
+            _ => false,
+        };
+        if supported {
+            self.protocol = request.protocol();
+        }
+        self.receive_jr_timestamps =
+            request.receive_jr_timestamps() && self.description.supports_receiving_jr_timestamps;
+        self.send_jr_timestamps =
+            request.send_jr_timestamps() && self.description.supports_sending_jr_timestamps;
+    }
+
+    fn endpoint_info(&self) -> EndpointInfo<Vec<u32>> {
+        let description = &self.description;
+        let mut message = EndpointInfo::<[u32; 4]>::new();
+        message.set_ump_version_major(description.ump_version_major);
+        message.set_ump_version_minor(description.ump_version_minor);
+        message.set_static_function_blocks(description.static_function_blocks);
+        message.set_supports_midi1_protocol(description.supports_midi1_protocol);
+        message.set_supports_midi2_protocol(description.supports_midi2_protocol);
+        message.set_supports_receiving_jr_timestamps(description.supports_receiving_jr_timestamps);
+        message.set_supports_sending_jr_timestamps(description.supports_sending_jr_timestamps);
+        message.set_number_of_function_blocks(u7::new(description.function_blocks.len() as u8));
+        message.rebuffer_into()
+    }
+
+    fn device_identity(&self) -> DeviceIdentity<Vec<u32>> {
+        let description = &self.description;
+        let mut message = DeviceIdentity::<[u32; 4]>::new();
+        message.set_device_manufacturer(description.device_manufacturer);
+        message.set_device_family(description.device_family);
+        message.set_device_family_model_number(description.device_family_model_number);
+        message.set_software_version(description.software_version);
+        message.rebuffer_into()
+    }
+
+    fn stream_configuration_notification(&self) -> StreamConfigurationNotification<Vec<u32>> {
+        let mut message = StreamConfigurationNotification::<[u32; 4]>::new();
+        message.set_protocol(self.protocol);
+        message.set_receive_jr_timestamps(self.receive_jr_timestamps);
+        message.set_send_jr_timestamps(self.send_jr_timestamps);
+        message.rebuffer_into()
+    }
+}
+
+fn function_block_info(index: usize, block: &FunctionBlock) -> FunctionBlockInfo<Vec<u32>> {
+    let mut message = FunctionBlockInfo::<[u32; 4]>::new();
+    message.set_active(block.active);
+    message.set_function_block_number(u7::new(index as u8));
+    message.set_first_group(block.first_group);
+    message.set_number_of_groups_spanned(block.number_of_groups_spanned);
+    message.set_midi_ci_version(block.midi_ci_version);
+    message.set_max_number_of_midi_ci_streams(block.max_number_of_midi_ci_streams);
+    message.set_ui_hint(block.ui_hint);
+    message.set_midi1_port(block.midi1_port);
+    message.set_direction(block.direction);
+    message.rebuffer_into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        traits::{Data, TryRebufferInto},
+        ump_stream::{Direction, UiHint},
+        ux::{u14, u4},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn description() -> EndpointDescription {
+        EndpointDescription {
+            name: "Synth".into(),
+            product_instance_id: "0001".into(),
+            device_manufacturer: [u7::new(0x1), u7::new(0x2), u7::new(0x3)],
+            device_family: u14::new(0x0101),
+            device_family_model_number: u14::new(0x0202),
+            software_version: [u7::new(0x4), u7::new(0x5), u7::new(0x6), u7::new(0x7)],
+            supports_midi1_protocol: true,
+            supports_midi2_protocol: true,
+            supports_receiving_jr_timestamps: true,
+            function_blocks: std::vec![
+                FunctionBlock {
+                    first_group: u4::new(0x0),
+                    number_of_groups_spanned: 2,
+                    ui_hint: UiHint::Receiver,
+                    direction: Direction::Input,
+                    name: "Piano".into(),
+                    ..Default::default()
+                },
+                FunctionBlock {
+                    first_group: u4::new(0x2),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn endpoint_discovery(filter: u32) -> UmpStream<[u32; 4]> {
+        UmpStream::try_from(&[0xF000_0101, filter, 0x0, 0x0][..])
+            .unwrap()
+            .try_rebuffer_into()
+            .unwrap()
+    }
+
+    fn function_block_discovery(block: u8, filter: u8) -> UmpStream<[u32; 4]> {
+        let mut message = FunctionBlockDiscovery::<[u32; 4]>::new();
+        message.set_function_block_number(block);
+        message.set_requesting_function_block_info(filter & 0b10 != 0);
+        message.set_requesting_function_block_name(filter & 0b01 != 0);
+        message.into()
+    }
+
+    #[test]
+    fn endpoint_info() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&endpoint_discovery(0b0_0001));
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].data(), &[0xF001_0101, 0x0200_0302],);
+    }
+
+    #[test]
+    fn device_identity() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&endpoint_discovery(0b0_0010));
+        assert_eq!(
+            replies[0].data(),
+            &[0xF002_0000, 0x0001_0203, 0x0102_0204, 0x0405_0607],
+        );
+    }
+
+    #[test]
+    fn all_endpoint_replies_in_order() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&endpoint_discovery(0b1_1111));
+        assert!(matches!(
+            replies.as_slice(),
+            [
+                UmpStream::EndpointInfo(_),
+                UmpStream::DeviceIdentity(_),
+                UmpStream::EndpointName(_),
+                UmpStream::ProductInstanceId(_),
+                UmpStream::StreamConfigurationNotification(_),
+            ]
+        ));
+    }
+
+    #[test]
+    fn empty_name_is_not_sent() {
+        let mut responder = EndpointResponder::new(EndpointDescription::default()).unwrap();
+        assert_eq!(
+            responder.respond(&endpoint_discovery(0b0_1100)),
+            std::vec![]
+        );
+    }
+
+    #[test]
+    fn names() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&endpoint_discovery(0b0_1100));
+        let [UmpStream::EndpointName(name), UmpStream::ProductInstanceId(id)] = replies.as_slice()
+        else {
+            panic!();
+        };
+        assert_eq!((name.name(), id.id()), ("Synth".into(), "0001".into()));
+    }
+
+    #[test]
+    fn all_function_blocks() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&function_block_discovery(0xFF, 0b11));
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0].data(), &[0xF011_8011, 0x0002_0000]);
+        let UmpStream::FunctionBlockName(name) = &replies[1] else {
+            panic!();
+        };
+        assert_eq!((name.function_block(), name.name()), (0, "Piano".into()));
+        assert_eq!(replies[2].data(), &[0xF011_8103, 0x0201_0000]);
+    }
+
+    #[test]
+    fn single_function_block_info() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&function_block_discovery(0x1, 0b10));
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].data(), &[0xF011_8103, 0x0201_0000]);
+    }
+
+    #[test]
+    fn missing_function_block() {
+        let replies = EndpointResponder::new(description())
+            .unwrap()
+            .respond(&function_block_discovery(0x5, 0b11));
+        assert_eq!(replies, std::vec![]);
+    }
+
+    #[test]
+    fn stream_configuration_request() {
+        let mut responder = EndpointResponder::new(description()).unwrap();
+        let mut request = StreamConfigurationRequest::<[u32; 4]>::new();
+        request.set_protocol(MIDI1_PROTOCOL);
+        request.set_receive_jr_timestamps(true);
+        request.set_send_jr_timestamps(true);
+        let replies = responder.respond(&request.into());
+        assert_eq!(responder.protocol(), MIDI1_PROTOCOL);
+        assert!(responder.receive_jr_timestamps());
+        assert!(!responder.send_jr_timestamps());
+        assert_eq!(replies.len(), 1);
+        assert_eq!(replies[0].data(), &[0xF006_0102]);
+    }
+
+    #[test]
+    fn unsupported_protocol_is_not_negotiated() {
+        let mut responder = EndpointResponder::new(EndpointDescription::default()).unwrap();
+        let mut request = StreamConfigurationRequest::<[u32; 4]>::new();
+        request.set_protocol(MIDI1_PROTOCOL);
+        let replies = responder.respond(&request.into());
+        assert_eq!(responder.protocol(), MIDI2_PROTOCOL);
+        assert_eq!(replies[0].data(), &[0xF006_0200]);
+    }
+
+    #[test]
+    fn too_many_function_blocks() {
+        let description = EndpointDescription {
+            function_blocks: std::vec![FunctionBlock::default(); 33],
+            ..Default::default()
+        };
+        assert_eq!(
+            EndpointResponder::new(description),
+            Err(Error::InvalidData(ERR_TOO_MANY_FUNCTION_BLOCKS)),
+        );
+    }
+
+    #[test]
+    fn other_messages_are_ignored() {
+        let mut responder = EndpointResponder::new(description()).unwrap();
+        let message: UmpStream<[u32; 4]> = crate::ump_stream::StartOfClip::<[u32; 4]>::new().into();
+        assert_eq!(responder.respond(&message), std::vec![]);
+    }
+}
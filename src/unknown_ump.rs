@@ -0,0 +1,279 @@
+use crate::{
+    buffer::{BufferDefault, BufferMut, BufferResize, BufferTryResize, Ump},
+    detail::BitOps,
+    traits::Data,
+    ux::u4,
+};
+
+// the message types are spelled out here since
+// their modules may be disabled
+const UTILITY_TYPE: u8 = 0x0;
+const CHANNEL_VOICE1_TYPE: u8 = 0x2;
+const CHANNEL_VOICE2_TYPE: u8 = 0x4;
+const UMP_STREAM_TYPE: u8 = 0xF;
+
+/// A ump message which the crate doesn't recognise.
+///
+/// This covers the reserved message types and unrecognised statuses within
+/// the known message types. The raw words are kept untouched so that the
+/// message can be forwarded. Messages of the types which may span several
+/// packets keep all of their packets.
+///
+/// ```rust
+/// use midi2::prelude::*;
+///
+/// // a packet of the reserved message type 0x6 followed by padding
+/// let message = UmpMessage::try_from(&[0x6A12_3456, 0x0][..]).unwrap();
+/// assert!(matches!(message, UmpMessage::Unknown(_)));
+/// assert_eq!(message.data(), &[0x6A12_3456]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownUmp<B: Ump>(B);
+
+impl<B: Ump> UnknownUmp<B> {
+    pub fn ump_type(&self) -> u4 {
+        self.0.buffer()[0].nibble(0)
+    }
+
+    /// The number of words in each packet of the message,
+    /// as set out for its message type by the MIDI 2.0 specification.
+    pub fn packet_size(&self) -> usize {
+        crate::ump_packet_size(self.ump_type())
+    }
+
+    fn has_group(&self) -> bool {
+        !matches!(u8::from(self.ump_type()), UTILITY_TYPE | UMP_STREAM_TYPE)
+    }
+
+    fn has_channel(&self) -> bool {
+        matches!(
+            u8::from(self.ump_type()),
+            CHANNEL_VOICE1_TYPE | CHANNEL_VOICE2_TYPE
+        )
+    }
+}
+
+impl<'a> core::convert::TryFrom<&'a [u32]> for UnknownUmp<&'a [u32]> {
+    type Error = crate::error::Error;
+    fn try_from(buffer: &'a [u32]) -> Result<Self, Self::Error> {
+        if buffer.is_empty() {
+            return Err(crate::error::Error::InvalidData("Slice is too short"));
+        };
+        crate::stream::message_size(buffer).map_err(|(_, error)| error)?;
+        Ok(UnknownUmp(buffer))
+    }
+}
+
+impl<'a> core::convert::TryFrom<&'a mut [u32]> for UnknownUmp<&'a mut [u32]> {
+    type Error = crate::error::Error;
+    fn try_from(buffer: &'a mut [u32]) -> Result<Self, Self::Error> {
+        UnknownUmp::try_from(&*buffer)?;
+        Ok(UnknownUmp(buffer))
+    }
+}
+
+impl<B: Ump> Data<B> for UnknownUmp<B> {
+    fn data(&self) -> &[u32] {
+        let buffer = self.0.buffer();
+        // the buffer is validated on construction
+        let Ok(size) = crate::stream::message_size(buffer) else {
+            unreachable!();
+        };
+        &buffer[..size]
+    }
+}
+
+/// Utility and ump stream messages have no group. The group of the
+/// other message types is read from the position it takes in the
+/// groupable message types.
+impl<B: Ump> crate::traits::OptionalGrouped<B> for UnknownUmp<B> {
+    fn group(&self) -> Option<u4> {
+        self.has_group().then(|| self.0.buffer()[0].nibble(1))
+    }
+    fn set_group(&mut self, group: u4)
+    where
+        B: BufferMut,
+    {
+        if !self.has_group() {
+            return;
+        }
+        let size = self.data().len();
+        let packet_size = self.packet_size();
+        for packet in self.0.buffer_mut()[..size].chunks_exact_mut(packet_size) {
+            packet[0].set_nibble(1, group);
+        }
+    }
+}
+
+/// Only the channel voice message types have a channel.
+impl<B: Ump> crate::traits::OptionalChanneled<B> for UnknownUmp<B> {
+    fn channel(&self) -> Option<u4> {
+        self.has_channel().then(|| self.0.buffer()[0].nibble(3))
+    }
+    fn set_channel(&mut self, channel: u4)
+    where
+        B: BufferMut,
+    {
+        if self.has_channel() {
+            self.0.buffer_mut()[0].set_nibble(3, channel);
+        }
+    }
+}
+
+impl<A: Ump, B: Ump + BufferMut + BufferDefault + BufferResize>
+    crate::traits::RebufferFrom<UnknownUmp<A>> for UnknownUmp<B>
+{
+    fn rebuffer_from(other: UnknownUmp<A>) -> Self {
+        let mut buffer = <B as BufferDefault>::default();
+        let message_size = other.data().len();
+        buffer.resize(message_size);
+        buffer.buffer_mut()[..message_size].copy_from_slice(other.data());
+        UnknownUmp(buffer)
+    }
+}
+
+impl<A: Ump, B: Ump + BufferMut + BufferDefault + BufferTryResize>
+    crate::traits::TryRebufferFrom<UnknownUmp<A>> for UnknownUmp<B>
+{
+    fn try_rebuffer_from(
+        other: UnknownUmp<A>,
+    ) -> core::result::Result<Self, crate::error::BufferOverflow> {
+        let mut buffer = <B as BufferDefault>::default();
+        let message_size = other.data().len();
+        buffer.try_resize(message_size)?;
+        buffer.buffer_mut()[..message_size].copy_from_slice(other.data());
+        Ok(UnknownUmp(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{OptionalChanneled, OptionalGrouped, RebufferInto, TryRebufferInto};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn try_from_reserved_type() {
+        assert_eq!(
+            UnknownUmp::try_from(&[0xB123_4567, 0x89AB_CDEF, 0x0123_4567, 0x0][..]),
+            Ok(UnknownUmp(
+                &[0xB123_4567, 0x89AB_CDEF, 0x0123_4567, 0x0][..]
+            )),
+        );
+    }
+
+    #[test]
+    fn try_from_truncated_packet() {
+        assert!(UnknownUmp::try_from(&[0xB123_4567, 0x89AB_CDEF][..]).is_err());
+    }
+
+    #[test]
+    fn try_from_empty() {
+        assert!(UnknownUmp::try_from(&[][..]).is_err());
+    }
+
+    #[test]
+    fn data() {
+        assert_eq!(
+            UnknownUmp::try_from(&[0xB123_4567, 0x89AB_CDEF, 0x0123_4567, 0x0][..])
+                .unwrap()
+                .data(),
+            &[0xB123_4567, 0x89AB_CDEF, 0x0123_4567],
+        );
+    }
+
+    #[test]
+    fn data_multi_packet() {
+        let buffer = [
+            0xF400_0000,
+            0x0,
+            0x0,
+            0x0,
+            0xFC00_0000,
+            0x0,
+            0x0,
+            0x0,
+            0x1234_5678,
+        ];
+        assert_eq!(
+            UnknownUmp::try_from(&buffer[..]).unwrap().data(),
+            &buffer[..8]
+        );
+    }
+
+    #[test]
+    fn packet_size() {
+        assert_eq!(
+            UnknownUmp::try_from(&[0x8123_4567, 0x0][..])
+                .unwrap()
+                .packet_size(),
+            2,
+        );
+    }
+
+    #[test]
+    fn set_group() {
+        let buffer = [0x5180_0000, 0x0, 0x0, 0x0];
+        let mut message: UnknownUmp<std::vec::Vec<u32>> =
+            UnknownUmp::try_from(&buffer[..]).unwrap().rebuffer_into();
+        message.set_group(u4::new(0xA));
+        assert_eq!(message.group(), Some(u4::new(0xA)));
+        assert_eq!(message.data(), &[0x5A80_0000, 0x0, 0x0, 0x0]);
+    }
+
+    #[test]
+    fn ungrouped_types() {
+        for buffer in [[0x0F12_3456, 0x0, 0x0, 0x0], [0xF3FF_0000, 0x0, 0x0, 0x0]] {
+            let mut message: UnknownUmp<std::vec::Vec<u32>> =
+                UnknownUmp::try_from(&buffer[..]).unwrap().rebuffer_into();
+            assert_eq!(message.group(), None);
+            message.set_group(u4::new(0xA));
+            message.set_channel(u4::new(0xA));
+            assert_eq!(message.data(), &buffer[..message.packet_size()]);
+        }
+    }
+
+    #[test]
+    fn channel() {
+        let buffer = [0x4B73_0000, 0x0];
+        let mut message: UnknownUmp<std::vec::Vec<u32>> =
+            UnknownUmp::try_from(&buffer[..]).unwrap().rebuffer_into();
+        assert_eq!(message.channel(), Some(u4::new(0x3)));
+        message.set_channel(u4::new(0x9));
+        assert_eq!(message.data(), &[0x4B79_0000, 0x0]);
+    }
+
+    #[test]
+    fn no_channel() {
+        assert_eq!(
+            UnknownUmp::try_from(&[0x6A12_3456][..]).unwrap().channel(),
+            None
+        );
+    }
+
+    #[test]
+    fn rebuffer_into() {
+        let message: UnknownUmp<std::vec::Vec<u32>> = UnknownUmp::try_from(&[0x6A12_3456, 0x0][..])
+            .unwrap()
+            .rebuffer_into();
+        assert_eq!(message, UnknownUmp(std::vec![0x6A12_3456]));
+    }
+
+    #[test]
+    fn try_rebuffer_into() {
+        let message: UnknownUmp<[u32; 4]> = UnknownUmp::try_from(&[0x6A12_3456][..])
+            .unwrap()
+            .try_rebuffer_into()
+            .unwrap();
+        assert_eq!(message.data(), &[0x6A12_3456]);
+    }
+
+    #[test]
+    fn try_rebuffer_into_overflow() {
+        let message: Result<UnknownUmp<[u32; 2]>, _> =
+            UnknownUmp::try_from(&[0xE123_4567, 0x0, 0x0, 0x0][..])
+                .unwrap()
+                .try_rebuffer_into();
+        assert_eq!(message, Err(crate::error::BufferOverflow));
+    }
+}
@@ -167,6 +167,7 @@ impl<B: crate::buffer::Ump + crate::buffer::BufferMut> crate::detail::property::
     midi2_proc::Data,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
+    midi2_proc::TryFromMutSlice,
     Clone,
     Debug,
     PartialEq,
@@ -184,7 +185,7 @@ pub enum Utility<B: crate::buffer::Ump> {
 impl<'a> core::convert::TryFrom<&'a [u32]> for Utility<&'a [u32]> {
     type Error = crate::error::Error;
     fn try_from(buffer: &'a [u32]) -> Result<Self, Self::Error> {
-        if buffer.len() < 1 {
+        if buffer.is_empty() {
             return Err(crate::error::Error::InvalidData("Slice is too short"));
         };
         Ok(match status(buffer) {